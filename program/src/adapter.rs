@@ -0,0 +1,817 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use serum_dex::matching::Side;
+
+use spl_token::state::Account as TokenAccount;
+
+use crate::{
+  instruction::Market,
+  error::ArbitrageError,
+  event::ArbitrageEvent,
+  math::{balance_decrease, balance_increase},
+  processor::{Processor, find_side},
+  serum_book::SerumBookReader,
+  validation,
+};
+
+/// amounts actually moved by a swap, measured on the user token accounts
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub struct SwapResult {
+  pub amount_in: u64,
+  pub amount_out: u64,
+  pub source_mint: Pubkey,
+  pub destination_mint: Pubkey,
+  /// balance of the user source account before the swap
+  pub source_balance_before: u64,
+  /// balance of the user destination account after the swap
+  pub destination_balance_after: u64,
+}
+
+/// A venue the arbitrage program can swap on.
+///
+/// `parse` takes the venue accounts in the order the processor expects them,
+/// `validate` checks them before any CPI, and `swap` wraps `invoke_swap` with the
+/// balance measurement every strategy used to do by hand.
+pub trait DexAdapter<'a, 'b>: Sized {
+  fn parse<I: Iterator<Item = &'a AccountInfo<'b>>>(account_info_iter: &mut I, side: u8) -> Result<Self, ProgramError>;
+
+  /// user token account debited by the swap
+  fn user_source(&self) -> &'a AccountInfo<'b>;
+
+  /// user token account credited by the swap
+  fn user_destination(&self) -> &'a AccountInfo<'b>;
+
+  /// owner of the user token accounts, must sign
+  fn user_owner(&self) -> &'a AccountInfo<'b>;
+
+  /// program owning the pool state and the pool state account itself
+  fn pool(&self) -> (&'a AccountInfo<'b>, &'a AccountInfo<'b>);
+
+  /// the venue's program, the only one the adapter invokes
+  fn program_id() -> Pubkey;
+
+  fn invoke_swap(&self, amount_in: u64, minimum_amount_out: u64) -> ProgramResult;
+
+  fn validate(&self) -> ProgramResult {
+    validation::signer(self.user_owner())?;
+    let (program_acc, market_acc) = self.pool();
+    validation::program(program_acc, &Self::program_id())?;
+    if market_acc.owner != program_acc.key {
+      return Err(ArbitrageError::InvalidOwner.into());
+    }
+    if self.user_source().key == self.user_destination().key {
+      return Err(ArbitrageError::InvalidCall.into());
+    }
+    validation::user_token_account(self.user_source(), self.user_owner())?;
+    validation::user_token_account(self.user_destination(), self.user_owner())?;
+    Ok(())
+  }
+
+  fn swap(&self, amount_in: u64, minimum_amount_out: u64) -> Result<SwapResult, ProgramError> {
+    let user_source_acc_before = TokenAccount::unpack(&self.user_source().try_borrow_data()?)?;
+    let user_dst_acc_before = TokenAccount::unpack(&self.user_destination().try_borrow_data()?)?;
+    let user_source_acc_balance_before = user_source_acc_before.amount;
+    let user_dst_acc_balance_before = user_dst_acc_before.amount;
+    msg!(
+      "source account balance: {}, destination account balance: {}",
+      user_source_acc_balance_before, user_dst_acc_balance_before,
+    );
+
+    self.invoke_swap(amount_in, minimum_amount_out)?;
+
+    let user_source_acc_balance_after = token_balance(self.user_source())?;
+    let user_dst_acc_balance_after = token_balance(self.user_destination())?;
+    msg!(
+      "source account balance: {}, destination account balance: {}",
+      user_source_acc_balance_after, user_dst_acc_balance_after,
+    );
+
+    let result = SwapResult {
+      amount_in: balance_decrease(user_source_acc_balance_before, user_source_acc_balance_after)?,
+      amount_out: balance_increase(user_dst_acc_balance_before, user_dst_acc_balance_after)?,
+      source_mint: user_source_acc_before.mint,
+      destination_mint: user_dst_acc_before.mint,
+      source_balance_before: user_source_acc_balance_before,
+      destination_balance_after: user_dst_acc_balance_after,
+    };
+    if result.amount_out < minimum_amount_out {
+      return Err(ArbitrageError::SlippageExceeded.into());
+    }
+    Ok(result)
+  }
+}
+
+pub fn token_balance(token_acc: &AccountInfo) -> Result<u64, ProgramError> {
+  Ok(TokenAccount::unpack(&token_acc.try_borrow_data()?)?.amount)
+}
+
+/// logs `error` with the strategy, hop and venue it surfaced at and returns
+/// it unchanged, so the client still sees the venue's own code.
+///
+/// A venue failing inside its own program aborts the transaction before the
+/// invoke returns, the runtime's `Program <id> failed` line carries that code.
+/// On paths and routes the hop is then the first without a `HopExecuted` event.
+pub fn venue_error(strategy: u8, hop: u8, venue: Market, error: ProgramError) -> ProgramError {
+  msg!("{:?} failed at hop {}: {}", venue, hop, error);
+  ArbitrageEvent::VenueFailed {
+    strategy: strategy,
+    hop: hop,
+    venue: venue as u8,
+    error: u64::from(error.clone()),
+  }.emit();
+  error
+}
+
+/// parse, validate and execute a swap on `market`
+pub fn swap_with_market<'a, 'b, I: Iterator<Item = &'a AccountInfo<'b>>>(
+  market: Market,
+  side: u8,
+  account_info_iter: &mut I,
+  amount_in: u64,
+  minimum_amount_out: u64,
+) -> Result<SwapResult, ProgramError> {
+  match market {
+    Market::Orca => {
+      msg!("orca swap");
+      execute::<OrcaAdapter, I>(account_info_iter, side, amount_in, minimum_amount_out)
+    },
+    Market::Saber => {
+      msg!("saber swap");
+      execute::<SaberAdapter, I>(account_info_iter, side, amount_in, minimum_amount_out)
+    },
+    Market::Serum => {
+      msg!("serum swap");
+      execute::<SerumAdapter, I>(account_info_iter, side, amount_in, minimum_amount_out)
+    },
+    Market::Raydium => {
+      msg!("raydium swap");
+      execute::<RaydiumAdapter, I>(account_info_iter, side, amount_in, minimum_amount_out)
+    },
+    Market::Mercurial_2pool => {
+      msg!("mercurial swap");
+      execute::<MercurialAdapter<2>, I>(account_info_iter, side, amount_in, minimum_amount_out)
+    },
+    Market::Mercurial_3pool => {
+      msg!("mercurial swap");
+      execute::<MercurialAdapter<3>, I>(account_info_iter, side, amount_in, minimum_amount_out)
+    },
+    Market::Mercurial_4pool => {
+      msg!("mercurial swap");
+      execute::<MercurialAdapter<4>, I>(account_info_iter, side, amount_in, minimum_amount_out)
+    },
+    Market::Whirl => {
+      msg!("whirl swap");
+      execute::<WhirlAdapter, I>(account_info_iter, side, amount_in, minimum_amount_out)
+    },
+    Market::Crema => {
+      msg!("crema swap");
+      execute::<CremaAdapter, I>(account_info_iter, side, amount_in, minimum_amount_out)
+    },
+  }
+}
+
+fn execute<'a, 'b, A: DexAdapter<'a, 'b>, I: Iterator<Item = &'a AccountInfo<'b>>>(
+  account_info_iter: &mut I,
+  side: u8,
+  amount_in: u64,
+  minimum_amount_out: u64,
+) -> Result<SwapResult, ProgramError> {
+  let adapter = A::parse(account_info_iter, side)?;
+  adapter.validate()?;
+  adapter.swap(amount_in, minimum_amount_out)
+}
+
+/// spl-token-swap pool
+pub struct OrcaAdapter<'a, 'b> {
+  pub program_acc: &'a AccountInfo<'b>,
+  pub market_acc: &'a AccountInfo<'b>,
+  pub market_auth_acc: &'a AccountInfo<'b>,
+  pub user_owner_acc: &'a AccountInfo<'b>,
+  pub user_source_acc: &'a AccountInfo<'b>,
+  pub swap_source_acc: &'a AccountInfo<'b>,
+  pub swap_dst_acc: &'a AccountInfo<'b>,
+  pub user_dst_acc: &'a AccountInfo<'b>,
+  pub pool_mint_acc: &'a AccountInfo<'b>,
+  pub fee_acc: &'a AccountInfo<'b>,
+  pub spl_token_program_acc: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> DexAdapter<'a, 'b> for OrcaAdapter<'a, 'b> {
+  fn parse<I: Iterator<Item = &'a AccountInfo<'b>>>(account_info_iter: &mut I, _side: u8) -> Result<Self, ProgramError> {
+    Ok(
+      OrcaAdapter {
+        program_acc: next_account_info(account_info_iter)?,
+        market_acc: next_account_info(account_info_iter)?,
+        market_auth_acc: next_account_info(account_info_iter)?,
+        user_owner_acc: next_account_info(account_info_iter)?,
+        user_source_acc: next_account_info(account_info_iter)?,
+        swap_source_acc: next_account_info(account_info_iter)?,
+        swap_dst_acc: next_account_info(account_info_iter)?,
+        user_dst_acc: next_account_info(account_info_iter)?,
+        pool_mint_acc: next_account_info(account_info_iter)?,
+        fee_acc: next_account_info(account_info_iter)?,
+        spl_token_program_acc: next_account_info(account_info_iter)?,
+      }
+    )
+  }
+
+  fn user_source(&self) -> &'a AccountInfo<'b> {
+    self.user_source_acc
+  }
+
+  fn user_destination(&self) -> &'a AccountInfo<'b> {
+    self.user_dst_acc
+  }
+
+  fn user_owner(&self) -> &'a AccountInfo<'b> {
+    self.user_owner_acc
+  }
+
+  fn pool(&self) -> (&'a AccountInfo<'b>, &'a AccountInfo<'b>) {
+    (self.program_acc, self.market_acc)
+  }
+
+  fn program_id() -> Pubkey {
+    validation::orca::id()
+  }
+
+  fn invoke_swap(&self, amount_in: u64, minimum_amount_out: u64) -> ProgramResult {
+    Processor::orca_swap(
+      self.program_acc.key,
+      self.market_acc,
+      self.market_auth_acc,
+      self.user_owner_acc,
+      self.user_source_acc,
+      self.swap_source_acc,
+      self.swap_dst_acc,
+      self.user_dst_acc,
+      self.pool_mint_acc,
+      self.fee_acc,
+      self.spl_token_program_acc,
+      amount_in,
+      minimum_amount_out,
+    )
+  }
+}
+
+/// saber stable swap pool
+pub struct SaberAdapter<'a, 'b> {
+  pub program_acc: &'a AccountInfo<'b>,
+  pub market_acc: &'a AccountInfo<'b>,
+  pub market_auth_acc: &'a AccountInfo<'b>,
+  pub user_owner_acc: &'a AccountInfo<'b>,
+  pub user_source_acc: &'a AccountInfo<'b>,
+  pub swap_source_acc: &'a AccountInfo<'b>,
+  pub swap_dst_acc: &'a AccountInfo<'b>,
+  pub user_dst_acc: &'a AccountInfo<'b>,
+  pub pool_fee_acc: &'a AccountInfo<'b>,
+  pub spl_token_program_acc: &'a AccountInfo<'b>,
+  pub sys_clock: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> DexAdapter<'a, 'b> for SaberAdapter<'a, 'b> {
+  fn parse<I: Iterator<Item = &'a AccountInfo<'b>>>(account_info_iter: &mut I, _side: u8) -> Result<Self, ProgramError> {
+    Ok(
+      SaberAdapter {
+        program_acc: next_account_info(account_info_iter)?,
+        market_acc: next_account_info(account_info_iter)?,
+        market_auth_acc: next_account_info(account_info_iter)?,
+        user_owner_acc: next_account_info(account_info_iter)?,
+        user_source_acc: next_account_info(account_info_iter)?,
+        swap_source_acc: next_account_info(account_info_iter)?,
+        swap_dst_acc: next_account_info(account_info_iter)?,
+        user_dst_acc: next_account_info(account_info_iter)?,
+        pool_fee_acc: next_account_info(account_info_iter)?,
+        spl_token_program_acc: next_account_info(account_info_iter)?,
+        sys_clock: next_account_info(account_info_iter)?,
+      }
+    )
+  }
+
+  fn user_source(&self) -> &'a AccountInfo<'b> {
+    self.user_source_acc
+  }
+
+  fn user_destination(&self) -> &'a AccountInfo<'b> {
+    self.user_dst_acc
+  }
+
+  fn user_owner(&self) -> &'a AccountInfo<'b> {
+    self.user_owner_acc
+  }
+
+  fn pool(&self) -> (&'a AccountInfo<'b>, &'a AccountInfo<'b>) {
+    (self.program_acc, self.market_acc)
+  }
+
+  fn program_id() -> Pubkey {
+    validation::saber::id()
+  }
+
+  fn invoke_swap(&self, amount_in: u64, minimum_amount_out: u64) -> ProgramResult {
+    Processor::saber_swap(
+      self.program_acc.key,
+      self.market_acc,
+      self.market_auth_acc,
+      self.user_owner_acc,
+      self.user_source_acc,
+      self.swap_source_acc,
+      self.swap_dst_acc,
+      self.user_dst_acc,
+      self.pool_fee_acc,
+      self.spl_token_program_acc,
+      self.sys_clock,
+      amount_in,
+      minimum_amount_out,
+    )
+  }
+}
+
+/// serum order book, side 0 buys base with quote, side 1 sells base for quote
+pub struct SerumAdapter<'a, 'b> {
+  pub program_acc: &'a AccountInfo<'b>,
+  pub market_acc: &'a AccountInfo<'b>,
+  pub open_orders_acc: &'a AccountInfo<'b>,
+  pub request_queue_acc: &'a AccountInfo<'b>,
+  pub event_queue_acc: &'a AccountInfo<'b>,
+  pub bids_acc: &'a AccountInfo<'b>,
+  pub asks_acc: &'a AccountInfo<'b>,
+  pub base_vault_acc: &'a AccountInfo<'b>,
+  pub quote_vault_acc: &'a AccountInfo<'b>,
+  pub vault_signer_acc: &'a AccountInfo<'b>,
+  pub user_owner_acc: &'a AccountInfo<'b>,
+  pub user_source_acc: &'a AccountInfo<'b>,
+  pub user_dst_acc: &'a AccountInfo<'b>,
+  pub sys_rent_acc: &'a AccountInfo<'b>,
+  pub spl_token_program_acc: &'a AccountInfo<'b>,
+  pub side: Side,
+}
+
+impl<'a, 'b> DexAdapter<'a, 'b> for SerumAdapter<'a, 'b> {
+  fn parse<I: Iterator<Item = &'a AccountInfo<'b>>>(account_info_iter: &mut I, side: u8) -> Result<Self, ProgramError> {
+    Ok(
+      SerumAdapter {
+        program_acc: next_account_info(account_info_iter)?,
+        market_acc: next_account_info(account_info_iter)?,
+        open_orders_acc: next_account_info(account_info_iter)?,
+        request_queue_acc: next_account_info(account_info_iter)?,
+        event_queue_acc: next_account_info(account_info_iter)?,
+        bids_acc: next_account_info(account_info_iter)?,
+        asks_acc: next_account_info(account_info_iter)?,
+        base_vault_acc: next_account_info(account_info_iter)?,
+        quote_vault_acc: next_account_info(account_info_iter)?,
+        vault_signer_acc: next_account_info(account_info_iter)?,
+        user_owner_acc: next_account_info(account_info_iter)?,
+        user_source_acc: next_account_info(account_info_iter)?,
+        user_dst_acc: next_account_info(account_info_iter)?,
+        sys_rent_acc: next_account_info(account_info_iter)?,
+        spl_token_program_acc: next_account_info(account_info_iter)?,
+        side: find_side(side).ok_or(ArbitrageError::InvalidInstruction)?,
+      }
+    )
+  }
+
+  fn user_source(&self) -> &'a AccountInfo<'b> {
+    self.user_source_acc
+  }
+
+  fn user_destination(&self) -> &'a AccountInfo<'b> {
+    self.user_dst_acc
+  }
+
+  fn user_owner(&self) -> &'a AccountInfo<'b> {
+    self.user_owner_acc
+  }
+
+  fn pool(&self) -> (&'a AccountInfo<'b>, &'a AccountInfo<'b>) {
+    (self.program_acc, self.market_acc)
+  }
+
+  fn program_id() -> Pubkey {
+    validation::serum::id()
+  }
+
+  // serum has no minimum out on new order, the measured output is checked by `swap`
+  fn invoke_swap(&self, amount_in: u64, _minimum_amount_out: u64) -> ProgramResult {
+    match self.side {
+      Side::Bid => {
+        // quote -> base
+        Processor::serum_swap(
+          self.program_acc.key,
+          self.market_acc,
+          self.open_orders_acc,
+          self.request_queue_acc,
+          self.event_queue_acc,
+          self.bids_acc,
+          self.asks_acc,
+          self.user_source_acc,
+          self.base_vault_acc,
+          self.quote_vault_acc,
+          self.user_dst_acc,
+          self.user_source_acc,
+          self.vault_signer_acc,
+          self.user_owner_acc,
+          self.spl_token_program_acc,
+          self.sys_rent_acc,
+          Side::Bid,
+          u64::MAX,
+          u64::MAX,
+          amount_in,
+        )
+      },
+      Side::Ask => {
+        // base -> quote
+        let serum_book = SerumBookReader::load(self.program_acc.key, self.market_acc, self.bids_acc, self.asks_acc)?;
+        let max_base_qty = amount_in / serum_book.coin_lot_size;
+        if max_base_qty == 0 {
+          return Err(ArbitrageError::AmountBelowLotSize.into());
+        }
+        Processor::serum_swap(
+          self.program_acc.key,
+          self.market_acc,
+          self.open_orders_acc,
+          self.request_queue_acc,
+          self.event_queue_acc,
+          self.bids_acc,
+          self.asks_acc,
+          self.user_source_acc,
+          self.base_vault_acc,
+          self.quote_vault_acc,
+          self.user_source_acc,
+          self.user_dst_acc,
+          self.vault_signer_acc,
+          self.user_owner_acc,
+          self.spl_token_program_acc,
+          self.sys_rent_acc,
+          Side::Ask,
+          1,
+          max_base_qty,
+          u64::MAX,
+        )
+      },
+    }
+  }
+}
+
+/// raydium amm v4
+pub struct RaydiumAdapter<'a, 'b> {
+  pub program_acc: &'a AccountInfo<'b>,
+  pub market_acc: &'a AccountInfo<'b>,
+  pub market_auth_acc: &'a AccountInfo<'b>,
+  pub open_orders_acc: &'a AccountInfo<'b>,
+  pub target_orders_acc: &'a AccountInfo<'b>,
+  pub coin_vault_acc: &'a AccountInfo<'b>,
+  pub pc_vault_acc: &'a AccountInfo<'b>,
+  pub serum_program_acc: &'a AccountInfo<'b>,
+  pub serum_market_acc: &'a AccountInfo<'b>,
+  pub serum_request_queue_acc: &'a AccountInfo<'b>,
+  pub serum_event_queue_acc: &'a AccountInfo<'b>,
+  pub serum_bids_acc: &'a AccountInfo<'b>,
+  pub serum_asks_acc: &'a AccountInfo<'b>,
+  pub serum_base_vault_acc: &'a AccountInfo<'b>,
+  pub serum_quote_vault_acc: &'a AccountInfo<'b>,
+  pub serum_vault_signer_acc: &'a AccountInfo<'b>,
+  pub user_owner_acc: &'a AccountInfo<'b>,
+  pub user_source_acc: &'a AccountInfo<'b>,
+  pub user_dst_acc: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> DexAdapter<'a, 'b> for RaydiumAdapter<'a, 'b> {
+  fn parse<I: Iterator<Item = &'a AccountInfo<'b>>>(account_info_iter: &mut I, _side: u8) -> Result<Self, ProgramError> {
+    Ok(
+      RaydiumAdapter {
+        program_acc: next_account_info(account_info_iter)?,
+        market_acc: next_account_info(account_info_iter)?,
+        market_auth_acc: next_account_info(account_info_iter)?,
+        open_orders_acc: next_account_info(account_info_iter)?,
+        target_orders_acc: next_account_info(account_info_iter)?,
+        coin_vault_acc: next_account_info(account_info_iter)?,
+        pc_vault_acc: next_account_info(account_info_iter)?,
+        serum_program_acc: next_account_info(account_info_iter)?,
+        serum_market_acc: next_account_info(account_info_iter)?,
+        serum_request_queue_acc: next_account_info(account_info_iter)?,
+        serum_event_queue_acc: next_account_info(account_info_iter)?,
+        serum_bids_acc: next_account_info(account_info_iter)?,
+        serum_asks_acc: next_account_info(account_info_iter)?,
+        serum_base_vault_acc: next_account_info(account_info_iter)?,
+        serum_quote_vault_acc: next_account_info(account_info_iter)?,
+        serum_vault_signer_acc: next_account_info(account_info_iter)?,
+        user_owner_acc: next_account_info(account_info_iter)?,
+        user_source_acc: next_account_info(account_info_iter)?,
+        user_dst_acc: next_account_info(account_info_iter)?,
+      }
+    )
+  }
+
+  fn user_source(&self) -> &'a AccountInfo<'b> {
+    self.user_source_acc
+  }
+
+  fn user_destination(&self) -> &'a AccountInfo<'b> {
+    self.user_dst_acc
+  }
+
+  fn user_owner(&self) -> &'a AccountInfo<'b> {
+    self.user_owner_acc
+  }
+
+  fn pool(&self) -> (&'a AccountInfo<'b>, &'a AccountInfo<'b>) {
+    (self.program_acc, self.market_acc)
+  }
+
+  fn program_id() -> Pubkey {
+    validation::raydium::id()
+  }
+
+  fn invoke_swap(&self, amount_in: u64, minimum_amount_out: u64) -> ProgramResult {
+    Processor::raydium_swap(
+      self.program_acc.key,
+      self.market_acc,
+      self.market_auth_acc,
+      self.open_orders_acc,
+      self.target_orders_acc,
+      self.coin_vault_acc,
+      self.pc_vault_acc,
+      self.serum_program_acc.key,
+      self.serum_market_acc,
+      self.serum_bids_acc,
+      self.serum_asks_acc,
+      self.serum_event_queue_acc,
+      self.serum_base_vault_acc,
+      self.serum_quote_vault_acc,
+      self.serum_vault_signer_acc,
+      self.user_source_acc,
+      self.user_dst_acc,
+      self.user_owner_acc,
+      amount_in,
+      minimum_amount_out,
+    )
+  }
+}
+
+/// mercurial stable swap pool with `N` token vaults
+pub struct MercurialAdapter<'a, 'b, const N: usize> {
+  pub program_acc: &'a AccountInfo<'b>,
+  pub market_acc: &'a AccountInfo<'b>,
+  pub market_auth_acc: &'a AccountInfo<'b>,
+  pub user_owner_acc: &'a AccountInfo<'b>,
+  pub user_source_acc: &'a AccountInfo<'b>,
+  pub swap_accs: Vec<&'a AccountInfo<'b>>,
+  pub user_dst_acc: &'a AccountInfo<'b>,
+  pub spl_token_program_acc: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b, const N: usize> DexAdapter<'a, 'b> for MercurialAdapter<'a, 'b, N> {
+  fn parse<I: Iterator<Item = &'a AccountInfo<'b>>>(account_info_iter: &mut I, _side: u8) -> Result<Self, ProgramError> {
+    let program_acc = next_account_info(account_info_iter)?;
+    let market_acc = next_account_info(account_info_iter)?;
+    let market_auth_acc = next_account_info(account_info_iter)?;
+    let user_owner_acc = next_account_info(account_info_iter)?;
+    let user_source_acc = next_account_info(account_info_iter)?;
+    let mut swap_accs = Vec::with_capacity(N);
+    for _ in 0..N {
+      swap_accs.push(next_account_info(account_info_iter)?);
+    }
+    Ok(
+      MercurialAdapter {
+        program_acc: program_acc,
+        market_acc: market_acc,
+        market_auth_acc: market_auth_acc,
+        user_owner_acc: user_owner_acc,
+        user_source_acc: user_source_acc,
+        swap_accs: swap_accs,
+        user_dst_acc: next_account_info(account_info_iter)?,
+        spl_token_program_acc: next_account_info(account_info_iter)?,
+      }
+    )
+  }
+
+  fn user_source(&self) -> &'a AccountInfo<'b> {
+    self.user_source_acc
+  }
+
+  fn user_destination(&self) -> &'a AccountInfo<'b> {
+    self.user_dst_acc
+  }
+
+  fn user_owner(&self) -> &'a AccountInfo<'b> {
+    self.user_owner_acc
+  }
+
+  fn pool(&self) -> (&'a AccountInfo<'b>, &'a AccountInfo<'b>) {
+    (self.program_acc, self.market_acc)
+  }
+
+  fn program_id() -> Pubkey {
+    validation::mercurial::id()
+  }
+
+  fn invoke_swap(&self, amount_in: u64, minimum_amount_out: u64) -> ProgramResult {
+    match self.swap_accs[..] {
+      [swap_acc1, swap_acc2] => {
+        Processor::mercurial_swap_2pool(
+          self.program_acc.key,
+          self.market_acc,
+          self.market_auth_acc,
+          self.user_owner_acc,
+          swap_acc1,
+          swap_acc2,
+          self.user_source_acc,
+          self.user_dst_acc,
+          self.spl_token_program_acc,
+          amount_in,
+          minimum_amount_out,
+        )
+      },
+      [swap_acc1, swap_acc2, swap_acc3] => {
+        Processor::mercurial_swap_3pool(
+          self.program_acc.key,
+          self.market_acc,
+          self.market_auth_acc,
+          self.user_owner_acc,
+          swap_acc1,
+          swap_acc2,
+          swap_acc3,
+          self.user_source_acc,
+          self.user_dst_acc,
+          self.spl_token_program_acc,
+          amount_in,
+          minimum_amount_out,
+        )
+      },
+      [swap_acc1, swap_acc2, swap_acc3, swap_acc4] => {
+        Processor::mercurial_swap_4pool(
+          self.program_acc.key,
+          self.market_acc,
+          self.market_auth_acc,
+          self.user_owner_acc,
+          swap_acc1,
+          swap_acc2,
+          swap_acc3,
+          swap_acc4,
+          self.user_source_acc,
+          self.user_dst_acc,
+          self.spl_token_program_acc,
+          amount_in,
+          minimum_amount_out,
+        )
+      },
+      _ => Err(ArbitrageError::InvalidInstruction.into()),
+    }
+  }
+}
+
+/// orca whirlpool, side 1 swaps token a for token b, side 0 token b for token a
+pub struct WhirlAdapter<'a, 'b> {
+  pub program_acc: &'a AccountInfo<'b>,
+  pub market_acc: &'a AccountInfo<'b>,
+  pub user_owner_acc: &'a AccountInfo<'b>,
+  pub user_a_acc: &'a AccountInfo<'b>,
+  pub vault_a_acc: &'a AccountInfo<'b>,
+  pub user_b_acc: &'a AccountInfo<'b>,
+  pub vault_b_acc: &'a AccountInfo<'b>,
+  pub tick_acc: &'a AccountInfo<'b>,
+  pub oracle_acc: &'a AccountInfo<'b>,
+  pub spl_token_program_acc: &'a AccountInfo<'b>,
+  pub a_to_b: u8,
+}
+
+impl<'a, 'b> DexAdapter<'a, 'b> for WhirlAdapter<'a, 'b> {
+  fn parse<I: Iterator<Item = &'a AccountInfo<'b>>>(account_info_iter: &mut I, side: u8) -> Result<Self, ProgramError> {
+    if side > 1 {
+      return Err(ArbitrageError::InvalidInstruction.into());
+    }
+    Ok(
+      WhirlAdapter {
+        program_acc: next_account_info(account_info_iter)?,
+        market_acc: next_account_info(account_info_iter)?,
+        user_owner_acc: next_account_info(account_info_iter)?,
+        user_a_acc: next_account_info(account_info_iter)?,
+        vault_a_acc: next_account_info(account_info_iter)?,
+        user_b_acc: next_account_info(account_info_iter)?,
+        vault_b_acc: next_account_info(account_info_iter)?,
+        tick_acc: next_account_info(account_info_iter)?,
+        oracle_acc: next_account_info(account_info_iter)?,
+        spl_token_program_acc: next_account_info(account_info_iter)?,
+        a_to_b: side,
+      }
+    )
+  }
+
+  fn user_source(&self) -> &'a AccountInfo<'b> {
+    if self.a_to_b == 1 { self.user_a_acc } else { self.user_b_acc }
+  }
+
+  fn user_destination(&self) -> &'a AccountInfo<'b> {
+    if self.a_to_b == 1 { self.user_b_acc } else { self.user_a_acc }
+  }
+
+  fn user_owner(&self) -> &'a AccountInfo<'b> {
+    self.user_owner_acc
+  }
+
+  fn pool(&self) -> (&'a AccountInfo<'b>, &'a AccountInfo<'b>) {
+    (self.program_acc, self.market_acc)
+  }
+
+  fn program_id() -> Pubkey {
+    validation::whirlpool::id()
+  }
+
+  fn invoke_swap(&self, amount_in: u64, minimum_amount_out: u64) -> ProgramResult {
+    Processor::whirl_swap(
+      self.program_acc.key,
+      self.market_acc,
+      self.user_owner_acc,
+      self.user_a_acc,
+      self.vault_a_acc,
+      self.user_b_acc,
+      self.vault_b_acc,
+      self.tick_acc,
+      self.tick_acc,
+      self.tick_acc,
+      self.oracle_acc,
+      self.spl_token_program_acc,
+      amount_in,
+      minimum_amount_out,
+      self.a_to_b,
+    )
+  }
+}
+
+/// crema clmm pool
+pub struct CremaAdapter<'a, 'b> {
+  pub program_acc: &'a AccountInfo<'b>,
+  pub market_acc: &'a AccountInfo<'b>,
+  pub market_auth_acc: &'a AccountInfo<'b>,
+  pub user_owner_acc: &'a AccountInfo<'b>,
+  pub user_source_acc: &'a AccountInfo<'b>,
+  pub swap_source_acc: &'a AccountInfo<'b>,
+  pub swap_dst_acc: &'a AccountInfo<'b>,
+  pub user_dst_acc: &'a AccountInfo<'b>,
+  pub ticks_acc: &'a AccountInfo<'b>,
+  pub spl_token_program_acc: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> DexAdapter<'a, 'b> for CremaAdapter<'a, 'b> {
+  fn parse<I: Iterator<Item = &'a AccountInfo<'b>>>(account_info_iter: &mut I, _side: u8) -> Result<Self, ProgramError> {
+    Ok(
+      CremaAdapter {
+        program_acc: next_account_info(account_info_iter)?,
+        market_acc: next_account_info(account_info_iter)?,
+        market_auth_acc: next_account_info(account_info_iter)?,
+        user_owner_acc: next_account_info(account_info_iter)?,
+        user_source_acc: next_account_info(account_info_iter)?,
+        swap_source_acc: next_account_info(account_info_iter)?,
+        swap_dst_acc: next_account_info(account_info_iter)?,
+        user_dst_acc: next_account_info(account_info_iter)?,
+        ticks_acc: next_account_info(account_info_iter)?,
+        spl_token_program_acc: next_account_info(account_info_iter)?,
+      }
+    )
+  }
+
+  fn user_source(&self) -> &'a AccountInfo<'b> {
+    self.user_source_acc
+  }
+
+  fn user_destination(&self) -> &'a AccountInfo<'b> {
+    self.user_dst_acc
+  }
+
+  fn user_owner(&self) -> &'a AccountInfo<'b> {
+    self.user_owner_acc
+  }
+
+  fn pool(&self) -> (&'a AccountInfo<'b>, &'a AccountInfo<'b>) {
+    (self.program_acc, self.market_acc)
+  }
+
+  fn program_id() -> Pubkey {
+    validation::crema::id()
+  }
+
+  fn invoke_swap(&self, amount_in: u64, minimum_amount_out: u64) -> ProgramResult {
+    Processor::crema_swap(
+      self.program_acc.key,
+      self.market_acc,
+      self.market_auth_acc,
+      self.user_owner_acc,
+      self.user_source_acc,
+      self.swap_source_acc,
+      self.swap_dst_acc,
+      self.user_dst_acc,
+      self.ticks_acc,
+      self.spl_token_program_acc,
+      amount_in,
+      minimum_amount_out,
+    )
+  }
+}
@@ -0,0 +1,1108 @@
+//! Instruction builders for off-chain callers.
+//!
+//! Every builder lists its accounts in the order the matching processor
+//! reads them with `next_account_info`, so bots do not have to keep their
+//! own copy of the 30+ account layouts.
+
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+    sysvar,
+};
+
+use crate::{
+  error::ArbitrageError,
+  instruction::{
+    ArbitrageInstruction,
+    ConfigInstruction,
+    ExchangeStateInstruction,
+    ExchangeWithMinimumsInstruction,
+    ExchangeWithPathInstruction,
+    ExchangeWithTryInstruction,
+    ExecuteRouteInstruction,
+    Market,
+    RouteHop,
+    SerumSelectInstruction,
+    StrategyParamsInstruction,
+  },
+  state::{CONFIG_SEED, EXCHANGE_SEED, STRATEGY_SEED},
+};
+
+/// `Exchange_Stable1..32` in discriminant order, slot `n` is `2 * n` forward and `2 * n + 1` reversed
+const STABLE_VARIANTS: [fn(ExchangeWithTryInstruction) -> ArbitrageInstruction; 32] = [
+  ArbitrageInstruction::Exchange_Stable1,
+  ArbitrageInstruction::Exchange_Stable2,
+  ArbitrageInstruction::Exchange_Stable3,
+  ArbitrageInstruction::Exchange_Stable4,
+  ArbitrageInstruction::Exchange_Stable5,
+  ArbitrageInstruction::Exchange_Stable6,
+  ArbitrageInstruction::Exchange_Stable7,
+  ArbitrageInstruction::Exchange_Stable8,
+  ArbitrageInstruction::Exchange_Stable9,
+  ArbitrageInstruction::Exchange_Stable10,
+  ArbitrageInstruction::Exchange_Stable11,
+  ArbitrageInstruction::Exchange_Stable12,
+  ArbitrageInstruction::Exchange_Stable13,
+  ArbitrageInstruction::Exchange_Stable14,
+  ArbitrageInstruction::Exchange_Stable15,
+  ArbitrageInstruction::Exchange_Stable16,
+  ArbitrageInstruction::Exchange_Stable17,
+  ArbitrageInstruction::Exchange_Stable18,
+  ArbitrageInstruction::Exchange_Stable19,
+  ArbitrageInstruction::Exchange_Stable20,
+  ArbitrageInstruction::Exchange_Stable21,
+  ArbitrageInstruction::Exchange_Stable22,
+  ArbitrageInstruction::Exchange_Stable23,
+  ArbitrageInstruction::Exchange_Stable24,
+  ArbitrageInstruction::Exchange_Stable25,
+  ArbitrageInstruction::Exchange_Stable26,
+  ArbitrageInstruction::Exchange_Stable27,
+  ArbitrageInstruction::Exchange_Stable28,
+  ArbitrageInstruction::Exchange_Stable29,
+  ArbitrageInstruction::Exchange_Stable30,
+  ArbitrageInstruction::Exchange_Stable31,
+  ArbitrageInstruction::Exchange_Stable32,
+];
+
+/// config pda of the program
+pub fn config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+}
+
+/// params pda of the strategy at `index`, the slot of the stable builders
+pub fn strategy_address(program_id: &Pubkey, index: u8) -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[STRATEGY_SEED, &[index]], program_id)
+}
+
+/// exchange state pda of `owner` for `strategy_id`
+pub fn exchange_address(program_id: &Pubkey, owner: &Pubkey, strategy_id: u8) -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[EXCHANGE_SEED, owner.as_ref(), &[strategy_id]], program_id)
+}
+
+fn stable_instruction(slot: u8, reversed: bool, flag: u8, begin_run: bool, max_amount_in: u64, min_amounts_out: [u64; 2]) -> Result<ArbitrageInstruction, ProgramError> {
+  let index = slot as usize * 2 + reversed as usize;
+  let variant = STABLE_VARIANTS.get(index).ok_or(ArbitrageError::InvalidInstruction)?;
+  Ok(
+    variant(ExchangeWithTryInstruction {
+      flag: flag,
+      begin_run: begin_run,
+      max_amount_in: max_amount_in,
+      min_amounts_out: min_amounts_out,
+    })
+  )
+}
+
+fn check_slot(slot: u8, slots: &[u8]) -> Result<(), ProgramError> {
+  if !slots.contains(&slot) {
+    return Err(ArbitrageError::InvalidInstruction.into());
+  }
+  Ok(())
+}
+
+/// orca (spl token swap) pool
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrcaAccounts {
+  pub program: Pubkey,
+  pub market: Pubkey,
+  pub authority: Pubkey,
+  pub swap_a: Pubkey,
+  pub swap_b: Pubkey,
+  pub pool_mint: Pubkey,
+  pub fee: Pubkey,
+}
+
+impl OrcaAccounts {
+  fn append(&self, accounts: &mut Vec<AccountMeta>) {
+    accounts.push(AccountMeta::new_readonly(self.program, false));
+    accounts.push(AccountMeta::new_readonly(self.market, false));
+    accounts.push(AccountMeta::new_readonly(self.authority, false));
+    accounts.push(AccountMeta::new(self.swap_a, false));
+    accounts.push(AccountMeta::new(self.swap_b, false));
+    accounts.push(AccountMeta::new(self.pool_mint, false));
+    accounts.push(AccountMeta::new(self.fee, false));
+  }
+
+  /// accounts of one `Market::Orca` hop, `a_to_b` picks the pool side receiving the input
+  pub fn hop_accounts(&self, owner: &Pubkey, user_source: &Pubkey, user_destination: &Pubkey, a_to_b: bool) -> Vec<AccountMeta> {
+    let (swap_source, swap_destination) = if a_to_b { (self.swap_a, self.swap_b) } else { (self.swap_b, self.swap_a) };
+    vec![
+      AccountMeta::new_readonly(self.program, false),
+      AccountMeta::new_readonly(self.market, false),
+      AccountMeta::new_readonly(self.authority, false),
+      AccountMeta::new_readonly(*owner, true),
+      AccountMeta::new(*user_source, false),
+      AccountMeta::new(swap_source, false),
+      AccountMeta::new(swap_destination, false),
+      AccountMeta::new(*user_destination, false),
+      AccountMeta::new(self.pool_mint, false),
+      AccountMeta::new(self.fee, false),
+      AccountMeta::new_readonly(spl_token::id(), false),
+    ]
+  }
+}
+
+/// saber stable swap pool, `admin_fee` is the admin fee account of the output side
+#[derive(Clone, Debug, PartialEq)]
+pub struct SaberAccounts {
+  pub program: Pubkey,
+  pub market: Pubkey,
+  pub authority: Pubkey,
+  pub swap_a: Pubkey,
+  pub swap_b: Pubkey,
+  pub admin_fee: Pubkey,
+}
+
+impl SaberAccounts {
+  fn append(&self, accounts: &mut Vec<AccountMeta>) {
+    accounts.push(AccountMeta::new_readonly(self.program, false));
+    accounts.push(AccountMeta::new_readonly(self.market, false));
+    accounts.push(AccountMeta::new_readonly(self.authority, false));
+    accounts.push(AccountMeta::new(self.swap_a, false));
+    accounts.push(AccountMeta::new(self.swap_b, false));
+    accounts.push(AccountMeta::new(self.admin_fee, false));
+  }
+
+  /// accounts of one `Market::Saber` hop, `a_to_b` picks the pool side receiving the input
+  pub fn hop_accounts(&self, owner: &Pubkey, user_source: &Pubkey, user_destination: &Pubkey, a_to_b: bool) -> Vec<AccountMeta> {
+    let (swap_source, swap_destination) = if a_to_b { (self.swap_a, self.swap_b) } else { (self.swap_b, self.swap_a) };
+    vec![
+      AccountMeta::new_readonly(self.program, false),
+      AccountMeta::new_readonly(self.market, false),
+      AccountMeta::new_readonly(self.authority, false),
+      AccountMeta::new_readonly(*owner, true),
+      AccountMeta::new(*user_source, false),
+      AccountMeta::new(swap_source, false),
+      AccountMeta::new(swap_destination, false),
+      AccountMeta::new(*user_destination, false),
+      AccountMeta::new(self.admin_fee, false),
+      AccountMeta::new_readonly(spl_token::id(), false),
+      AccountMeta::new_readonly(sysvar::clock::id(), false),
+    ]
+  }
+}
+
+/// serum market, in the order the serum strategies read it
+#[derive(Clone, Debug, PartialEq)]
+pub struct SerumAccounts {
+  pub program: Pubkey,
+  pub market: Pubkey,
+  pub open_orders: Pubkey,
+  pub request_queue: Pubkey,
+  pub event_queue: Pubkey,
+  pub bids: Pubkey,
+  pub asks: Pubkey,
+  pub base_vault: Pubkey,
+  pub quote_vault: Pubkey,
+  pub vault_signer: Pubkey,
+}
+
+impl SerumAccounts {
+  fn append(&self, accounts: &mut Vec<AccountMeta>) {
+    accounts.push(AccountMeta::new_readonly(self.program, false));
+    accounts.push(AccountMeta::new(self.market, false));
+    accounts.push(AccountMeta::new(self.open_orders, false));
+    accounts.push(AccountMeta::new(self.request_queue, false));
+    accounts.push(AccountMeta::new(self.event_queue, false));
+    accounts.push(AccountMeta::new(self.bids, false));
+    accounts.push(AccountMeta::new(self.asks, false));
+    accounts.push(AccountMeta::new(self.base_vault, false));
+    accounts.push(AccountMeta::new(self.quote_vault, false));
+    accounts.push(AccountMeta::new_readonly(self.vault_signer, false));
+  }
+
+  /// accounts of one `Market::Serum` hop, the side is passed in the instruction
+  pub fn hop_accounts(&self, owner: &Pubkey, user_source: &Pubkey, user_destination: &Pubkey) -> Vec<AccountMeta> {
+    let mut accounts = Vec::with_capacity(15);
+    self.append(&mut accounts);
+    accounts.push(AccountMeta::new_readonly(*owner, true));
+    accounts.push(AccountMeta::new(*user_source, false));
+    accounts.push(AccountMeta::new(*user_destination, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts
+  }
+}
+
+/// raydium amm together with the serum market it is backed by
+#[derive(Clone, Debug, PartialEq)]
+pub struct RaydiumAccounts {
+  pub program: Pubkey,
+  pub market: Pubkey,
+  pub authority: Pubkey,
+  pub open_orders: Pubkey,
+  pub target_orders: Pubkey,
+  pub coin_vault: Pubkey,
+  pub pc_vault: Pubkey,
+  pub serum_program: Pubkey,
+  pub serum_market: Pubkey,
+  pub serum_request_queue: Pubkey,
+  pub serum_event_queue: Pubkey,
+  pub serum_bids: Pubkey,
+  pub serum_asks: Pubkey,
+  pub serum_base_vault: Pubkey,
+  pub serum_quote_vault: Pubkey,
+  pub serum_vault_signer: Pubkey,
+}
+
+impl RaydiumAccounts {
+  fn append_amm(&self, accounts: &mut Vec<AccountMeta>) {
+    accounts.push(AccountMeta::new_readonly(self.program, false));
+    accounts.push(AccountMeta::new(self.market, false));
+    accounts.push(AccountMeta::new_readonly(self.authority, false));
+    accounts.push(AccountMeta::new(self.open_orders, false));
+    accounts.push(AccountMeta::new(self.target_orders, false));
+    accounts.push(AccountMeta::new(self.coin_vault, false));
+    accounts.push(AccountMeta::new(self.pc_vault, false));
+  }
+
+  fn append(&self, accounts: &mut Vec<AccountMeta>) {
+    self.append_amm(accounts);
+    accounts.push(AccountMeta::new_readonly(self.serum_program, false));
+    accounts.push(AccountMeta::new(self.serum_market, false));
+    accounts.push(AccountMeta::new(self.serum_request_queue, false));
+    accounts.push(AccountMeta::new(self.serum_event_queue, false));
+    accounts.push(AccountMeta::new(self.serum_bids, false));
+    accounts.push(AccountMeta::new(self.serum_asks, false));
+    accounts.push(AccountMeta::new(self.serum_base_vault, false));
+    accounts.push(AccountMeta::new(self.serum_quote_vault, false));
+    accounts.push(AccountMeta::new_readonly(self.serum_vault_signer, false));
+  }
+
+  /// accounts of one `Market::Raydium` hop
+  pub fn hop_accounts(&self, owner: &Pubkey, user_source: &Pubkey, user_destination: &Pubkey) -> Vec<AccountMeta> {
+    let mut accounts = Vec::with_capacity(19);
+    self.append(&mut accounts);
+    accounts.push(AccountMeta::new_readonly(*owner, true));
+    accounts.push(AccountMeta::new(*user_source, false));
+    accounts.push(AccountMeta::new(*user_destination, false));
+    accounts
+  }
+}
+
+/// mercurial n pool, `swap_accounts` holds one token account per pool token
+#[derive(Clone, Debug, PartialEq)]
+pub struct MercurialAccounts {
+  pub program: Pubkey,
+  pub market: Pubkey,
+  pub authority: Pubkey,
+  pub swap_accounts: Vec<Pubkey>,
+}
+
+impl MercurialAccounts {
+  fn append(&self, accounts: &mut Vec<AccountMeta>) {
+    accounts.push(AccountMeta::new_readonly(self.program, false));
+    accounts.push(AccountMeta::new_readonly(self.market, false));
+    accounts.push(AccountMeta::new_readonly(self.authority, false));
+    for swap_account in self.swap_accounts.iter() {
+      accounts.push(AccountMeta::new(*swap_account, false));
+    }
+  }
+
+  /// market of a hop through this pool
+  pub fn market(&self) -> Result<Market, ProgramError> {
+    match self.swap_accounts.len() {
+      2 => Ok(Market::Mercurial_2pool),
+      3 => Ok(Market::Mercurial_3pool),
+      4 => Ok(Market::Mercurial_4pool),
+      _ => Err(ArbitrageError::InvalidInstruction.into()),
+    }
+  }
+
+  /// accounts of one `Market::Mercurial_*pool` hop
+  pub fn hop_accounts(&self, owner: &Pubkey, user_source: &Pubkey, user_destination: &Pubkey) -> Vec<AccountMeta> {
+    let mut accounts = vec![
+      AccountMeta::new_readonly(self.program, false),
+      AccountMeta::new_readonly(self.market, false),
+      AccountMeta::new_readonly(self.authority, false),
+      AccountMeta::new_readonly(*owner, true),
+      AccountMeta::new(*user_source, false),
+    ];
+    for swap_account in self.swap_accounts.iter() {
+      accounts.push(AccountMeta::new(*swap_account, false));
+    }
+    accounts.push(AccountMeta::new(*user_destination, false));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts
+  }
+}
+
+/// orca whirlpool
+#[derive(Clone, Debug, PartialEq)]
+pub struct WhirlAccounts {
+  pub program: Pubkey,
+  pub market: Pubkey,
+  pub vault_a: Pubkey,
+  pub vault_b: Pubkey,
+  pub tick_array: Pubkey,
+  pub oracle: Pubkey,
+}
+
+impl WhirlAccounts {
+  fn append(&self, accounts: &mut Vec<AccountMeta>) {
+    accounts.push(AccountMeta::new_readonly(self.program, false));
+    accounts.push(AccountMeta::new(self.market, false));
+    accounts.push(AccountMeta::new(self.vault_a, false));
+    accounts.push(AccountMeta::new(self.vault_b, false));
+    accounts.push(AccountMeta::new(self.tick_array, false));
+    accounts.push(AccountMeta::new_readonly(self.oracle, false));
+  }
+
+  /// accounts of one `Market::Whirl` hop, `user_a`/`user_b` hold the pool's token a/b
+  pub fn hop_accounts(&self, owner: &Pubkey, user_a: &Pubkey, user_b: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+      AccountMeta::new_readonly(self.program, false),
+      AccountMeta::new(self.market, false),
+      AccountMeta::new_readonly(*owner, true),
+      AccountMeta::new(*user_a, false),
+      AccountMeta::new(self.vault_a, false),
+      AccountMeta::new(*user_b, false),
+      AccountMeta::new(self.vault_b, false),
+      AccountMeta::new(self.tick_array, false),
+      AccountMeta::new_readonly(self.oracle, false),
+      AccountMeta::new_readonly(spl_token::id(), false),
+    ]
+  }
+}
+
+/// crema clmm pool
+#[derive(Clone, Debug, PartialEq)]
+pub struct CremaAccounts {
+  pub program: Pubkey,
+  pub market: Pubkey,
+  pub authority: Pubkey,
+  pub swap_a: Pubkey,
+  pub swap_b: Pubkey,
+  pub ticks: Pubkey,
+}
+
+impl CremaAccounts {
+  fn append(&self, accounts: &mut Vec<AccountMeta>) {
+    accounts.push(AccountMeta::new_readonly(self.program, false));
+    accounts.push(AccountMeta::new(self.market, false));
+    accounts.push(AccountMeta::new_readonly(self.authority, false));
+    accounts.push(AccountMeta::new(self.swap_a, false));
+    accounts.push(AccountMeta::new(self.swap_b, false));
+    accounts.push(AccountMeta::new(self.ticks, false));
+  }
+
+  /// accounts of one `Market::Crema` hop, `a_to_b` picks the pool side receiving the input
+  pub fn hop_accounts(&self, owner: &Pubkey, user_source: &Pubkey, user_destination: &Pubkey, a_to_b: bool) -> Vec<AccountMeta> {
+    let (swap_source, swap_destination) = if a_to_b { (self.swap_a, self.swap_b) } else { (self.swap_b, self.swap_a) };
+    vec![
+      AccountMeta::new_readonly(self.program, false),
+      AccountMeta::new(self.market, false),
+      AccountMeta::new_readonly(self.authority, false),
+      AccountMeta::new_readonly(*owner, true),
+      AccountMeta::new(*user_source, false),
+      AccountMeta::new(swap_source, false),
+      AccountMeta::new(swap_destination, false),
+      AccountMeta::new(*user_destination, false),
+      AccountMeta::new(self.ticks, false),
+      AccountMeta::new_readonly(spl_token::id(), false),
+    ]
+  }
+}
+
+/// `InitializeConfig`, creates the config pda paid by `payer`
+pub struct InitializeConfig {
+  pub payer: Pubkey,
+  pub admin: Pubkey,
+  pub operators: Vec<Pubkey>,
+}
+
+impl InitializeConfig {
+  pub fn instruction(&self, program_id: &Pubkey) -> Instruction {
+    let data = ArbitrageInstruction::InitializeConfig(ConfigInstruction {
+      admin: self.admin,
+      operators: self.operators.clone(),
+    });
+    Instruction {
+      program_id: *program_id,
+      accounts: vec![
+        AccountMeta::new(self.payer, true),
+        AccountMeta::new(config_address(program_id).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+      ],
+      data: data.pack(),
+    }
+  }
+}
+
+/// `UpdateConfig`, signed by the current admin
+pub struct UpdateConfig {
+  pub admin: Pubkey,
+  pub new_admin: Pubkey,
+  pub operators: Vec<Pubkey>,
+}
+
+impl UpdateConfig {
+  pub fn instruction(&self, program_id: &Pubkey) -> Instruction {
+    let data = ArbitrageInstruction::UpdateConfig(ConfigInstruction {
+      admin: self.new_admin,
+      operators: self.operators.clone(),
+    });
+    Instruction {
+      program_id: *program_id,
+      accounts: vec![
+        AccountMeta::new_readonly(self.admin, true),
+        AccountMeta::new(config_address(program_id).0, false),
+      ],
+      data: data.pack(),
+    }
+  }
+}
+
+/// `InitializeStrategy`, creates the params pda of strategy `index` paid by the admin
+pub struct InitializeStrategy {
+  pub admin: Pubkey,
+  pub index: u8,
+  pub enabled: bool,
+  pub input_amount: u64,
+  pub threshold: u64,
+  pub expected_profit: u64,
+}
+
+impl InitializeStrategy {
+  pub fn instruction(&self, program_id: &Pubkey) -> Instruction {
+    let data = ArbitrageInstruction::InitializeStrategy(StrategyParamsInstruction {
+      index: self.index,
+      enabled: self.enabled,
+      input_amount: self.input_amount,
+      threshold: self.threshold,
+      expected_profit: self.expected_profit,
+    });
+    Instruction {
+      program_id: *program_id,
+      accounts: vec![
+        AccountMeta::new(self.admin, true),
+        AccountMeta::new_readonly(config_address(program_id).0, false),
+        AccountMeta::new(strategy_address(program_id, self.index).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+      ],
+      data: data.pack(),
+    }
+  }
+}
+
+/// `UpdateStrategy`, signed by the config admin
+pub struct UpdateStrategy {
+  pub admin: Pubkey,
+  pub index: u8,
+  pub enabled: bool,
+  pub input_amount: u64,
+  pub threshold: u64,
+  pub expected_profit: u64,
+}
+
+impl UpdateStrategy {
+  pub fn instruction(&self, program_id: &Pubkey) -> Instruction {
+    let data = ArbitrageInstruction::UpdateStrategy(StrategyParamsInstruction {
+      index: self.index,
+      enabled: self.enabled,
+      input_amount: self.input_amount,
+      threshold: self.threshold,
+      expected_profit: self.expected_profit,
+    });
+    Instruction {
+      program_id: *program_id,
+      accounts: vec![
+        AccountMeta::new_readonly(self.admin, true),
+        AccountMeta::new_readonly(config_address(program_id).0, false),
+        AccountMeta::new(strategy_address(program_id, self.index).0, false),
+      ],
+      data: data.pack(),
+    }
+  }
+}
+
+/// `MigrateExchangeState`, copies a 32 byte exchange state into the pda of `owner`, signed by the config admin
+///
+/// the legacy account is closed rather than resized, later exchange instructions
+/// take `exchange_address(program_id, owner, strategy_id)` in its place
+pub struct MigrateExchangeState {
+  pub admin: Pubkey,
+  pub legacy: Pubkey,
+  pub owner: Pubkey,
+  pub strategy_id: u8,
+}
+
+impl MigrateExchangeState {
+  pub fn instruction(&self, program_id: &Pubkey) -> Instruction {
+    let data = ArbitrageInstruction::MigrateExchangeState(ExchangeStateInstruction {
+      strategy_id: self.strategy_id,
+    });
+    Instruction {
+      program_id: *program_id,
+      accounts: vec![
+        AccountMeta::new(self.admin, true),
+        AccountMeta::new_readonly(config_address(program_id).0, false),
+        AccountMeta::new(self.legacy, false),
+        AccountMeta::new_readonly(self.owner, false),
+        AccountMeta::new(exchange_address(program_id, &self.owner, self.strategy_id).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+      ],
+      data: data.pack(),
+    }
+  }
+}
+
+/// `InitializeExchangeState`, creates the exchange state pda of the operator for `strategy_id`
+pub struct InitializeExchangeState {
+  pub operator: Pubkey,
+  pub strategy_id: u8,
+}
+
+impl InitializeExchangeState {
+  pub fn instruction(&self, program_id: &Pubkey) -> Instruction {
+    let data = ArbitrageInstruction::InitializeExchangeState(ExchangeStateInstruction {
+      strategy_id: self.strategy_id,
+    });
+    Instruction {
+      program_id: *program_id,
+      accounts: vec![
+        AccountMeta::new(self.operator, true),
+        AccountMeta::new_readonly(config_address(program_id).0, false),
+        AccountMeta::new(exchange_address(program_id, &self.operator, self.strategy_id).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+      ],
+      data: data.pack(),
+    }
+  }
+}
+
+/// `ResetExchangeState`, signed by the owner of the state
+pub struct ResetExchangeState {
+  pub owner: Pubkey,
+  pub exchange: Pubkey,
+  pub strategy_id: u8,
+}
+
+impl ResetExchangeState {
+  pub fn instruction(&self, program_id: &Pubkey) -> Instruction {
+    let data = ArbitrageInstruction::ResetExchangeState(ExchangeStateInstruction {
+      strategy_id: self.strategy_id,
+    });
+    Instruction {
+      program_id: *program_id,
+      accounts: vec![
+        AccountMeta::new_readonly(self.owner, true),
+        AccountMeta::new(self.exchange, false),
+      ],
+      data: data.pack(),
+    }
+  }
+}
+
+/// `CloseExchangeState`, signed by the owner of the state; the rent goes to `destination`
+pub struct CloseExchangeState {
+  pub owner: Pubkey,
+  pub exchange: Pubkey,
+  pub destination: Pubkey,
+  pub strategy_id: u8,
+}
+
+impl CloseExchangeState {
+  pub fn instruction(&self, program_id: &Pubkey) -> Instruction {
+    let data = ArbitrageInstruction::CloseExchangeState(ExchangeStateInstruction {
+      strategy_id: self.strategy_id,
+    });
+    Instruction {
+      program_id: *program_id,
+      accounts: vec![
+        AccountMeta::new_readonly(self.owner, true),
+        AccountMeta::new(self.exchange, false),
+        AccountMeta::new(self.destination, false),
+      ],
+      data: data.pack(),
+    }
+  }
+}
+
+/// `Exchange_WithPath`, one hop; `hop_accounts` comes from the venue's `hop_accounts`
+pub struct PathExchange {
+  pub operator: Pubkey,
+  pub exchange: Pubkey,
+  pub flag: u8,
+  pub amount: u64,
+  pub market: Market,
+  pub side: u8,
+  pub minimum_amount_out: u64,
+  pub hop_accounts: Vec<AccountMeta>,
+}
+
+impl PathExchange {
+  pub fn instruction(&self, program_id: &Pubkey) -> Instruction {
+    let data = ArbitrageInstruction::Exchange_WithPath(ExchangeWithPathInstruction {
+      flag: self.flag,
+      amount: self.amount,
+      market: self.market,
+      side: self.side,
+      minimum_amount_out: self.minimum_amount_out,
+    });
+    let mut accounts = vec![
+      AccountMeta::new_readonly(config_address(program_id).0, false),
+      AccountMeta::new_readonly(self.operator, true),
+      AccountMeta::new(self.exchange, false),
+    ];
+    accounts.extend(self.hop_accounts.iter().cloned());
+    Instruction {
+      program_id: *program_id,
+      accounts: accounts,
+      data: data.pack(),
+    }
+  }
+}
+
+/// one hop of an `ExecuteRoute`
+pub struct RouteHopAccounts {
+  pub market: Market,
+  pub side: u8,
+  pub minimum_amount_out: u64,
+  pub accounts: Vec<AccountMeta>,
+}
+
+/// `ExecuteRoute`, every hop's accounts are laid out back to back
+pub struct ExecuteRoute {
+  pub operator: Pubkey,
+  pub amount_in: u64,
+  pub hops: Vec<RouteHopAccounts>,
+}
+
+impl ExecuteRoute {
+  pub fn instruction(&self, program_id: &Pubkey) -> Result<Instruction, ProgramError> {
+    let mut hops = Vec::with_capacity(self.hops.len());
+    let mut accounts = vec![
+      AccountMeta::new_readonly(config_address(program_id).0, false),
+      AccountMeta::new_readonly(self.operator, true),
+    ];
+    for hop in self.hops.iter() {
+      if hop.accounts.len() > u8::MAX as usize {
+        return Err(ArbitrageError::InvalidInstruction.into());
+      }
+      hops.push(RouteHop {
+        market: hop.market,
+        side: hop.side,
+        accounts_len: hop.accounts.len() as u8,
+        minimum_amount_out: hop.minimum_amount_out,
+      });
+      accounts.extend(hop.accounts.iter().cloned());
+    }
+    let data = ArbitrageInstruction::ExecuteRoute(ExecuteRouteInstruction {
+      amount_in: self.amount_in,
+      hops: hops,
+    });
+    Ok(
+      Instruction {
+        program_id: *program_id,
+        accounts: accounts,
+        data: data.pack(),
+      }
+    )
+  }
+}
+
+/// `Exchange_NonStable_All`, orca against raydium with serum as price reference
+pub struct OrcaRaydiumSerumExchange {
+  pub exchange: Pubkey,
+  pub raydium: RaydiumAccounts,
+  pub orca: OrcaAccounts,
+  pub owner: Pubkey,
+  pub user_usdc: Pubkey,
+  pub user_other: Pubkey,
+  /// mint of `user_other`, its decimals scale the prices
+  pub other_mint: Pubkey,
+  pub flag: u8,
+  /// reseed the trade size and clear the run's profit and loss first
+  pub begin_run: bool,
+  /// minimum output of the first and the second leg, whichever venue the prices run first
+  pub min_amounts_out: [u64; 2],
+}
+
+impl OrcaRaydiumSerumExchange {
+  pub fn instruction(&self, program_id: &Pubkey) -> Instruction {
+    let data = ArbitrageInstruction::Exchange_NonStable_All(ExchangeWithTryInstruction {
+      flag: self.flag,
+      begin_run: self.begin_run,
+      max_amount_in: 0,
+      min_amounts_out: self.min_amounts_out,
+    });
+    let mut accounts = vec![
+      AccountMeta::new_readonly(config_address(program_id).0, false),
+      AccountMeta::new(self.exchange, false),
+    ];
+    self.raydium.append_amm(&mut accounts);
+    accounts.push(AccountMeta::new_readonly(self.raydium.serum_program, false));
+    accounts.push(AccountMeta::new(self.raydium.serum_market, false));
+    accounts.push(AccountMeta::new(self.raydium.serum_bids, false));
+    accounts.push(AccountMeta::new(self.raydium.serum_asks, false));
+    accounts.push(AccountMeta::new(self.raydium.serum_event_queue, false));
+    accounts.push(AccountMeta::new(self.raydium.serum_base_vault, false));
+    accounts.push(AccountMeta::new(self.raydium.serum_quote_vault, false));
+    accounts.push(AccountMeta::new_readonly(self.raydium.serum_vault_signer, false));
+    self.orca.append(&mut accounts);
+    accounts.push(AccountMeta::new_readonly(self.owner, true));
+    accounts.push(AccountMeta::new(self.user_usdc, false));
+    accounts.push(AccountMeta::new(self.user_other, false));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts.push(AccountMeta::new_readonly(self.other_mint, false));
+    Instruction {
+      program_id: *program_id,
+      accounts: accounts,
+      data: data.pack(),
+    }
+  }
+}
+
+/// `Exchange_NonStable_Serum1/2`, serum against saber and orca
+pub struct SerumSelectExchange {
+  pub serum: SerumAccounts,
+  /// `admin_fee` is the fee account of token a
+  pub saber: SaberAccounts,
+  pub saber_admin_fee_b: Pubkey,
+  pub orca: OrcaAccounts,
+  pub owner: Pubkey,
+  pub user_usdc: Pubkey,
+  pub user_usdt: Pubkey,
+  pub user_sol: Pubkey,
+  /// mint of `user_sol`, its decimals scale the serum prices
+  pub sol_mint: Pubkey,
+  /// encode as `Exchange_NonStable_Serum2` instead of `Exchange_NonStable_Serum1`
+  pub v2: bool,
+  /// minimum output of each leg when the serum bid is above orca, in swap order
+  pub bid_min_amounts_out: [u64; 3],
+  /// minimum output of each leg when the serum ask is below orca, in swap order
+  pub ask_min_amounts_out: [u64; 3],
+}
+
+impl SerumSelectExchange {
+  pub fn instruction(&self, program_id: &Pubkey) -> Instruction {
+    let minimums = SerumSelectInstruction {
+      bid_min_amounts_out: self.bid_min_amounts_out,
+      ask_min_amounts_out: self.ask_min_amounts_out,
+    };
+    let data = if self.v2 {
+      ArbitrageInstruction::Exchange_NonStable_Serum2(minimums)
+    } else {
+      ArbitrageInstruction::Exchange_NonStable_Serum1(minimums)
+    };
+    let mut accounts = vec![AccountMeta::new_readonly(config_address(program_id).0, false)];
+    self.serum.append(&mut accounts);
+    self.saber.append(&mut accounts);
+    accounts.push(AccountMeta::new(self.saber_admin_fee_b, false));
+    self.orca.append(&mut accounts);
+    accounts.push(AccountMeta::new_readonly(self.owner, true));
+    accounts.push(AccountMeta::new(self.user_usdc, false));
+    accounts.push(AccountMeta::new(self.user_usdt, false));
+    accounts.push(AccountMeta::new(self.user_sol, false));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    accounts.push(AccountMeta::new_readonly(self.sol_mint, false));
+    Instruction {
+      program_id: *program_id,
+      accounts: accounts,
+      data: data.pack(),
+    }
+  }
+}
+
+/// `Exchange_NonStable_Serum3/4`, serum and whirlpool through saber
+pub struct SerumWhirlExchange {
+  pub serum: SerumAccounts,
+  /// `admin_fee` is the fee account of token a
+  pub saber: SaberAccounts,
+  pub saber_admin_fee_b: Pubkey,
+  pub whirl: WhirlAccounts,
+  pub owner: Pubkey,
+  pub user_usdc: Pubkey,
+  pub user_stsol: Pubkey,
+  pub user_sol: Pubkey,
+  /// mint of `user_sol`, its decimals scale the serum prices
+  pub sol_mint: Pubkey,
+  /// whirlpool leg first (`Exchange_NonStable_Serum4`) instead of serum first
+  pub reversed: bool,
+  /// minimum output of each of the three legs in swap order
+  pub min_amounts_out: [u64; 3],
+}
+
+impl SerumWhirlExchange {
+  pub fn instruction(&self, program_id: &Pubkey) -> Instruction {
+    let minimums = ExchangeWithMinimumsInstruction { min_amounts_out: self.min_amounts_out };
+    let data = if self.reversed {
+      ArbitrageInstruction::Exchange_NonStable_Serum4(minimums)
+    } else {
+      ArbitrageInstruction::Exchange_NonStable_Serum3(minimums)
+    };
+    let mut accounts = vec![AccountMeta::new_readonly(config_address(program_id).0, false)];
+    self.serum.append(&mut accounts);
+    self.saber.append(&mut accounts);
+    accounts.push(AccountMeta::new(self.saber_admin_fee_b, false));
+    self.whirl.append(&mut accounts);
+    accounts.push(AccountMeta::new_readonly(self.owner, true));
+    accounts.push(AccountMeta::new(self.user_usdc, false));
+    accounts.push(AccountMeta::new(self.user_stsol, false));
+    accounts.push(AccountMeta::new(self.user_sol, false));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    accounts.push(AccountMeta::new_readonly(self.sol_mint, false));
+    Instruction {
+      program_id: *program_id,
+      accounts: accounts,
+      data: data.pack(),
+    }
+  }
+}
+
+/// user side shared by the two leg stable strategies
+#[derive(Clone, Debug, PartialEq)]
+pub struct StableUserAccounts {
+  pub owner: Pubkey,
+  pub user_usdc: Pubkey,
+  pub user_ust: Pubkey,
+}
+
+impl StableUserAccounts {
+  fn append(&self, accounts: &mut Vec<AccountMeta>) {
+    accounts.push(AccountMeta::new_readonly(self.owner, true));
+    accounts.push(AccountMeta::new(self.user_usdc, false));
+    accounts.push(AccountMeta::new(self.user_ust, false));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+  }
+}
+
+/// `Exchange_Stable1..12`, saber against a mercurial pool.
+///
+/// `slot` is the strategy slot of the pair: 0-1 for 3pools, 2-3 for 2pools and
+/// 4-5 for 4pools. `reversed` runs the mercurial leg first.
+pub struct SaberMercurialExchange {
+  pub exchange: Pubkey,
+  pub saber: SaberAccounts,
+  pub mercurial: MercurialAccounts,
+  pub user: StableUserAccounts,
+  pub slot: u8,
+  pub reversed: bool,
+  pub flag: u8,
+  /// reseed the trade size and clear the run's profit and loss first
+  pub begin_run: bool,
+  /// minimum output of the first and the second leg, in the order `reversed` runs them
+  pub min_amounts_out: [u64; 2],
+  /// 0 sizes from the tables, otherwise the solver searches up to this much usdc
+  pub max_amount_in: u64,
+}
+
+impl SaberMercurialExchange {
+  pub fn instruction(&self, program_id: &Pubkey) -> Result<Instruction, ProgramError> {
+    let slots: &[u8] = match self.mercurial.market()? {
+      Market::Mercurial_3pool => &[0, 1],
+      Market::Mercurial_2pool => &[2, 3],
+      _ => &[4, 5],
+    };
+    check_slot(self.slot, slots)?;
+    let data = stable_instruction(self.slot, self.reversed, self.flag, self.begin_run, self.max_amount_in, self.min_amounts_out)?;
+    let mut accounts = vec![
+      AccountMeta::new_readonly(config_address(program_id).0, false),
+      AccountMeta::new_readonly(strategy_address(program_id, self.slot).0, false),
+      AccountMeta::new(self.exchange, false),
+    ];
+    self.saber.append(&mut accounts);
+    self.mercurial.append(&mut accounts);
+    self.user.append(&mut accounts);
+    Ok(
+      Instruction {
+        program_id: *program_id,
+        accounts: accounts,
+        data: data.pack(),
+      }
+    )
+  }
+}
+
+/// `Exchange_Stable13..16`, saber against a whirlpool, slots 6-7
+pub struct SaberWhirlExchange {
+  pub exchange: Pubkey,
+  pub saber: SaberAccounts,
+  pub whirl: WhirlAccounts,
+  pub user: StableUserAccounts,
+  pub slot: u8,
+  pub reversed: bool,
+  pub flag: u8,
+  /// reseed the trade size and clear the run's profit and loss first
+  pub begin_run: bool,
+  /// minimum output of the first and the second leg, in the order `reversed` runs them
+  pub min_amounts_out: [u64; 2],
+}
+
+impl SaberWhirlExchange {
+  pub fn instruction(&self, program_id: &Pubkey) -> Result<Instruction, ProgramError> {
+    check_slot(self.slot, &[6, 7])?;
+    let data = stable_instruction(self.slot, self.reversed, self.flag, self.begin_run, 0, self.min_amounts_out)?;
+    let mut accounts = vec![
+      AccountMeta::new_readonly(config_address(program_id).0, false),
+      AccountMeta::new_readonly(strategy_address(program_id, self.slot).0, false),
+      AccountMeta::new(self.exchange, false),
+    ];
+    self.saber.append(&mut accounts);
+    self.whirl.append(&mut accounts);
+    self.user.append(&mut accounts);
+    Ok(
+      Instruction {
+        program_id: *program_id,
+        accounts: accounts,
+        data: data.pack(),
+      }
+    )
+  }
+}
+
+/// `Exchange_Stable17..20`, saber against a crema pool (`swap_a` usdc, `swap_b` ust), slots 8-9
+pub struct SaberCremaExchange {
+  pub exchange: Pubkey,
+  pub saber: SaberAccounts,
+  pub crema: CremaAccounts,
+  pub user: StableUserAccounts,
+  pub slot: u8,
+  pub reversed: bool,
+  pub flag: u8,
+  /// reseed the trade size and clear the run's profit and loss first
+  pub begin_run: bool,
+  /// minimum output of the first and the second leg, in the order `reversed` runs them
+  pub min_amounts_out: [u64; 2],
+}
+
+impl SaberCremaExchange {
+  pub fn instruction(&self, program_id: &Pubkey) -> Result<Instruction, ProgramError> {
+    check_slot(self.slot, &[8, 9])?;
+    let data = stable_instruction(self.slot, self.reversed, self.flag, self.begin_run, 0, self.min_amounts_out)?;
+    let mut accounts = vec![
+      AccountMeta::new_readonly(config_address(program_id).0, false),
+      AccountMeta::new_readonly(strategy_address(program_id, self.slot).0, false),
+      AccountMeta::new(self.exchange, false),
+    ];
+    self.saber.append(&mut accounts);
+    self.crema.append(&mut accounts);
+    self.user.append(&mut accounts);
+    Ok(
+      Instruction {
+        program_id: *program_id,
+        accounts: accounts,
+        data: data.pack(),
+      }
+    )
+  }
+}
+
+/// `Exchange_Stable21..24`, orca against the serum ust/usdc market, slots 10-11
+pub struct OrcaSerumExchange {
+  pub exchange: Pubkey,
+  pub orca: OrcaAccounts,
+  pub serum: SerumAccounts,
+  pub user: StableUserAccounts,
+  pub slot: u8,
+  pub reversed: bool,
+  pub flag: u8,
+  /// reseed the trade size and clear the run's profit and loss first
+  pub begin_run: bool,
+  /// minimum output of the first and the second leg, in the order `reversed` runs them
+  pub min_amounts_out: [u64; 2],
+}
+
+impl OrcaSerumExchange {
+  pub fn instruction(&self, program_id: &Pubkey) -> Result<Instruction, ProgramError> {
+    check_slot(self.slot, &[10, 11])?;
+    let data = stable_instruction(self.slot, self.reversed, self.flag, self.begin_run, 0, self.min_amounts_out)?;
+    let mut accounts = vec![
+      AccountMeta::new_readonly(config_address(program_id).0, false),
+      AccountMeta::new_readonly(strategy_address(program_id, self.slot).0, false),
+      AccountMeta::new(self.exchange, false),
+    ];
+    self.orca.append(&mut accounts);
+    self.serum.append(&mut accounts);
+    self.user.append(&mut accounts);
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    Ok(
+      Instruction {
+        program_id: *program_id,
+        accounts: accounts,
+        data: data.pack(),
+      }
+    )
+  }
+}
+
+/// `Exchange_Stable25..28`, orca against a raydium amm, slots 12-13
+pub struct OrcaRaydiumExchange {
+  pub exchange: Pubkey,
+  pub orca: OrcaAccounts,
+  pub raydium: RaydiumAccounts,
+  pub user: StableUserAccounts,
+  pub slot: u8,
+  pub reversed: bool,
+  pub flag: u8,
+  /// reseed the trade size and clear the run's profit and loss first
+  pub begin_run: bool,
+  /// minimum output of the first and the second leg, in the order `reversed` runs them
+  pub min_amounts_out: [u64; 2],
+}
+
+impl OrcaRaydiumExchange {
+  pub fn instruction(&self, program_id: &Pubkey) -> Result<Instruction, ProgramError> {
+    check_slot(self.slot, &[12, 13])?;
+    let data = stable_instruction(self.slot, self.reversed, self.flag, self.begin_run, 0, self.min_amounts_out)?;
+    let mut accounts = vec![
+      AccountMeta::new_readonly(config_address(program_id).0, false),
+      AccountMeta::new_readonly(strategy_address(program_id, self.slot).0, false),
+      AccountMeta::new(self.exchange, false),
+    ];
+    self.orca.append(&mut accounts);
+    self.raydium.append(&mut accounts);
+    self.user.append(&mut accounts);
+    Ok(
+      Instruction {
+        program_id: *program_id,
+        accounts: accounts,
+        data: data.pack(),
+      }
+    )
+  }
+}
+
+/// `Exchange_Stable29..32`, orca against a whirlpool, slots 14-15
+pub struct OrcaWhirlExchange {
+  pub exchange: Pubkey,
+  pub orca: OrcaAccounts,
+  pub whirl: WhirlAccounts,
+  pub user: StableUserAccounts,
+  pub slot: u8,
+  pub reversed: bool,
+  pub flag: u8,
+  /// reseed the trade size and clear the run's profit and loss first
+  pub begin_run: bool,
+  /// minimum output of the first and the second leg, in the order `reversed` runs them
+  pub min_amounts_out: [u64; 2],
+}
+
+impl OrcaWhirlExchange {
+  pub fn instruction(&self, program_id: &Pubkey) -> Result<Instruction, ProgramError> {
+    check_slot(self.slot, &[14, 15])?;
+    let data = stable_instruction(self.slot, self.reversed, self.flag, self.begin_run, 0, self.min_amounts_out)?;
+    let mut accounts = vec![
+      AccountMeta::new_readonly(config_address(program_id).0, false),
+      AccountMeta::new_readonly(strategy_address(program_id, self.slot).0, false),
+      AccountMeta::new(self.exchange, false),
+    ];
+    self.orca.append(&mut accounts);
+    self.whirl.append(&mut accounts);
+    self.user.append(&mut accounts);
+    Ok(
+      Instruction {
+        program_id: *program_id,
+        accounts: accounts,
+        data: data.pack(),
+      }
+    )
+  }
+}
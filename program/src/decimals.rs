@@ -0,0 +1,51 @@
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use spl_token::state::{Account as TokenAccount, Mint};
+
+use crate::error::ArbitrageError;
+
+/// Decimals of the mints an instruction prices against, every mint account is
+/// unpacked once per instruction.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MintDecimals {
+  cache: Vec<(Pubkey, u8)>,
+}
+
+impl MintDecimals {
+  pub fn new() -> Self {
+    MintDecimals { cache: vec![] }
+  }
+
+  /// decimals of `mint_acc`, which must be an initialized spl-token mint
+  pub fn decimals(&mut self, mint_acc: &AccountInfo) -> Result<u8, ProgramError> {
+    if let Some((_, decimals)) = self.cache.iter().find(|(mint, _)| mint == mint_acc.key) {
+      return Ok(*decimals);
+    }
+    if *mint_acc.owner != spl_token::id() {
+      return Err(ArbitrageError::InvalidOwner.into());
+    }
+    let decimals = Mint::unpack(&mint_acc.try_borrow_data()?)?.decimals;
+    self.cache.push((*mint_acc.key, decimals));
+    Ok(decimals)
+  }
+
+  /// native units of one whole token of `mint_acc`
+  pub fn unit(&mut self, mint_acc: &AccountInfo) -> Result<u64, ProgramError> {
+    let decimals = self.decimals(mint_acc)?;
+    10u64.checked_pow(decimals as u32).ok_or_else(|| ArbitrageError::MathOverflow.into())
+  }
+
+  /// `unit` of the mint of `token_acc`, `mint_acc` must be that mint
+  pub fn unit_of(&mut self, token_acc: &AccountInfo, mint_acc: &AccountInfo) -> Result<u64, ProgramError> {
+    let mint = TokenAccount::unpack(&token_acc.try_borrow_data()?)?.mint;
+    if mint != *mint_acc.key {
+      return Err(ArbitrageError::MintMismatch.into());
+    }
+    self.unit(mint_acc)
+  }
+}
@@ -1,8 +1,8 @@
 use solana_program::{
-    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, pubkey::Pubkey,
+    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, program_error::PrintProgramError, pubkey::Pubkey,
 };
 
-use crate::processor::Processor;
+use crate::{error::ArbitrageError, processor::Processor};
 
 entrypoint!(process_instruction);
 fn process_instruction(
@@ -10,5 +10,10 @@ fn process_instruction(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    Processor::process(accounts, instruction_data)
+    if let Err(error) = Processor::process(program_id, accounts, instruction_data) {
+        // logs the variant name next to the custom code
+        error.print::<ArbitrageError>();
+        return Err(error);
+    }
+    Ok(())
 }
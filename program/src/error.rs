@@ -1,25 +1,99 @@
+//! Errors of the arbitrage program.
+//!
+//! Every variant but `ProgramError` is returned as `ProgramError::Custom`
+//! with the code in its doc comment. Codes are stable: a variant keeps its
+//! code for good and a retired code is never handed to another variant.
+
+use num_traits::FromPrimitive;
 use thiserror::Error;
 use solana_program::{
+    decode_error::DecodeError,
     msg,
-    program_error::{ProgramError},
+    program_error::{PrintProgramError, ProgramError},
 };
 
 #[derive(Error, Debug, Clone)]
 pub enum ArbitrageError {
   #[error(transparent)]
   ProgramError(#[from] ProgramError),
-  /// Invalid instruction
+  /// 10000: instruction data does not unpack
   #[error("Invalid Instruction")]
   InvalidInstruction,
-  /// Not Rent Exempt
+  /// 10001: accounts passed in a combination the instruction cannot use
   #[error("Invalid Call")]
   InvalidCall,
-  /// Not Rent Exempt
-  #[error("Invalid Onwer")]
+  /// 10002: pool, market, mint or exchange state account is not owned by the program it belongs to
+  #[error("Invalid Owner")]
   InvalidOwner,
-  /// Expected Amount Mismatch
+  /// 10003: retired, profit checks return `ProfitBelowMinimum`
   #[error("swap out amount is smaller than in amount")]
   OutAmountSmallerThanInAmount,
+  /// 10004: signer is neither the admin nor an operator of the config
+  #[error("Unauthorized Operator")]
+  UnauthorizedOperator,
+  /// 10005: the requested side of the serum book has no orders
+  #[error("Empty Order Book")]
+  EmptyOrderBook,
+  /// 10006: serum market, bids or asks account does not decode
+  #[error("Invalid Order Book")]
+  InvalidOrderBook,
+  /// 10007: pool curve the quote engine does not model
+  #[error("Unsupported Curve")]
+  UnsupportedCurve,
+  /// 10008: pool state or tick array account does not decode
+  #[error("Invalid Pool State")]
+  InvalidPoolState,
+  /// 10009: the admin turned the strategy off in its params account
+  #[error("Strategy Disabled")]
+  StrategyDisabled,
+  /// 10010: checked arithmetic on amounts, prices or accumulators overflowed
+  #[error("Math Overflow")]
+  MathOverflow,
+  /// 10011: a balance a swap should have grown is lower after it
+  #[error("Balance Decreased")]
+  BalanceDecreased,
+  /// 10012: a venue or token program account is not the program it claims to be
+  #[error("Invalid Program Id")]
+  InvalidProgramId,
+  /// 10013: a sysvar account is not the sysvar it claims to be
+  #[error("Invalid Sysvar")]
+  InvalidSysvar,
+  /// 10014: account is not an initialized spl-token account
+  #[error("Invalid Token Account")]
+  InvalidTokenAccount,
+  /// 10015: user token account is not held by the signing owner
+  #[error("Token Owner Mismatch")]
+  TokenOwnerMismatch,
+  /// 10016: token accounts hold mints the pool or the instruction does not
+  #[error("Mint Mismatch")]
+  MintMismatch,
+  /// 10017: a hop received less than its minimum amount out
+  #[error("Slippage Exceeded")]
+  SlippageExceeded,
+  /// 10018: a cycle, path or route lost more than it earned
+  #[error("Profit Below Minimum")]
+  ProfitBelowMinimum,
+  /// 10019: a later path hop ran before the path's flag 0 hop
+  #[error("Stale State")]
+  StaleState,
+  /// 10020: market tag no adapter swaps on
+  #[error("Unsupported Market")]
+  UnsupportedMarket,
+  /// 10021: vault or reserve accounts are not the ones the pool state names
+  #[error("Pool Account Mismatch")]
+  PoolAccountMismatch,
+  /// 10022: the exchange state belongs to another owner
+  #[error("Exchange Owner Mismatch")]
+  ExchangeOwnerMismatch,
+  /// 10023: the exchange state belongs to another strategy
+  #[error("Strategy Mismatch")]
+  StrategyMismatch,
+  /// 10024: an account the instruction writes is passed read-only
+  #[error("Account Not Writable")]
+  AccountNotWritable,
+  /// 10025: the serum order rounds down to zero lots
+  #[error("Amount Below Lot Size")]
+  AmountBelowLotSize,
 }
 
 impl From<ArbitrageError> for ProgramError {
@@ -38,7 +112,131 @@ impl From<ArbitrageError> for ProgramError {
       ArbitrageError::OutAmountSmallerThanInAmount => {
         ProgramError::Custom(10003)
       }
+      ArbitrageError::UnauthorizedOperator => {
+        ProgramError::Custom(10004)
+      }
+      ArbitrageError::EmptyOrderBook => {
+        ProgramError::Custom(10005)
+      }
+      ArbitrageError::InvalidOrderBook => {
+        ProgramError::Custom(10006)
+      }
+      ArbitrageError::UnsupportedCurve => {
+        ProgramError::Custom(10007)
+      }
+      ArbitrageError::InvalidPoolState => {
+        ProgramError::Custom(10008)
+      }
+      ArbitrageError::StrategyDisabled => {
+        ProgramError::Custom(10009)
+      }
+      ArbitrageError::MathOverflow => {
+        ProgramError::Custom(10010)
+      }
+      ArbitrageError::BalanceDecreased => {
+        ProgramError::Custom(10011)
+      }
+      ArbitrageError::InvalidProgramId => {
+        ProgramError::Custom(10012)
+      }
+      ArbitrageError::InvalidSysvar => {
+        ProgramError::Custom(10013)
+      }
+      ArbitrageError::InvalidTokenAccount => {
+        ProgramError::Custom(10014)
+      }
+      ArbitrageError::TokenOwnerMismatch => {
+        ProgramError::Custom(10015)
+      }
+      ArbitrageError::MintMismatch => {
+        ProgramError::Custom(10016)
+      }
+      ArbitrageError::SlippageExceeded => {
+        ProgramError::Custom(10017)
+      }
+      ArbitrageError::ProfitBelowMinimum => {
+        ProgramError::Custom(10018)
+      }
+      ArbitrageError::StaleState => {
+        ProgramError::Custom(10019)
+      }
+      ArbitrageError::UnsupportedMarket => {
+        ProgramError::Custom(10020)
+      }
+      ArbitrageError::PoolAccountMismatch => {
+        ProgramError::Custom(10021)
+      }
+      ArbitrageError::ExchangeOwnerMismatch => {
+        ProgramError::Custom(10022)
+      }
+      ArbitrageError::StrategyMismatch => {
+        ProgramError::Custom(10023)
+      }
+      ArbitrageError::AccountNotWritable => {
+        ProgramError::Custom(10024)
+      }
+      ArbitrageError::AmountBelowLotSize => {
+        ProgramError::Custom(10025)
+      }
+    }
+  }
+}
+
+impl FromPrimitive for ArbitrageError {
+  fn from_i64(n: i64) -> Option<Self> {
+    if n < 0 {
+      return None;
     }
+    Self::from_u64(n as u64)
+  }
+
+  /// the variant of a `ProgramError::Custom` code, `None` for codes of other programs
+  fn from_u64(n: u64) -> Option<Self> {
+    let error = match n {
+      10000 => ArbitrageError::InvalidInstruction,
+      10001 => ArbitrageError::InvalidCall,
+      10002 => ArbitrageError::InvalidOwner,
+      10003 => ArbitrageError::OutAmountSmallerThanInAmount,
+      10004 => ArbitrageError::UnauthorizedOperator,
+      10005 => ArbitrageError::EmptyOrderBook,
+      10006 => ArbitrageError::InvalidOrderBook,
+      10007 => ArbitrageError::UnsupportedCurve,
+      10008 => ArbitrageError::InvalidPoolState,
+      10009 => ArbitrageError::StrategyDisabled,
+      10010 => ArbitrageError::MathOverflow,
+      10011 => ArbitrageError::BalanceDecreased,
+      10012 => ArbitrageError::InvalidProgramId,
+      10013 => ArbitrageError::InvalidSysvar,
+      10014 => ArbitrageError::InvalidTokenAccount,
+      10015 => ArbitrageError::TokenOwnerMismatch,
+      10016 => ArbitrageError::MintMismatch,
+      10017 => ArbitrageError::SlippageExceeded,
+      10018 => ArbitrageError::ProfitBelowMinimum,
+      10019 => ArbitrageError::StaleState,
+      10020 => ArbitrageError::UnsupportedMarket,
+      10021 => ArbitrageError::PoolAccountMismatch,
+      10022 => ArbitrageError::ExchangeOwnerMismatch,
+      10023 => ArbitrageError::StrategyMismatch,
+      10024 => ArbitrageError::AccountNotWritable,
+      10025 => ArbitrageError::AmountBelowLotSize,
+      _ => return None,
+    };
+    Some(error)
+  }
+}
+
+impl<T> DecodeError<T> for ArbitrageError {
+  fn type_of() -> &'static str {
+    "ArbitrageError"
+  }
+}
+
+impl PrintProgramError for ArbitrageError {
+  fn print<E>(&self)
+  where
+    E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive,
+  {
+    msg!("Error: {}", self);
   }
 }
 
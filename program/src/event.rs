@@ -0,0 +1,226 @@
+//! Events the strategies log with `sol_log_data`, one field per event.
+//!
+//! Every event is `[EVENT_VERSION, tag, fields]` with little-endian integers,
+//! the layout of a tag never changes once released.
+
+use arrayref::{array_ref, array_refs};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+pub const EVENT_VERSION: u8 = 0xE1;
+
+/// prefix the runtime logs `sol_log_data` fields behind, base64 encoded
+pub const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// strategies without a params slot, the stable strategies log their slot
+pub const STRATEGY_ORCA_RAYDIUM_SERUM: u8 = 0x80;
+pub const STRATEGY_SERUM_SELECT: u8 = 0x81;
+pub const STRATEGY_SERUM_WHIRL: u8 = 0x82;
+pub const STRATEGY_WHIRL_SERUM: u8 = 0x83;
+pub const STRATEGY_PATH: u8 = 0x84;
+pub const STRATEGY_ROUTE: u8 = 0x85;
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub enum SkipReason {
+    /// `actual` is the state's last profit, `expected` the strategy threshold
+    BelowThreshold = 0,
+    /// `actual` is the quoted output, `expected` the input it was quoted for
+    QuoteBelowInput = 1,
+    /// `actual` is the serum depth in base units, `expected` one lot
+    BelowLotSize = 2,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArbitrageEvent {
+    /// one hop of a path or route, amounts measured on the user token accounts
+    HopExecuted {
+        strategy: u8,
+        hop: u8,
+        amount_in: u64,
+        amount_out: u64,
+    },
+    /// a strategy traded, balances of the token the cycle starts and ends in
+    CycleCompleted {
+        strategy: u8,
+        balance_before: u64,
+        balance_after: u64,
+        total_profit: u64,
+        total_lost: u64,
+    },
+    CycleSkipped {
+        strategy: u8,
+        reason: SkipReason,
+        expected: u64,
+        actual: u64,
+    },
+    /// the flagged check found more lost than earned, the transaction fails;
+    /// strategies with an exchange state report the run's amounts
+    ProfitCheckFailed {
+        strategy: u8,
+        total_profit: u64,
+        total_lost: u64,
+    },
+    /// a hop failed with `error`, the `ProgramError` as `u64`, on the venue
+    /// with `Market` tag `venue`
+    VenueFailed {
+        strategy: u8,
+        hop: u8,
+        venue: u8,
+        error: u64,
+    },
+}
+
+impl ArbitrageEvent {
+    const HOP_EXECUTED_LEN: usize = 18;
+    const CYCLE_COMPLETED_LEN: usize = 33;
+    const CYCLE_SKIPPED_LEN: usize = 18;
+    const PROFIT_CHECK_FAILED_LEN: usize = 17;
+    const VENUE_FAILED_LEN: usize = 11;
+
+    /// `None` for another version, an unknown tag or a length other than the tag's
+    pub fn unpack(input: &[u8]) -> Option<Self> {
+        let (&version, rest) = input.split_first()?;
+        if version != EVENT_VERSION {
+            return None;
+        }
+        let (&tag, rest) = rest.split_first()?;
+        Some(match (tag, rest.len()) {
+            (0, Self::HOP_EXECUTED_LEN) => {
+                let data = array_ref![rest, 0, Self::HOP_EXECUTED_LEN];
+                let (&[strategy], &[hop], amount_in, amount_out) = array_refs![data, 1, 1, 8, 8];
+                Self::HopExecuted {
+                    strategy: strategy,
+                    hop: hop,
+                    amount_in: u64::from_le_bytes(*amount_in),
+                    amount_out: u64::from_le_bytes(*amount_out),
+                }
+            }
+            (1, Self::CYCLE_COMPLETED_LEN) => {
+                let data = array_ref![rest, 0, Self::CYCLE_COMPLETED_LEN];
+                let (&[strategy], balance_before, balance_after, total_profit, total_lost) =
+                    array_refs![data, 1, 8, 8, 8, 8];
+                Self::CycleCompleted {
+                    strategy: strategy,
+                    balance_before: u64::from_le_bytes(*balance_before),
+                    balance_after: u64::from_le_bytes(*balance_after),
+                    total_profit: u64::from_le_bytes(*total_profit),
+                    total_lost: u64::from_le_bytes(*total_lost),
+                }
+            }
+            (2, Self::CYCLE_SKIPPED_LEN) => {
+                let data = array_ref![rest, 0, Self::CYCLE_SKIPPED_LEN];
+                let (&[strategy], &[reason], expected, actual) = array_refs![data, 1, 1, 8, 8];
+                let reason = match reason {
+                    0 => SkipReason::BelowThreshold,
+                    1 => SkipReason::QuoteBelowInput,
+                    2 => SkipReason::BelowLotSize,
+                    _ => return None,
+                };
+                Self::CycleSkipped {
+                    strategy: strategy,
+                    reason: reason,
+                    expected: u64::from_le_bytes(*expected),
+                    actual: u64::from_le_bytes(*actual),
+                }
+            }
+            (3, Self::PROFIT_CHECK_FAILED_LEN) => {
+                let data = array_ref![rest, 0, Self::PROFIT_CHECK_FAILED_LEN];
+                let (&[strategy], total_profit, total_lost) = array_refs![data, 1, 8, 8];
+                Self::ProfitCheckFailed {
+                    strategy: strategy,
+                    total_profit: u64::from_le_bytes(*total_profit),
+                    total_lost: u64::from_le_bytes(*total_lost),
+                }
+            }
+            (4, Self::VENUE_FAILED_LEN) => {
+                let data = array_ref![rest, 0, Self::VENUE_FAILED_LEN];
+                let (&[strategy], &[hop], &[venue], error) = array_refs![data, 1, 1, 1, 8];
+                Self::VenueFailed {
+                    strategy: strategy,
+                    hop: hop,
+                    venue: venue,
+                    error: u64::from_le_bytes(*error),
+                }
+            }
+            _ => return None,
+        })
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = vec![EVENT_VERSION];
+        match self {
+            Self::HopExecuted { strategy, hop, amount_in, amount_out } => {
+                buf.extend_from_slice(&[0, *strategy, *hop]);
+                buf.extend_from_slice(&amount_in.to_le_bytes());
+                buf.extend_from_slice(&amount_out.to_le_bytes());
+            }
+            Self::CycleCompleted { strategy, balance_before, balance_after, total_profit, total_lost } => {
+                buf.extend_from_slice(&[1, *strategy]);
+                buf.extend_from_slice(&balance_before.to_le_bytes());
+                buf.extend_from_slice(&balance_after.to_le_bytes());
+                buf.extend_from_slice(&total_profit.to_le_bytes());
+                buf.extend_from_slice(&total_lost.to_le_bytes());
+            }
+            Self::CycleSkipped { strategy, reason, expected, actual } => {
+                buf.extend_from_slice(&[2, *strategy, *reason as u8]);
+                buf.extend_from_slice(&expected.to_le_bytes());
+                buf.extend_from_slice(&actual.to_le_bytes());
+            }
+            Self::ProfitCheckFailed { strategy, total_profit, total_lost } => {
+                buf.extend_from_slice(&[3, *strategy]);
+                buf.extend_from_slice(&total_profit.to_le_bytes());
+                buf.extend_from_slice(&total_lost.to_le_bytes());
+            }
+            Self::VenueFailed { strategy, hop, venue, error } => {
+                buf.extend_from_slice(&[4, *strategy, *hop, *venue]);
+                buf.extend_from_slice(&error.to_le_bytes());
+            }
+        }
+        buf
+    }
+
+    pub fn emit(&self) {
+        sol_log_data(&[&self.pack()]);
+    }
+}
+
+/// the event of one `Program data: ` log line, `None` for any other line or
+/// data another program logged in a layout of its own
+pub fn decode_log(log: &str) -> Option<ArbitrageEvent> {
+    let fields = log.strip_prefix(PROGRAM_DATA_PREFIX)?;
+    // the program logs a single field per event
+    let mut fields = fields.split_whitespace();
+    let data = base64::decode(fields.next()?).ok()?;
+    if fields.next().is_some() {
+        return None;
+    }
+    ArbitrageEvent::unpack(&data)
+}
+
+/// events `program_id` logged in a transaction's logs, in order.
+///
+/// Programs the arbitrage program invokes log their own data lines, the
+/// `invoke`/`success`/`failed` lines around every call tell them apart.
+pub fn decode_logs<S: AsRef<str>>(program_id: &Pubkey, logs: &[S]) -> Vec<ArbitrageEvent> {
+    let program = program_id.to_string();
+    let mut invoked: Vec<&str> = vec![];
+    let mut events = vec![];
+    for log in logs.iter().map(|log| log.as_ref()) {
+        if log.starts_with(PROGRAM_DATA_PREFIX) {
+            if invoked.last() == Some(&program.as_str()) {
+                events.extend(decode_log(log));
+            }
+            continue;
+        }
+        let words: Vec<&str> = log.split_whitespace().collect();
+        match words.as_slice() {
+            // `Program log: ...` lines of any program read like these
+            ["Program", id, ..] if id.ends_with(':') => {}
+            ["Program", id, "invoke", depth] if depth.starts_with('[') => invoked.push(*id),
+            ["Program", _, "success"] | ["Program", _, "failed:", ..] => {
+                invoked.pop();
+            }
+            _ => {}
+        }
+    }
+    events
+}
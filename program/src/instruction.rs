@@ -1,18 +1,29 @@
-use solana_program::program_error::ProgramError;
-use crate::error::ArbitrageError;
+use solana_program::{
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use crate::{
+    error::ArbitrageError,
+    state::{is_exchange_strategy, MAX_OPERATORS, MAX_STRATEGIES},
+};
 use arrayref::{array_ref, array_refs};
 
+/// First byte of every instruction. Versions start above the legacy tag space
+/// (0..=170) so both layouts can be decoded side by side.
+pub const INSTRUCTION_VERSION: u8 = 0xF1;
+
 /// market
 #[derive(Clone, Debug, PartialEq, Copy)]
 pub enum Market {
-    Orca,
-    Saber,
-    Serum,
-    Raydium,
-    Mercurial_2pool,
-    Mercurial_3pool,
-    Mercurial_4pool,
-    Whirl,
+    Orca = 0,
+    Saber = 1,
+    Serum = 2,
+    Raydium = 3,
+    Mercurial_2pool = 4,
+    Mercurial_3pool = 5,
+    Mercurial_4pool = 6,
+    Whirl = 7,
+    Crema = 8,
 }
 
 impl Market {
@@ -26,6 +37,7 @@ impl Market {
             5 => Some(Market::Mercurial_3pool),
             6 => Some(Market::Mercurial_4pool),
             7 => Some(Market::Whirl),
+            8 => Some(Market::Crema),
             _ => None,
         }
     }
@@ -36,15 +48,34 @@ pub struct ExchangeWithPathInstruction {
     pub amount: u64,
     pub market: Market,
     pub side: u8,
+    /// forwarded to the dex; legacy payloads without it decode as 0
+    pub minimum_amount_out: u64,
 }
 
 impl ExchangeWithPathInstruction {
-    const DATA_LEN: usize = 11;
+    const LEGACY_DATA_LEN: usize = 11;
+    const DATA_LEN: usize = 19;
     fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
         if input.len() < ExchangeWithPathInstruction::DATA_LEN {
             return Err(ArbitrageError::InvalidInstruction.into());
         }
-        let arr_data = array_ref![input, 0, ExchangeWithPathInstruction::DATA_LEN];
+        let minimum_amount_out = u64::from_le_bytes(*array_ref![input, ExchangeWithPathInstruction::LEGACY_DATA_LEN, 8]);
+        ExchangeWithPathInstruction::unpack_with_minimum(input, minimum_amount_out)
+    }
+    #[cfg(feature = "legacy-tags")]
+    fn unpack_legacy(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() < ExchangeWithPathInstruction::LEGACY_DATA_LEN {
+            return Err(ArbitrageError::InvalidInstruction.into());
+        }
+        let minimum_amount_out = if input.len() >= ExchangeWithPathInstruction::DATA_LEN {
+            u64::from_le_bytes(*array_ref![input, ExchangeWithPathInstruction::LEGACY_DATA_LEN, 8])
+        } else {
+            0
+        };
+        ExchangeWithPathInstruction::unpack_with_minimum(input, minimum_amount_out)
+    }
+    fn unpack_with_minimum(input: &[u8], minimum_amount_out: u64) -> Result<Self, ProgramError> {
+        let arr_data = array_ref![input, 0, ExchangeWithPathInstruction::LEGACY_DATA_LEN];
         let (
             &[flag],
             &amount_arr,
@@ -55,35 +86,354 @@ impl ExchangeWithPathInstruction {
             ExchangeWithPathInstruction {
                 flag: flag,
                 amount: u64::from_le_bytes(amount_arr),
-                market: Market::from(market).ok_or(ProgramError::InvalidInstructionData)?,
+                market: Market::from(market).ok_or(ArbitrageError::UnsupportedMarket)?,
                 side: side,
+                minimum_amount_out: minimum_amount_out,
             }
         )
     }
+    fn pack(&self, dst: &mut Vec<u8>) {
+        dst.push(self.flag);
+        dst.extend_from_slice(&self.amount.to_le_bytes());
+        dst.push(self.market as u8);
+        dst.push(self.side);
+        dst.extend_from_slice(&self.minimum_amount_out.to_le_bytes());
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub struct RouteHop {
+    pub market: Market,
+    pub side: u8,
+    /// number of accounts this hop takes from the instruction accounts
+    pub accounts_len: u8,
+    /// forwarded to the dex, the hop fails if it receives less
+    pub minimum_amount_out: u64,
+}
+
+impl RouteHop {
+    const DATA_LEN: usize = 11;
+    fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() < RouteHop::DATA_LEN {
+            return Err(ArbitrageError::InvalidInstruction.into());
+        }
+        let arr_data = array_ref![input, 0, RouteHop::DATA_LEN];
+        let (
+            &[market],
+            &[side],
+            &[accounts_len],
+            &minimum_amount_out_arr,
+        ) = array_refs![arr_data, 1, 1, 1, 8];
+        Ok(
+            RouteHop {
+                market: Market::from(market).ok_or(ArbitrageError::UnsupportedMarket)?,
+                side: side,
+                accounts_len: accounts_len,
+                minimum_amount_out: u64::from_le_bytes(minimum_amount_out_arr),
+            }
+        )
+    }
+    fn pack(&self, dst: &mut Vec<u8>) {
+        dst.push(self.market as u8);
+        dst.push(self.side);
+        dst.push(self.accounts_len);
+        dst.extend_from_slice(&self.minimum_amount_out.to_le_bytes());
+    }
+}
+
+pub struct ExecuteRouteInstruction {
+    pub amount_in: u64,
+    pub hops: Vec<RouteHop>,
+}
+
+impl ExecuteRouteInstruction {
+    const HEADER_LEN: usize = 9;
+    pub const MAX_HOPS: usize = 8;
+    fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() < ExecuteRouteInstruction::HEADER_LEN {
+            return Err(ArbitrageError::InvalidInstruction.into());
+        }
+        let arr_data = array_ref![input, 0, ExecuteRouteInstruction::HEADER_LEN];
+        let (
+            &amount_in_arr,
+            &[hop_count],
+        ) = array_refs![arr_data, 8, 1];
+        let hop_count = hop_count as usize;
+        if hop_count == 0 || hop_count > ExecuteRouteInstruction::MAX_HOPS {
+            return Err(ArbitrageError::InvalidInstruction.into());
+        }
+        let hops_data = &input[ExecuteRouteInstruction::HEADER_LEN..];
+        if hops_data.len() < hop_count * RouteHop::DATA_LEN {
+            return Err(ArbitrageError::InvalidInstruction.into());
+        }
+        let hops = hops_data
+            .chunks(RouteHop::DATA_LEN)
+            .take(hop_count)
+            .map(RouteHop::unpack)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(
+            ExecuteRouteInstruction {
+                amount_in: u64::from_le_bytes(amount_in_arr),
+                hops: hops,
+            }
+        )
+    }
+    fn pack(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(&self.amount_in.to_le_bytes());
+        dst.push(self.hops.len() as u8);
+        for hop in self.hops.iter() {
+            hop.pack(dst);
+        }
+    }
+}
+
+pub struct ConfigInstruction {
+    pub admin: Pubkey,
+    pub operators: Vec<Pubkey>,
+}
+
+impl ConfigInstruction {
+    const HEADER_LEN: usize = 33;
+    fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() < ConfigInstruction::HEADER_LEN {
+            return Err(ArbitrageError::InvalidInstruction.into());
+        }
+        let arr_data = array_ref![input, 0, ConfigInstruction::HEADER_LEN];
+        let (
+            admin_arr,
+            &[operators_len],
+        ) = array_refs![arr_data, 32, 1];
+        let operators_len = operators_len as usize;
+        if operators_len > MAX_OPERATORS {
+            return Err(ArbitrageError::InvalidInstruction.into());
+        }
+        let operators_data = &input[ConfigInstruction::HEADER_LEN..];
+        if operators_data.len() < operators_len * 32 {
+            return Err(ArbitrageError::InvalidInstruction.into());
+        }
+        Ok(
+            ConfigInstruction {
+                admin: Pubkey::new_from_array(*admin_arr),
+                operators: operators_data
+                    .chunks(32)
+                    .take(operators_len)
+                    .map(Pubkey::new)
+                    .collect(),
+            }
+        )
+    }
+    fn pack(&self, dst: &mut Vec<u8>) {
+        dst.extend_from_slice(self.admin.as_ref());
+        dst.push(self.operators.len() as u8);
+        for operator in self.operators.iter() {
+            dst.extend_from_slice(operator.as_ref());
+        }
+    }
+}
+
+pub struct StrategyParamsInstruction {
+    pub index: u8,
+    pub enabled: bool,
+    pub input_amount: u64,
+    pub threshold: u64,
+    pub expected_profit: u64,
+}
+
+impl StrategyParamsInstruction {
+    const DATA_LEN: usize = 26;
+    fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() < StrategyParamsInstruction::DATA_LEN {
+            return Err(ArbitrageError::InvalidInstruction.into());
+        }
+        let arr_data = array_ref![input, 0, StrategyParamsInstruction::DATA_LEN];
+        let (
+            &[index],
+            &[enabled],
+            &input_amount_arr,
+            &threshold_arr,
+            &expected_profit_arr,
+        ) = array_refs![arr_data, 1, 1, 8, 8, 8];
+        // the handlers divide by both
+        let input_amount = u64::from_le_bytes(input_amount_arr);
+        let expected_profit = u64::from_le_bytes(expected_profit_arr);
+        if index >= MAX_STRATEGIES || input_amount == 0 || expected_profit == 0 {
+            return Err(ArbitrageError::InvalidInstruction.into());
+        }
+        Ok(
+            StrategyParamsInstruction {
+                index: index,
+                enabled: enabled != 0,
+                input_amount: input_amount,
+                threshold: u64::from_le_bytes(threshold_arr),
+                expected_profit: expected_profit,
+            }
+        )
+    }
+    fn pack(&self, dst: &mut Vec<u8>) {
+        dst.push(self.index);
+        dst.push(self.enabled as u8);
+        dst.extend_from_slice(&self.input_amount.to_le_bytes());
+        dst.extend_from_slice(&self.threshold.to_le_bytes());
+        dst.extend_from_slice(&self.expected_profit.to_le_bytes());
+    }
+}
+
+pub struct ExchangeStateInstruction {
+    pub strategy_id: u8,
+}
+
+impl ExchangeStateInstruction {
+    const DATA_LEN: usize = 1;
+    fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() < ExchangeStateInstruction::DATA_LEN || !is_exchange_strategy(input[0]) {
+            return Err(ArbitrageError::InvalidInstruction.into());
+        }
+        Ok(
+            ExchangeStateInstruction {
+                strategy_id: input[0],
+            }
+        )
+    }
+    fn pack(&self, dst: &mut Vec<u8>) {
+        dst.push(self.strategy_id);
+    }
 }
 
 pub struct ExchangeWithTryInstruction {
+    /// 100 (and 127 for orca/raydium/serum) fails the run when the run's losses
+    /// exceed its profits; no flag resets the state, see `ResetExchangeState`
     pub flag: u8,
+    /// starts a run: reseeds the trade size and clears the run's profit and loss,
+    /// the lifetime totals are kept
+    pub begin_run: bool,
+    /// 0 sizes the trade from the fixed tables, otherwise the solver searches
+    /// for the most profitable input up to this amount
+    pub max_amount_in: u64,
+    /// minimum output of the first and the second leg, forwarded to the dex or,
+    /// for serum, checked against the balance; legacy payloads decode them as 0
+    pub min_amounts_out: [u64; 2],
 }
 
 impl ExchangeWithTryInstruction {
-    const DATA_LEN: usize = 1;
+    #[cfg(feature = "legacy-tags")]
+    const LEGACY_DATA_LEN: usize = 1;
+    #[cfg(feature = "legacy-tags")]
+    const LEGACY_DATA_WITH_MAX_LEN: usize = 9;
+    const DATA_LEN: usize = 26;
     fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
         if input.len() < ExchangeWithTryInstruction::DATA_LEN {
             return Err(ArbitrageError::InvalidInstruction.into());
         }
         let arr_data = array_ref![input, 0, ExchangeWithTryInstruction::DATA_LEN];
-        /*
         let (
             &[flag],
-        ) = array_refs![arr_data, 1];
-        */
+            &[begin_run],
+            &max_amount_in,
+            &first_min_out,
+            &second_min_out,
+        ) = array_refs![arr_data, 1, 1, 8, 8, 8];
+        Ok(
+            ExchangeWithTryInstruction {
+                flag: flag,
+                begin_run: begin_run != 0,
+                max_amount_in: u64::from_le_bytes(max_amount_in),
+                min_amounts_out: [u64::from_le_bytes(first_min_out), u64::from_le_bytes(second_min_out)],
+            }
+        )
+    }
+    #[cfg(feature = "legacy-tags")]
+    fn unpack_legacy(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() < ExchangeWithTryInstruction::LEGACY_DATA_LEN {
+            return Err(ArbitrageError::InvalidInstruction.into());
+        }
+        let max_amount_in = if input.len() >= ExchangeWithTryInstruction::LEGACY_DATA_WITH_MAX_LEN {
+            u64::from_le_bytes(*array_ref![input, ExchangeWithTryInstruction::LEGACY_DATA_LEN, 8])
+        } else {
+            0
+        };
         Ok(
             ExchangeWithTryInstruction {
-                flag: arr_data[0],
+                flag: input[0],
+                // the flags that restarted a run before `begin_run`
+                begin_run: input[0] == 0 || input[0] == 127,
+                max_amount_in: max_amount_in,
+                min_amounts_out: [0; 2],
+            }
+        )
+    }
+    fn pack(&self, dst: &mut Vec<u8>) {
+        dst.push(self.flag);
+        dst.push(self.begin_run as u8);
+        dst.extend_from_slice(&self.max_amount_in.to_le_bytes());
+        for min_amount_out in self.min_amounts_out.iter() {
+            dst.extend_from_slice(&min_amount_out.to_le_bytes());
+        }
+    }
+}
+
+/// `Exchange_NonStable_Serum3/4`, the serum leg is checked against its balance
+/// since the order takes a limit price rather than a minimum
+pub struct ExchangeWithMinimumsInstruction {
+    /// minimum output of each of the three legs in swap order
+    pub min_amounts_out: [u64; 3],
+}
+
+impl ExchangeWithMinimumsInstruction {
+    const DATA_LEN: usize = 24;
+    fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() < ExchangeWithMinimumsInstruction::DATA_LEN {
+            return Err(ArbitrageError::InvalidInstruction.into());
+        }
+        Ok(
+            ExchangeWithMinimumsInstruction {
+                min_amounts_out: unpack_amounts(array_ref![input, 0, ExchangeWithMinimumsInstruction::DATA_LEN]),
+            }
+        )
+    }
+    fn pack(&self, dst: &mut Vec<u8>) {
+        pack_amounts(&self.min_amounts_out, dst);
+    }
+}
+
+/// `Exchange_NonStable_Serum1/2` picks its direction from the serum book, so it
+/// carries the leg minimums of both
+pub struct SerumSelectInstruction {
+    /// saber, orca and serum legs, run when the best bid is above orca
+    pub bid_min_amounts_out: [u64; 3],
+    /// serum, orca and saber legs, run when the best ask is below orca
+    pub ask_min_amounts_out: [u64; 3],
+}
+
+impl SerumSelectInstruction {
+    const DATA_LEN: usize = 48;
+    fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() < SerumSelectInstruction::DATA_LEN {
+            return Err(ArbitrageError::InvalidInstruction.into());
+        }
+        let arr_data = array_ref![input, 0, SerumSelectInstruction::DATA_LEN];
+        let (bid_arr, ask_arr) = array_refs![arr_data, 24, 24];
+        Ok(
+            SerumSelectInstruction {
+                bid_min_amounts_out: unpack_amounts(bid_arr),
+                ask_min_amounts_out: unpack_amounts(ask_arr),
             }
         )
     }
+    fn pack(&self, dst: &mut Vec<u8>) {
+        pack_amounts(&self.bid_min_amounts_out, dst);
+        pack_amounts(&self.ask_min_amounts_out, dst);
+    }
+}
+
+fn unpack_amounts(input: &[u8; 24]) -> [u64; 3] {
+    let (first, second, third) = array_refs![input, 8, 8, 8];
+    [u64::from_le_bytes(*first), u64::from_le_bytes(*second), u64::from_le_bytes(*third)]
+}
+
+fn pack_amounts(amounts: &[u64; 3], dst: &mut Vec<u8>) {
+    for amount in amounts.iter() {
+        dst.extend_from_slice(&amount.to_le_bytes());
+    }
 }
 
 pub enum ArbitrageInstruction {
@@ -94,19 +444,19 @@ pub enum ArbitrageInstruction {
     ///
     /// 
     /// 
-    Exchange_NonStable_Serum1(),
+    Exchange_NonStable_Serum1(SerumSelectInstruction),
     ///
     /// 
     /// 
-    Exchange_NonStable_Serum2(),
+    Exchange_NonStable_Serum2(SerumSelectInstruction),
     ///
     /// 
     /// 
-    Exchange_NonStable_Serum3(),
+    Exchange_NonStable_Serum3(ExchangeWithMinimumsInstruction),
     ///
     /// 
     /// 
-    Exchange_NonStable_Serum4(),     
+    Exchange_NonStable_Serum4(ExchangeWithMinimumsInstruction),     
     ///
     /// 
     /// 
@@ -238,54 +588,309 @@ pub enum ArbitrageInstruction {
     ///
     ///
     /// 
-    Exchange_Stable32(ExchangeWithTryInstruction),   
+    Exchange_Stable32(ExchangeWithTryInstruction),
+    /// Swap through every hop of the route in one instruction, feeding the
+    /// measured output of each hop into the next one.
+    ///
+    /// Accounts: the accounts of each hop, in order, laid out as for `Exchange_WithPath`
+    /// without the exchange state account.
+    ExecuteRoute(ExecuteRouteInstruction),
+    /// Create the config PDA (seed `"config"`) holding the admin and the operators.
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` payer
+    /// 1. `[writable]` config PDA
+    /// 2. `[]` system program
+    InitializeConfig(ConfigInstruction),
+    /// Replace the admin and the operators of the config.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` current admin
+    /// 1. `[writable]` config PDA
+    UpdateConfig(ConfigInstruction),
+    /// Create the params PDA of a strategy (seeds `"strategy", index`).
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` config admin, pays for the account
+    /// 1. `[]` config PDA
+    /// 2. `[writable]` strategy params PDA
+    /// 3. `[]` system program
+    InitializeStrategy(StrategyParamsInstruction),
+    /// Replace the params of a strategy.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` config admin
+    /// 1. `[]` config PDA
+    /// 2. `[writable]` strategy params PDA
+    UpdateStrategy(StrategyParamsInstruction),
+    /// Copy the amounts of a 32 byte exchange state into the exchange state PDA
+    /// of `owner` and close the old account. Legacy states record no owner, so
+    /// the config admin names it.
+    ///
+    /// The state is not upgraded in place: solana-program 1.9 cannot resize an
+    /// account, so the larger versioned state lives at a new address. After migrating,
+    /// callers pass `client::exchange_address(program_id, owner, strategy_id)`
+    /// instead of the legacy account.
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` config admin, pays for the PDA and receives the old rent
+    /// 1. `[]` config PDA
+    /// 2. `[writable]` legacy exchange state
+    /// 3. `[]` owner of the new state, a config operator
+    /// 4. `[writable]` exchange state PDA
+    /// 5. `[]` system program
+    MigrateExchangeState(ExchangeStateInstruction),
+    /// Create the exchange state PDA of a strategy for the signer (seeds
+    /// `"exchange", owner, strategy_id`).
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` operator, owner of the state and pays for it
+    /// 1. `[]` config PDA
+    /// 2. `[writable]` exchange state PDA
+    /// 3. `[]` system program
+    InitializeExchangeState(ExchangeStateInstruction),
+    /// Clear the amounts and counters of an exchange state.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` owner of the state
+    /// 1. `[writable]` exchange state
+    ResetExchangeState(ExchangeStateInstruction),
+    /// Close an exchange state, its lamports go to the destination.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` owner of the state
+    /// 1. `[writable]` exchange state
+    /// 2. `[writable]` destination of the rent
+    CloseExchangeState(ExchangeStateInstruction),
 }
 
 impl ArbitrageInstruction {
-    /// Unpacks a byte buffer into a [EscrowInstruction](enum.EscrowInstruction.html).
+    /// Unpacks a byte buffer into a [ArbitrageInstruction](enum.ArbitrageInstruction.html).
+    ///
+    /// Layout: `[version, discriminant, payload..]`. With the `legacy-tags` feature,
+    /// buffers that do not start with a known version are decoded with the legacy tag ranges.
     pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let (version, rest) = input.split_first().ok_or(ArbitrageError::InvalidInstruction)?;
+        match *version {
+            INSTRUCTION_VERSION => Self::unpack_v1(rest),
+            #[cfg(feature = "legacy-tags")]
+            _ => Self::unpack_legacy(input),
+            #[cfg(not(feature = "legacy-tags"))]
+            _ => Err(ArbitrageError::InvalidInstruction.into()),
+        }
+    }
+
+    fn unpack_v1(input: &[u8]) -> Result<Self, ProgramError> {
+        let (discriminant, rest) = input.split_first().ok_or(ArbitrageError::InvalidInstruction)?;
+        Ok(match discriminant {
+            0 => Self::Exchange_NonStable_All(ExchangeWithTryInstruction::unpack(rest)?),
+            1 => Self::Exchange_NonStable_Serum1(SerumSelectInstruction::unpack(rest)?),
+            2 => Self::Exchange_NonStable_Serum2(SerumSelectInstruction::unpack(rest)?),
+            3 => Self::Exchange_NonStable_Serum3(ExchangeWithMinimumsInstruction::unpack(rest)?),
+            4 => Self::Exchange_NonStable_Serum4(ExchangeWithMinimumsInstruction::unpack(rest)?),
+            5 => Self::Exchange_WithPath(ExchangeWithPathInstruction::unpack(rest)?),
+            6 => Self::Exchange_Stable1(ExchangeWithTryInstruction::unpack(rest)?),
+            7 => Self::Exchange_Stable2(ExchangeWithTryInstruction::unpack(rest)?),
+            8 => Self::Exchange_Stable3(ExchangeWithTryInstruction::unpack(rest)?),
+            9 => Self::Exchange_Stable4(ExchangeWithTryInstruction::unpack(rest)?),
+            10 => Self::Exchange_Stable5(ExchangeWithTryInstruction::unpack(rest)?),
+            11 => Self::Exchange_Stable6(ExchangeWithTryInstruction::unpack(rest)?),
+            12 => Self::Exchange_Stable7(ExchangeWithTryInstruction::unpack(rest)?),
+            13 => Self::Exchange_Stable8(ExchangeWithTryInstruction::unpack(rest)?),
+            14 => Self::Exchange_Stable9(ExchangeWithTryInstruction::unpack(rest)?),
+            15 => Self::Exchange_Stable10(ExchangeWithTryInstruction::unpack(rest)?),
+            16 => Self::Exchange_Stable11(ExchangeWithTryInstruction::unpack(rest)?),
+            17 => Self::Exchange_Stable12(ExchangeWithTryInstruction::unpack(rest)?),
+            18 => Self::Exchange_Stable13(ExchangeWithTryInstruction::unpack(rest)?),
+            19 => Self::Exchange_Stable14(ExchangeWithTryInstruction::unpack(rest)?),
+            20 => Self::Exchange_Stable15(ExchangeWithTryInstruction::unpack(rest)?),
+            21 => Self::Exchange_Stable16(ExchangeWithTryInstruction::unpack(rest)?),
+            22 => Self::Exchange_Stable17(ExchangeWithTryInstruction::unpack(rest)?),
+            23 => Self::Exchange_Stable18(ExchangeWithTryInstruction::unpack(rest)?),
+            24 => Self::Exchange_Stable19(ExchangeWithTryInstruction::unpack(rest)?),
+            25 => Self::Exchange_Stable20(ExchangeWithTryInstruction::unpack(rest)?),
+            26 => Self::Exchange_Stable21(ExchangeWithTryInstruction::unpack(rest)?),
+            27 => Self::Exchange_Stable22(ExchangeWithTryInstruction::unpack(rest)?),
+            28 => Self::Exchange_Stable23(ExchangeWithTryInstruction::unpack(rest)?),
+            29 => Self::Exchange_Stable24(ExchangeWithTryInstruction::unpack(rest)?),
+            30 => Self::Exchange_Stable25(ExchangeWithTryInstruction::unpack(rest)?),
+            31 => Self::Exchange_Stable26(ExchangeWithTryInstruction::unpack(rest)?),
+            32 => Self::Exchange_Stable27(ExchangeWithTryInstruction::unpack(rest)?),
+            33 => Self::Exchange_Stable28(ExchangeWithTryInstruction::unpack(rest)?),
+            34 => Self::Exchange_Stable29(ExchangeWithTryInstruction::unpack(rest)?),
+            35 => Self::Exchange_Stable30(ExchangeWithTryInstruction::unpack(rest)?),
+            36 => Self::Exchange_Stable31(ExchangeWithTryInstruction::unpack(rest)?),
+            37 => Self::Exchange_Stable32(ExchangeWithTryInstruction::unpack(rest)?),
+            38 => Self::ExecuteRoute(ExecuteRouteInstruction::unpack(rest)?),
+            39 => Self::InitializeConfig(ConfigInstruction::unpack(rest)?),
+            40 => Self::UpdateConfig(ConfigInstruction::unpack(rest)?),
+            41 => Self::InitializeStrategy(StrategyParamsInstruction::unpack(rest)?),
+            42 => Self::UpdateStrategy(StrategyParamsInstruction::unpack(rest)?),
+            43 => Self::MigrateExchangeState(ExchangeStateInstruction::unpack(rest)?),
+            44 => Self::InitializeExchangeState(ExchangeStateInstruction::unpack(rest)?),
+            45 => Self::ResetExchangeState(ExchangeStateInstruction::unpack(rest)?),
+            46 => Self::CloseExchangeState(ExchangeStateInstruction::unpack(rest)?),
+            _ => return Err(ArbitrageError::InvalidInstruction.into()),
+        })
+    }
+
+    /// Decodes the tag ranges used before the versioned layout. Overlapping ranges
+    /// resolve to the arm that used to match first, so tag 10 is `Exchange_NonStable_All`,
+    /// tag 30 is `Exchange_WithPath`, tag 40 is `Exchange_Stable1` and so on.
+    /// `ExecuteRoute` and the config, strategy and exchange state instructions only
+    /// exist in the versioned layout.
+    #[cfg(feature = "legacy-tags")]
+    fn unpack_legacy(input: &[u8]) -> Result<Self, ProgramError> {
         let (tag, rest) = input.split_first().ok_or(ArbitrageError::InvalidInstruction)?;
         Ok(match tag {
-            0..=10 => Self::Exchange_NonStable_All(ExchangeWithTryInstruction::unpack(rest)?),
-            10 => Self::Exchange_NonStable_Serum1(),
-            11 => Self::Exchange_NonStable_Serum2(),
-            12 => Self::Exchange_NonStable_Serum3(),
-            13 => Self::Exchange_NonStable_Serum4(),
-            20..=30 => Self::Exchange_WithPath(ExchangeWithPathInstruction::unpack(rest)?),
-            30..=40 => Self::Exchange_Stable1(ExchangeWithTryInstruction::unpack(rest)?),
-            40..=50 => Self::Exchange_Stable2(ExchangeWithTryInstruction::unpack(rest)?),
-            50..=60 => Self::Exchange_Stable3(ExchangeWithTryInstruction::unpack(rest)?),
-            60..=70 => Self::Exchange_Stable4(ExchangeWithTryInstruction::unpack(rest)?),
-            70..=80 => Self::Exchange_Stable5(ExchangeWithTryInstruction::unpack(rest)?),
-            80..=90 => Self::Exchange_Stable6(ExchangeWithTryInstruction::unpack(rest)?),
-            90..=100 => Self::Exchange_Stable7(ExchangeWithTryInstruction::unpack(rest)?),
-            100..=110 => Self::Exchange_Stable8(ExchangeWithTryInstruction::unpack(rest)?),
-            110..=120 => Self::Exchange_Stable9(ExchangeWithTryInstruction::unpack(rest)?),
-            120..=130 => Self::Exchange_Stable10(ExchangeWithTryInstruction::unpack(rest)?),
-            130..=140 => Self::Exchange_Stable11(ExchangeWithTryInstruction::unpack(rest)?),
-            140..=150 => Self::Exchange_Stable12(ExchangeWithTryInstruction::unpack(rest)?),
-            151 => Self::Exchange_Stable13(ExchangeWithTryInstruction::unpack(rest)?),
-            152 => Self::Exchange_Stable14(ExchangeWithTryInstruction::unpack(rest)?),
-            153 => Self::Exchange_Stable15(ExchangeWithTryInstruction::unpack(rest)?),
-            154 => Self::Exchange_Stable16(ExchangeWithTryInstruction::unpack(rest)?),
-            155 => Self::Exchange_Stable17(ExchangeWithTryInstruction::unpack(rest)?),
-            156 => Self::Exchange_Stable18(ExchangeWithTryInstruction::unpack(rest)?),
-            157 => Self::Exchange_Stable19(ExchangeWithTryInstruction::unpack(rest)?),
-            158 => Self::Exchange_Stable20(ExchangeWithTryInstruction::unpack(rest)?),
-            159 => Self::Exchange_Stable21(ExchangeWithTryInstruction::unpack(rest)?),
-            160 => Self::Exchange_Stable22(ExchangeWithTryInstruction::unpack(rest)?),
-            161 => Self::Exchange_Stable23(ExchangeWithTryInstruction::unpack(rest)?),
-            162 => Self::Exchange_Stable24(ExchangeWithTryInstruction::unpack(rest)?),
-            163 => Self::Exchange_Stable25(ExchangeWithTryInstruction::unpack(rest)?),
-            164 => Self::Exchange_Stable26(ExchangeWithTryInstruction::unpack(rest)?),
-            165 => Self::Exchange_Stable27(ExchangeWithTryInstruction::unpack(rest)?),
-            166 => Self::Exchange_Stable28(ExchangeWithTryInstruction::unpack(rest)?),
-            167 => Self::Exchange_Stable29(ExchangeWithTryInstruction::unpack(rest)?),
-            168 => Self::Exchange_Stable30(ExchangeWithTryInstruction::unpack(rest)?),
-            169 => Self::Exchange_Stable31(ExchangeWithTryInstruction::unpack(rest)?),
-            170 => Self::Exchange_Stable32(ExchangeWithTryInstruction::unpack(rest)?),
+            0..=10 => Self::Exchange_NonStable_All(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            11 => Self::Exchange_NonStable_Serum2(SerumSelectInstruction { bid_min_amounts_out: [0; 3], ask_min_amounts_out: [0; 3] }),
+            12 => Self::Exchange_NonStable_Serum3(ExchangeWithMinimumsInstruction { min_amounts_out: [0; 3] }),
+            13 => Self::Exchange_NonStable_Serum4(ExchangeWithMinimumsInstruction { min_amounts_out: [0; 3] }),
+            20..=30 => Self::Exchange_WithPath(ExchangeWithPathInstruction::unpack_legacy(rest)?),
+            31..=40 => Self::Exchange_Stable1(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            41..=50 => Self::Exchange_Stable2(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            51..=60 => Self::Exchange_Stable3(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            61..=70 => Self::Exchange_Stable4(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            71..=80 => Self::Exchange_Stable5(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            81..=90 => Self::Exchange_Stable6(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            91..=100 => Self::Exchange_Stable7(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            101..=110 => Self::Exchange_Stable8(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            111..=120 => Self::Exchange_Stable9(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            121..=130 => Self::Exchange_Stable10(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            131..=140 => Self::Exchange_Stable11(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            141..=150 => Self::Exchange_Stable12(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            151 => Self::Exchange_Stable13(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            152 => Self::Exchange_Stable14(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            153 => Self::Exchange_Stable15(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            154 => Self::Exchange_Stable16(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            155 => Self::Exchange_Stable17(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            156 => Self::Exchange_Stable18(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            157 => Self::Exchange_Stable19(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            158 => Self::Exchange_Stable20(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            159 => Self::Exchange_Stable21(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            160 => Self::Exchange_Stable22(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            161 => Self::Exchange_Stable23(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            162 => Self::Exchange_Stable24(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            163 => Self::Exchange_Stable25(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            164 => Self::Exchange_Stable26(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            165 => Self::Exchange_Stable27(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            166 => Self::Exchange_Stable28(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            167 => Self::Exchange_Stable29(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            168 => Self::Exchange_Stable30(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            169 => Self::Exchange_Stable31(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            170 => Self::Exchange_Stable32(ExchangeWithTryInstruction::unpack_legacy(rest)?),
+            // briefly `ExecuteRoute` before the versioned layout, never reused
+            171 => return Err(ArbitrageError::InvalidInstruction.into()),
             _ => return Err(ArbitrageError::InvalidInstruction.into()),
         })
     }
+
+    pub fn discriminant(&self) -> u8 {
+        match self {
+            Self::Exchange_NonStable_All(_) => 0,
+            Self::Exchange_NonStable_Serum1(_) => 1,
+            Self::Exchange_NonStable_Serum2(_) => 2,
+            Self::Exchange_NonStable_Serum3(_) => 3,
+            Self::Exchange_NonStable_Serum4(_) => 4,
+            Self::Exchange_WithPath(_) => 5,
+            Self::Exchange_Stable1(_) => 6,
+            Self::Exchange_Stable2(_) => 7,
+            Self::Exchange_Stable3(_) => 8,
+            Self::Exchange_Stable4(_) => 9,
+            Self::Exchange_Stable5(_) => 10,
+            Self::Exchange_Stable6(_) => 11,
+            Self::Exchange_Stable7(_) => 12,
+            Self::Exchange_Stable8(_) => 13,
+            Self::Exchange_Stable9(_) => 14,
+            Self::Exchange_Stable10(_) => 15,
+            Self::Exchange_Stable11(_) => 16,
+            Self::Exchange_Stable12(_) => 17,
+            Self::Exchange_Stable13(_) => 18,
+            Self::Exchange_Stable14(_) => 19,
+            Self::Exchange_Stable15(_) => 20,
+            Self::Exchange_Stable16(_) => 21,
+            Self::Exchange_Stable17(_) => 22,
+            Self::Exchange_Stable18(_) => 23,
+            Self::Exchange_Stable19(_) => 24,
+            Self::Exchange_Stable20(_) => 25,
+            Self::Exchange_Stable21(_) => 26,
+            Self::Exchange_Stable22(_) => 27,
+            Self::Exchange_Stable23(_) => 28,
+            Self::Exchange_Stable24(_) => 29,
+            Self::Exchange_Stable25(_) => 30,
+            Self::Exchange_Stable26(_) => 31,
+            Self::Exchange_Stable27(_) => 32,
+            Self::Exchange_Stable28(_) => 33,
+            Self::Exchange_Stable29(_) => 34,
+            Self::Exchange_Stable30(_) => 35,
+            Self::Exchange_Stable31(_) => 36,
+            Self::Exchange_Stable32(_) => 37,
+            Self::ExecuteRoute(_) => 38,
+            Self::InitializeConfig(_) => 39,
+            Self::UpdateConfig(_) => 40,
+            Self::InitializeStrategy(_) => 41,
+            Self::UpdateStrategy(_) => 42,
+            Self::MigrateExchangeState(_) => 43,
+            Self::InitializeExchangeState(_) => 44,
+            Self::ResetExchangeState(_) => 45,
+            Self::CloseExchangeState(_) => 46,
+        }
+    }
+
+    /// Packs the instruction with the current versioned layout.
+    pub fn pack(&self) -> Vec<u8> {
+        let mut buf = vec![INSTRUCTION_VERSION, self.discriminant()];
+        match self {
+            Self::Exchange_NonStable_All(data) => data.pack(&mut buf),
+            Self::Exchange_NonStable_Serum1(data) => data.pack(&mut buf),
+            Self::Exchange_NonStable_Serum2(data) => data.pack(&mut buf),
+            Self::Exchange_NonStable_Serum3(data) => data.pack(&mut buf),
+            Self::Exchange_NonStable_Serum4(data) => data.pack(&mut buf),
+            Self::Exchange_WithPath(data) => data.pack(&mut buf),
+            Self::Exchange_Stable1(data) => data.pack(&mut buf),
+            Self::Exchange_Stable2(data) => data.pack(&mut buf),
+            Self::Exchange_Stable3(data) => data.pack(&mut buf),
+            Self::Exchange_Stable4(data) => data.pack(&mut buf),
+            Self::Exchange_Stable5(data) => data.pack(&mut buf),
+            Self::Exchange_Stable6(data) => data.pack(&mut buf),
+            Self::Exchange_Stable7(data) => data.pack(&mut buf),
+            Self::Exchange_Stable8(data) => data.pack(&mut buf),
+            Self::Exchange_Stable9(data) => data.pack(&mut buf),
+            Self::Exchange_Stable10(data) => data.pack(&mut buf),
+            Self::Exchange_Stable11(data) => data.pack(&mut buf),
+            Self::Exchange_Stable12(data) => data.pack(&mut buf),
+            Self::Exchange_Stable13(data) => data.pack(&mut buf),
+            Self::Exchange_Stable14(data) => data.pack(&mut buf),
+            Self::Exchange_Stable15(data) => data.pack(&mut buf),
+            Self::Exchange_Stable16(data) => data.pack(&mut buf),
+            Self::Exchange_Stable17(data) => data.pack(&mut buf),
+            Self::Exchange_Stable18(data) => data.pack(&mut buf),
+            Self::Exchange_Stable19(data) => data.pack(&mut buf),
+            Self::Exchange_Stable20(data) => data.pack(&mut buf),
+            Self::Exchange_Stable21(data) => data.pack(&mut buf),
+            Self::Exchange_Stable22(data) => data.pack(&mut buf),
+            Self::Exchange_Stable23(data) => data.pack(&mut buf),
+            Self::Exchange_Stable24(data) => data.pack(&mut buf),
+            Self::Exchange_Stable25(data) => data.pack(&mut buf),
+            Self::Exchange_Stable26(data) => data.pack(&mut buf),
+            Self::Exchange_Stable27(data) => data.pack(&mut buf),
+            Self::Exchange_Stable28(data) => data.pack(&mut buf),
+            Self::Exchange_Stable29(data) => data.pack(&mut buf),
+            Self::Exchange_Stable30(data) => data.pack(&mut buf),
+            Self::Exchange_Stable31(data) => data.pack(&mut buf),
+            Self::Exchange_Stable32(data) => data.pack(&mut buf),
+            Self::ExecuteRoute(data) => data.pack(&mut buf),
+            Self::InitializeConfig(data) => data.pack(&mut buf),
+            Self::UpdateConfig(data) => data.pack(&mut buf),
+            Self::InitializeStrategy(data) => data.pack(&mut buf),
+            Self::UpdateStrategy(data) => data.pack(&mut buf),
+            Self::MigrateExchangeState(data) => data.pack(&mut buf),
+            Self::InitializeExchangeState(data) => data.pack(&mut buf),
+            Self::ResetExchangeState(data) => data.pack(&mut buf),
+            Self::CloseExchangeState(data) => data.pack(&mut buf),
+        }
+        buf
+    }
 }
 
@@ -1,9 +1,22 @@
 
+pub mod adapter;
+pub mod decimals;
 pub mod error;
+pub mod event;
 pub mod instruction;
+pub mod math;
 pub mod processor;
+pub mod quote;
+pub mod serum_book;
+pub mod solver;
+pub mod stable_quote;
 pub mod state;
+pub mod validation;
+pub mod whirl_quote;
 
 #[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
 
+
+#[cfg(feature = "no-entrypoint")]
+pub mod client;
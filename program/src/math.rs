@@ -0,0 +1,63 @@
+#![allow(clippy::assign_op_pattern)]
+#![allow(clippy::ptr_offset_with_cast)]
+#![allow(clippy::manual_range_contains)]
+
+use solana_program::program_error::ProgramError;
+use uint::construct_uint;
+
+use std::convert::TryFrom;
+
+use crate::error::ArbitrageError;
+
+construct_uint! {
+  /// intermediate products of the quote engines
+  pub struct U256(4);
+}
+
+/// `None` when `value` does not fit
+pub fn to_u128(value: U256) -> Option<u128> {
+  if value.bits() > 128 { None } else { Some(value.as_u128()) }
+}
+
+/// `a + b` of balances and accumulators
+pub fn checked_add(a: u64, b: u64) -> Result<u64, ProgramError> {
+  a.checked_add(b).ok_or_else(|| ArbitrageError::MathOverflow.into())
+}
+
+/// `a * b` of sizes and thresholds
+pub fn checked_mul(a: u64, b: u64) -> Result<u64, ProgramError> {
+  a.checked_mul(b).ok_or_else(|| ArbitrageError::MathOverflow.into())
+}
+
+/// `a / b`, a zero `b` is reported rather than panicking
+pub fn checked_div(a: u64, b: u64) -> Result<u64, ProgramError> {
+  a.checked_div(b).ok_or_else(|| ArbitrageError::MathOverflow.into())
+}
+
+/// `value * numerator / denominator` over a u128 product
+pub fn mul_div(value: u64, numerator: u64, denominator: u64) -> Result<u64, ProgramError> {
+  (value as u128 * numerator as u128)
+    .checked_div(denominator as u128)
+    .and_then(|result| u64::try_from(result).ok())
+    .ok_or_else(|| ArbitrageError::MathOverflow.into())
+}
+
+/// what a swap paid into a balance, `BalanceDecreased` when it shrank instead
+pub fn balance_increase(before: u64, after: u64) -> Result<u64, ProgramError> {
+  after.checked_sub(before).ok_or_else(|| ArbitrageError::BalanceDecreased.into())
+}
+
+/// `balance_increase` of a venue without a minimum in its own instruction,
+/// `SlippageExceeded` when it paid less than `minimum_amount_out`
+pub fn balance_increase_at_least(before: u64, after: u64, minimum_amount_out: u64) -> Result<u64, ProgramError> {
+  let amount_out = balance_increase(before, after)?;
+  if amount_out < minimum_amount_out {
+    return Err(ArbitrageError::SlippageExceeded.into());
+  }
+  Ok(amount_out)
+}
+
+/// what a swap spent from a balance
+pub fn balance_decrease(before: u64, after: u64) -> Result<u64, ProgramError> {
+  before.checked_sub(after).ok_or_else(|| ArbitrageError::MathOverflow.into())
+}
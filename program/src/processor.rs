@@ -2,43 +2,49 @@ use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
     msg,
-    program::{invoke},
+    program::{invoke, invoke_signed},
     program_pack::{Pack},
     pubkey::Pubkey,
     instruction::{AccountMeta, Instruction},
     clock::Clock,
     sysvar::Sysvar,
+    program_error::ProgramError,
+    rent::Rent,
+    system_instruction,
 };
 
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 
-use std::ops::DerefMut;
-use std::str::FromStr;
-
-use serum_dex::{
-  matching::Side,
-  critbit::SlabView,
-  state::{MarketState},
-};
+use serum_dex::matching::Side;
 
 use spl_token::state::Account as TokenAccount;
 
 use crate::{
-  instruction::{ArbitrageInstruction, ExchangeWithPathInstruction, ExchangeWithTryInstruction, Market},
+  adapter::{swap_with_market, venue_error, CremaAdapter, DexAdapter, MercurialAdapter, OrcaAdapter, RaydiumAdapter, SaberAdapter, SerumAdapter, WhirlAdapter},
+  decimals::MintDecimals,
+  instruction::{ArbitrageInstruction, ConfigInstruction, ExchangeStateInstruction, ExchangeWithMinimumsInstruction, ExchangeWithPathInstruction, ExchangeWithTryInstruction, ExecuteRouteInstruction, Market, SerumSelectInstruction, StrategyParamsInstruction},
   error::{ArbitrageError},
-  state::{ExchangeState},
+  event::{ArbitrageEvent, SkipReason, STRATEGY_ORCA_RAYDIUM_SERUM, STRATEGY_PATH, STRATEGY_ROUTE, STRATEGY_SERUM_SELECT, STRATEGY_SERUM_WHIRL, STRATEGY_WHIRL_SERUM},
+  math::{balance_decrease, balance_increase, balance_increase_at_least, checked_add, checked_div, checked_mul, mul_div},
+  quote::ConstantProductPool,
+  serum_book::SerumBookReader,
+  solver::{optimal_input, SOLVER_MAX_ITERATIONS},
+  stable_quote::{MercurialPool, SaberMercurialCycle, SaberPool},
+  state::{Config, ExchangeState, StrategyParams, CONFIG_SEED, EXCHANGE_SEED, MAX_OPERATORS, STRATEGY_SEED},
+  validation,
+  whirl_quote::{MAX_SQRT_PRICE, MIN_SQRT_PRICE},
 };
 
 use std::{num::NonZeroU64};
 
-const normal_input_amount_all: [u64;16] = [2500000000, 100000000, 2500000000, 2500000000, 2500000000, 2500000000, 2500000000, 2500000000, 2500000000, 100000000, 2500000000, 2500000000, 2500000000, 2500000000, 2500000000, 2500000000];
-const threshold_base_all: [u64;16] = [20000000, 20000, 5000, 5000, 500000, 500000, 500000, 500000, 20000000, 20000, 5000, 5000, 500000, 500000, 2500000, 2500000];
-const expected_profit_base_all: [u64;16] = [80000000, 100000, 100000, 100000, 2500000, 500000, 500000, 500000, 80000000, 100000, 100000, 100000, 2500000, 500000, 25000000, 25000000];
+/// price limit of the depth walk in the strategies without a reference venue, 10 bps past the best price keeps the fill near the top of the book
+const SERUM_DEPTH_BAND_BPS: u64 = 10;
 
 pub struct Processor;
 
 impl Processor {
   pub fn process(
+      program_id: &Pubkey,
       accounts: &[AccountInfo],
       instruction_data: &[u8],
   ) -> ProgramResult {
@@ -46,653 +52,547 @@ impl Processor {
     match instruction {
       ArbitrageInstruction::Exchange_NonStable_All (data) => {
         msg!("Instruction: orca raydium v2");
-        Self::process_orca_raydium_serum_exchange(accounts, &data)
+        Self::process_orca_raydium_serum_exchange(program_id, accounts, &data)
       }
-      ArbitrageInstruction::Exchange_NonStable_Serum1 () => {
+      ArbitrageInstruction::Exchange_NonStable_Serum1 (data) => {
         msg!("Instruction: serum select Exchange v1");
-        Self::process_exchange_serum_select(accounts)
+        Self::process_exchange_serum_select(program_id, accounts, &data)
       }
-      ArbitrageInstruction::Exchange_NonStable_Serum2 () => {
+      ArbitrageInstruction::Exchange_NonStable_Serum2 (data) => {
         msg!("Instruction: serum select Exchange v2");
-        Self::process_exchange_serum_select(accounts)
+        Self::process_exchange_serum_select(program_id, accounts, &data)
       }
-      ArbitrageInstruction::Exchange_NonStable_Serum3 () => {
+      ArbitrageInstruction::Exchange_NonStable_Serum3 (data) => {
         msg!("Instruction: serum no select Exchange v1");
-        Self::process_exchange_serum_whirl_noselect(accounts)
+        Self::process_exchange_serum_whirl_noselect(program_id, accounts, &data)
       }
-      ArbitrageInstruction::Exchange_NonStable_Serum4 () => {
+      ArbitrageInstruction::Exchange_NonStable_Serum4 (data) => {
         msg!("Instruction: serum no select Exchange v2");
-        Self::process_exchange_whirl_serum_noselect(accounts)
+        Self::process_exchange_whirl_serum_noselect(program_id, accounts, &data)
       }
       ArbitrageInstruction::Exchange_WithPath (data) => {
         msg!("Instruction: path Exchange v1");
-        Self::process_path_exchange(accounts, &data)
+        Self::process_path_exchange(program_id, accounts, &data)
       }
       ArbitrageInstruction::Exchange_Stable1 (data) => {
         msg!("Instruction: saber/mercurial 3pool Exchange v1");
-        Self::process_exchange_saber_mercurial_3pool(accounts, &data, 0)
+        Self::process_exchange_saber_mercurial_3pool(program_id, accounts, &data, 0)
       }
       ArbitrageInstruction::Exchange_Stable2 (data) => {
         msg!("Instruction: saber/mercurial 3pool Exchange v2");
-        Self::process_exchange_mercurial_saber_3pool(accounts, &data, 0)
+        Self::process_exchange_mercurial_saber_3pool(program_id, accounts, &data, 0)
       }
       ArbitrageInstruction::Exchange_Stable3 (data) => {
         msg!("Instruction: saber/mercurial 3pool Exchange v1");
-        Self::process_exchange_saber_mercurial_3pool(accounts, &data, 1)
+        Self::process_exchange_saber_mercurial_3pool(program_id, accounts, &data, 1)
       }
       ArbitrageInstruction::Exchange_Stable4 (data) => {
         msg!("Instruction: saber/mercurial 3pool Exchange v2");
-        Self::process_exchange_mercurial_saber_3pool(accounts, &data, 1)
+        Self::process_exchange_mercurial_saber_3pool(program_id, accounts, &data, 1)
       }
       ArbitrageInstruction::Exchange_Stable5 (data) => {
         msg!("Instruction: saber/mercurial 2pool Exchange v1");
-        Self::process_exchange_saber_mercurial_2pool(accounts, &data, 2)
+        Self::process_exchange_saber_mercurial_2pool(program_id, accounts, &data, 2)
       }
       ArbitrageInstruction::Exchange_Stable6 (data) => {
         msg!("Instruction: saber/mercurial 2pool Exchange v2");
-        Self::process_exchange_mercurial_saber_2pool(accounts, &data, 2)
+        Self::process_exchange_mercurial_saber_2pool(program_id, accounts, &data, 2)
       }
       ArbitrageInstruction::Exchange_Stable7 (data) => {
         msg!("Instruction: saber/mercurial 2pool Exchange v1");
-        Self::process_exchange_saber_mercurial_2pool(accounts, &data, 3)
+        Self::process_exchange_saber_mercurial_2pool(program_id, accounts, &data, 3)
       }
       ArbitrageInstruction::Exchange_Stable8 (data) => {
         msg!("Instruction: saber/mercurial 2pool Exchange v2");
-        Self::process_exchange_mercurial_saber_2pool(accounts, &data, 3)
+        Self::process_exchange_mercurial_saber_2pool(program_id, accounts, &data, 3)
       }
       ArbitrageInstruction::Exchange_Stable9 (data) => {
         msg!("Instruction: saber/mercurial 4pool Exchange v1");
-        Self::process_exchange_saber_mercurial_4pool(accounts, &data, 4)
+        Self::process_exchange_saber_mercurial_4pool(program_id, accounts, &data, 4)
       }
       ArbitrageInstruction::Exchange_Stable10 (data) => {
         msg!("Instruction: saber/mercurial 4pool Exchange v2");
-        Self::process_exchange_mercurial_saber_4pool(accounts, &data, 4)
+        Self::process_exchange_mercurial_saber_4pool(program_id, accounts, &data, 4)
       }
       ArbitrageInstruction::Exchange_Stable11 (data) => {
         msg!("Instruction: saber/mercurial 4pool Exchange v1");
-        Self::process_exchange_saber_mercurial_4pool(accounts, &data, 5)
+        Self::process_exchange_saber_mercurial_4pool(program_id, accounts, &data, 5)
       }
       ArbitrageInstruction::Exchange_Stable12 (data) => {
         msg!("Instruction: saber/mercurial 4pool Exchange v2");
-        Self::process_exchange_mercurial_saber_4pool(accounts, &data, 5)
+        Self::process_exchange_mercurial_saber_4pool(program_id, accounts, &data, 5)
       }
       ArbitrageInstruction::Exchange_Stable13 (data) => {
         msg!("Instruction: saber/whirl Exchange v1");
-        Self::process_exchange_saber_whirl(accounts, &data, 6)
+        Self::process_exchange_saber_whirl(program_id, accounts, &data, 6)
       }
       ArbitrageInstruction::Exchange_Stable14 (data) => {
         msg!("Instruction: saber/whirl Exchange v2");
-        Self::process_exchange_whirl_saber(accounts, &data, 6)
+        Self::process_exchange_whirl_saber(program_id, accounts, &data, 6)
       }
       ArbitrageInstruction::Exchange_Stable15 (data) => {
         msg!("Instruction: saber/whirl Exchange v1");
-        Self::process_exchange_saber_whirl(accounts, &data, 7)
+        Self::process_exchange_saber_whirl(program_id, accounts, &data, 7)
       }
       ArbitrageInstruction::Exchange_Stable16 (data) => {
         msg!("Instruction: saber/whirl Exchange v2");
-        Self::process_exchange_whirl_saber(accounts, &data, 7)
+        Self::process_exchange_whirl_saber(program_id, accounts, &data, 7)
       }
       ArbitrageInstruction::Exchange_Stable17 (data) => {
         msg!("Instruction: saber/crema Exchange v1");
-        Self::process_exchange_saber_crema(accounts, &data, 8)
+        Self::process_exchange_saber_crema(program_id, accounts, &data, 8)
       }
       ArbitrageInstruction::Exchange_Stable18 (data) => {
         msg!("Instruction: saber/crema Exchange v2");
-        Self::process_exchange_crema_saber(accounts, &data, 8)
+        Self::process_exchange_crema_saber(program_id, accounts, &data, 8)
       }
       ArbitrageInstruction::Exchange_Stable19 (data) => {
         msg!("Instruction: saber/crema Exchange v1");
-        Self::process_exchange_saber_crema(accounts, &data, 9)
+        Self::process_exchange_saber_crema(program_id, accounts, &data, 9)
       }
       ArbitrageInstruction::Exchange_Stable20 (data) => {
         msg!("Instruction: saber/crema Exchange v2");
-        Self::process_exchange_crema_saber(accounts, &data, 9)
+        Self::process_exchange_crema_saber(program_id, accounts, &data, 9)
       }
       ArbitrageInstruction::Exchange_Stable21 (data) => {
         msg!("Instruction: orca/serum Exchange v1");
-        Self::process_exchange_orca_serum(accounts, &data, 10)
+        Self::process_exchange_orca_serum(program_id, accounts, &data, 10)
       }
       ArbitrageInstruction::Exchange_Stable22 (data) => {
         msg!("Instruction: orca/serum Exchange v2");
-        Self::process_exchange_serum_orca(accounts, &data, 10)
+        Self::process_exchange_serum_orca(program_id, accounts, &data, 10)
       }
       ArbitrageInstruction::Exchange_Stable23 (data) => {
         msg!("Instruction: orca/serum Exchange v1");
-        Self::process_exchange_orca_serum(accounts, &data, 11)
+        Self::process_exchange_orca_serum(program_id, accounts, &data, 11)
       }
       ArbitrageInstruction::Exchange_Stable24 (data) => {
         msg!("Instruction: orca/serum Exchange v2");
-        Self::process_exchange_serum_orca(accounts, &data, 11)
+        Self::process_exchange_serum_orca(program_id, accounts, &data, 11)
       }
       ArbitrageInstruction::Exchange_Stable25 (data) => {
         msg!("Instruction: orca/raydium Exchange v1");
-        Self::process_exchange_orca_raydium(accounts, &data, 12)
+        Self::process_exchange_orca_raydium(program_id, accounts, &data, 12)
       }
       ArbitrageInstruction::Exchange_Stable26 (data) => {
         msg!("Instruction: orca/raydium Exchange v2");
-        Self::process_exchange_raydium_orca(accounts, &data, 12)
+        Self::process_exchange_raydium_orca(program_id, accounts, &data, 12)
       }
       ArbitrageInstruction::Exchange_Stable27 (data) => {
         msg!("Instruction: orca/raydium Exchange v1");
-        Self::process_exchange_orca_raydium(accounts, &data, 13)
+        Self::process_exchange_orca_raydium(program_id, accounts, &data, 13)
       }
       ArbitrageInstruction::Exchange_Stable28 (data) => {
         msg!("Instruction: orca/raydium Exchange v2");
-        Self::process_exchange_raydium_orca(accounts, &data, 13)
+        Self::process_exchange_raydium_orca(program_id, accounts, &data, 13)
       }
       ArbitrageInstruction::Exchange_Stable29 (data) => {
         msg!("Instruction: orca/whirl Exchange v1");
-        Self::process_exchange_orca_whirl(accounts, &data, 14)
+        Self::process_exchange_orca_whirl(program_id, accounts, &data, 14)
       }
       ArbitrageInstruction::Exchange_Stable30 (data) => {
         msg!("Instruction: orca/whirl Exchange v2");
-        Self::process_exchange_whirl_orca(accounts, &data, 14)
+        Self::process_exchange_whirl_orca(program_id, accounts, &data, 14)
       }
       ArbitrageInstruction::Exchange_Stable31 (data) => {
         msg!("Instruction: orca/whirl Exchange v1");
-        Self::process_exchange_orca_whirl(accounts, &data, 15)
+        Self::process_exchange_orca_whirl(program_id, accounts, &data, 15)
       }
       ArbitrageInstruction::Exchange_Stable32 (data) => {
         msg!("Instruction: orca/whirl Exchange v2");
-        Self::process_exchange_whirl_orca(accounts, &data, 15)
+        Self::process_exchange_whirl_orca(program_id, accounts, &data, 15)
       }
-      _ => {
-        msg!("unknow instruction!");
-        Ok(())
+      ArbitrageInstruction::ExecuteRoute (data) => {
+        msg!("Instruction: route Exchange v1");
+        Self::process_route_exchange(program_id, accounts, &data)
+      }
+      ArbitrageInstruction::InitializeConfig (data) => {
+        msg!("Instruction: initialize config");
+        Self::process_initialize_config(program_id, accounts, &data)
+      }
+      ArbitrageInstruction::UpdateConfig (data) => {
+        msg!("Instruction: update config");
+        Self::process_update_config(program_id, accounts, &data)
+      }
+      ArbitrageInstruction::InitializeStrategy (data) => {
+        msg!("Instruction: initialize strategy");
+        Self::process_initialize_strategy(program_id, accounts, &data)
+      }
+      ArbitrageInstruction::UpdateStrategy (data) => {
+        msg!("Instruction: update strategy");
+        Self::process_update_strategy(program_id, accounts, &data)
+      }
+      ArbitrageInstruction::MigrateExchangeState (data) => {
+        msg!("Instruction: migrate exchange state");
+        Self::process_migrate_exchange_state(program_id, accounts, &data)
       }
-    } 
+      ArbitrageInstruction::InitializeExchangeState (data) => {
+        msg!("Instruction: initialize exchange state");
+        Self::process_initialize_exchange_state(program_id, accounts, &data)
+      }
+      ArbitrageInstruction::ResetExchangeState (data) => {
+        msg!("Instruction: reset exchange state");
+        Self::process_reset_exchange_state(program_id, accounts, &data)
+      }
+      ArbitrageInstruction::CloseExchangeState (data) => {
+        msg!("Instruction: close exchange state");
+        Self::process_close_exchange_state(program_id, accounts, &data)
+      }
+    }
   }
 
-  //
-  fn process_path_exchange(accounts: &[AccountInfo], path_exchange: &ExchangeWithPathInstruction) -> ProgramResult {
+  fn process_initialize_config(program_id: &Pubkey, accounts: &[AccountInfo], config_ins: &ConfigInstruction) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    let exchange_acc = next_account_info(account_info_iter)?;
-    let mut exchange_acc_state = ExchangeState::unpack_from_slice(&exchange_acc.try_borrow_data()?)?;
-    let mut amount_in = 0;
-    let flag = path_exchange.flag;
-    let amount = path_exchange.amount;
-    let market = path_exchange.market;
-    let side = path_exchange.side;
-    if flag == 0 {
-      amount_in = amount;
-      exchange_acc_state.input_amount = amount_in;
-    } else {
-      amount_in = exchange_acc_state.exchange_out;
+    let payer_acc = next_account_info(account_info_iter)?;
+    let config_acc = next_account_info(account_info_iter)?;
+    let system_program_acc = next_account_info(account_info_iter)?;
+
+    validation::signer(payer_acc)?;
+    let (config_key, bump_seed) = Pubkey::find_program_address(&[CONFIG_SEED], program_id);
+    if config_key != *config_acc.key {
+      return Err(ProgramError::InvalidSeeds);
+    }
+    if !config_acc.data_is_empty() {
+      return Err(ProgramError::AccountAlreadyInitialized);
     }
-    match market {
-      Market::Orca => {
-        msg!("orca swap");
-        let orca_program_acc = next_account_info(account_info_iter)?;
-        let orca_market_acc = next_account_info(account_info_iter)?;
-        let orca_market_auth_acc = next_account_info(account_info_iter)?;
-        let user_owner_acc = next_account_info(account_info_iter)?;
-        let user_source_acc = next_account_info(account_info_iter)?;
-        let orca_swap_source_acc = next_account_info(account_info_iter)?;
-        let orca_swap_dst_acc = next_account_info(account_info_iter)?;
-        let user_dst_acc = next_account_info(account_info_iter)?;
-        let orca_pool_mint_acc = next_account_info(account_info_iter)?;
-        let orca_fee_acc = next_account_info(account_info_iter)?;
-        let spl_token_program_acc = next_account_info(account_info_iter)?;
-
-        let user_source_acc_state_before = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_before = user_source_acc_state_before.amount;
-
-        let user_dst_acc_state_before = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_before = user_dst_acc_state_before.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_before, user_dst_acc_balance_before,
-        );
 
-        Self::orca_swap(
-          orca_program_acc.key,
-          orca_market_acc,
-          orca_market_auth_acc,
-          user_owner_acc,
-          user_source_acc,
-          orca_swap_source_acc,
-          orca_swap_dst_acc,
-          user_dst_acc,
-          orca_pool_mint_acc,
-          orca_fee_acc,
-          spl_token_program_acc,
-          amount_in,
-        )?;
+    let rent = Rent::get()?;
+    let create_config_instruction = system_instruction::create_account(
+      payer_acc.key,
+      config_acc.key,
+      rent.minimum_balance(Config::LEN),
+      Config::LEN as u64,
+      program_id,
+    );
+    invoke_signed(
+      &create_config_instruction,
+      &[payer_acc.clone(), config_acc.clone(), system_program_acc.clone()],
+      &[&[CONFIG_SEED, &[bump_seed]]],
+    )?;
+
+    let config = Config {
+      is_initialized: true,
+      bump_seed: bump_seed,
+      admin: config_ins.admin,
+      operators: config_ins.operators.clone(),
+    };
+    config.pack_into_slice(&mut config_acc.try_borrow_mut_data()?);
+    msg!("config admin: {}, operators: {}", config.admin, config.operators.len());
+    Ok(())
+  }
 
-        let user_source_acc_state_after = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_after = user_source_acc_state_after.amount;
+  fn process_update_config(program_id: &Pubkey, accounts: &[AccountInfo], config_ins: &ConfigInstruction) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin_acc = next_account_info(account_info_iter)?;
+    let config_acc = next_account_info(account_info_iter)?;
 
-        let user_dst_acc_state_after = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_after = user_dst_acc_state_after.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_after, user_dst_acc_balance_after,
-        );
-        exchange_acc_state.exchange_out = user_dst_acc_balance_after - user_dst_acc_balance_before;
-      },
-      Market::Saber => {
-        msg!("saber swap");
-        let saber_program_acc = next_account_info(account_info_iter)?;
-        let saber_market_acc = next_account_info(account_info_iter)?;
-        let saber_market_auth_acc = next_account_info(account_info_iter)?;
-        let user_owner_acc = next_account_info(account_info_iter)?;
-        let user_source_acc = next_account_info(account_info_iter)?;
-        let saber_swap_source_acc = next_account_info(account_info_iter)?;
-        let saber_swap_dst_acc = next_account_info(account_info_iter)?;
-        let user_dst_acc = next_account_info(account_info_iter)?;
-        let saber_pool_fee_acc = next_account_info(account_info_iter)?;
-        let spl_token_program_acc = next_account_info(account_info_iter)?;
-        let sys_clock = next_account_info(account_info_iter)?;
-
-        let user_source_acc_state_before = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_before = user_source_acc_state_before.amount;
-
-        let user_dst_acc_state_before = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_before = user_dst_acc_state_before.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_before, user_dst_acc_balance_before,
-        );
-
-        Self::saber_swap(
-          saber_program_acc.key,
-          saber_market_acc,
-          saber_market_auth_acc,
-          user_owner_acc,
-          user_source_acc,
-          saber_swap_source_acc,
-          saber_swap_dst_acc,
-          user_dst_acc,
-          saber_pool_fee_acc,
-          spl_token_program_acc,
-          sys_clock,
-          amount_in,
-        )?;
+    Self::authorize_admin(program_id, config_acc, admin_acc)?;
+    let mut config = Self::load_config(program_id, config_acc)?;
+    if config_ins.operators.len() > MAX_OPERATORS {
+      return Err(ArbitrageError::InvalidInstruction.into());
+    }
+    config.admin = config_ins.admin;
+    config.operators = config_ins.operators.clone();
+    config.pack_into_slice(&mut config_acc.try_borrow_mut_data()?);
+    msg!("config admin: {}, operators: {}", config.admin, config.operators.len());
+    Ok(())
+  }
 
-        let user_source_acc_state_after = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_after = user_source_acc_state_after.amount;
+  fn load_config(program_id: &Pubkey, config_acc: &AccountInfo) -> Result<Config, ProgramError> {
+    if config_acc.owner != program_id {
+      return Err(ArbitrageError::InvalidOwner.into());
+    }
+    let config = Config::unpack_from_slice(&config_acc.try_borrow_data()?)?;
+    if !config.is_initialized {
+      return Err(ProgramError::UninitializedAccount);
+    }
+    let config_key = Pubkey::create_program_address(&[CONFIG_SEED, &[config.bump_seed]], program_id)?;
+    if config_key != *config_acc.key {
+      return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(config)
+  }
 
-        let user_dst_acc_state_after = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_after = user_dst_acc_state_after.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_after, user_dst_acc_balance_after,
-        );
-        exchange_acc_state.exchange_out = user_dst_acc_balance_after - user_dst_acc_balance_before;
-      },
-      Market::Serum => {
-        msg!("serum swap");
-        let serum_program_acc = next_account_info(account_info_iter)?;
-        let serum_market_acc = next_account_info(account_info_iter)?;
-        let serum_open_orders_acc = next_account_info(account_info_iter)?;
-        let serem_request_queue_acc = next_account_info(account_info_iter)?;
-        let serem_event_queue_acc = next_account_info(account_info_iter)?;
-        let serum_bids_acc = next_account_info(account_info_iter)?;
-        let serum_asks_acc = next_account_info(account_info_iter)?;
-        let serum_base_vault_acc = next_account_info(account_info_iter)?;
-        let serum_quote_vault_acc = next_account_info(account_info_iter)?;
-        let serum_vault_signer_acc = next_account_info(account_info_iter)?;
-        //
-        let user_owner_acc = next_account_info(account_info_iter)?;
-        let user_source_acc = next_account_info(account_info_iter)?;
-        let user_dst_acc = next_account_info(account_info_iter)?;
-        //
-        let sys_rent_acc = next_account_info(account_info_iter)?;
-        let spl_token_program_acc = next_account_info(account_info_iter)?;
-
-        let user_source_acc_state_before = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_before = user_source_acc_state_before.amount;
-
-        let user_dst_acc_state_before = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_before = user_dst_acc_state_before.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_before, user_dst_acc_balance_before,
-        );
-
-        let xside = find_side(side).ok_or(ArbitrageError::InvalidInstruction)?;
-
-        match xside {
-          Side::Bid => {
-            let limit_price = u64::MAX;
-            let max_base_qty = u64::MAX;
-            let max_quote_qty = amount_in;
-            Self::serum_swap(
-              serum_program_acc.key,
-              serum_market_acc,
-              serum_open_orders_acc,
-              serem_request_queue_acc,
-              serem_event_queue_acc,
-              serum_bids_acc,
-              serum_asks_acc,
-              user_source_acc,
-              serum_base_vault_acc,
-              serum_quote_vault_acc,
-              user_dst_acc,
-              user_source_acc,
-              serum_vault_signer_acc,
-              user_owner_acc,
-              spl_token_program_acc,
-              sys_rent_acc,
-              serum_dex::matching::Side::Bid,
-              limit_price,
-              max_base_qty,
-              max_quote_qty,
-            )?;
-          },
-          Side::Ask => {
-            let limit_price = 1;
-            let max_base_qty = {
-              //
-              let market1 = MarketState::load(serum_market_acc, serum_program_acc.key, true)?;
-              amount_in.checked_div(market1.coin_lot_size).unwrap()
-            };
-            let max_quote_qty = u64::MAX;
-            Self::serum_swap(
-              serum_program_acc.key,
-              serum_market_acc,
-              serum_open_orders_acc,
-              serem_request_queue_acc,
-              serem_event_queue_acc,
-              serum_bids_acc,
-              serum_asks_acc,
-              user_source_acc,
-              serum_base_vault_acc,
-              serum_quote_vault_acc,
-              user_source_acc,
-              user_dst_acc,
-              serum_vault_signer_acc,
-              user_owner_acc,
-              spl_token_program_acc,
-              sys_rent_acc,
-              serum_dex::matching::Side::Bid,
-              limit_price,
-              max_base_qty,
-              max_quote_qty,
-            )?;           
-          },
-          _ => {
-
-          },
-        }
+  // the operator must sign and be listed in the config
+  fn authorize_operator(program_id: &Pubkey, config_acc: &AccountInfo, operator_acc: &AccountInfo) -> ProgramResult {
+    let config = Self::load_config(program_id, config_acc)?;
+    validation::signer(operator_acc)?;
+    if !config.is_operator(operator_acc.key) {
+      return Err(ArbitrageError::UnauthorizedOperator.into());
+    }
+    Ok(())
+  }
 
-        let user_source_acc_state_after = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_after = user_source_acc_state_after.amount;
+  // the admin must sign and be the config's admin
+  fn authorize_admin(program_id: &Pubkey, config_acc: &AccountInfo, admin_acc: &AccountInfo) -> ProgramResult {
+    let config = Self::load_config(program_id, config_acc)?;
+    validation::signer(admin_acc)?;
+    if config.admin != *admin_acc.key {
+      return Err(ArbitrageError::UnauthorizedOperator.into());
+    }
+    Ok(())
+  }
 
-        let user_dst_acc_state_after = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_after = user_dst_acc_state_after.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_after, user_dst_acc_balance_after,
-        );
-        exchange_acc_state.exchange_out = user_dst_acc_balance_after - user_dst_acc_balance_before;
-      },
-      Market::Raydium => {
-        msg!("raydium swap");
-        let raydium_program_acc = next_account_info(account_info_iter)?;
-        let raydium_market_acc = next_account_info(account_info_iter)?;
-        let raydium_market_auth_acc = next_account_info(account_info_iter)?;
-        let raydium_open_orders_acc = next_account_info(account_info_iter)?;
-        let raydium_target_orders_acc = next_account_info(account_info_iter)?;
-        let raydium_coin_vault_acc = next_account_info(account_info_iter)?;
-        let raydium_pc_vault_acc = next_account_info(account_info_iter)?;
-        let serum_program_acc = next_account_info(account_info_iter)?;
-        let serum_market_acc = next_account_info(account_info_iter)?;
-        let serem_request_queue_acc = next_account_info(account_info_iter)?;
-        let serem_event_queue_acc = next_account_info(account_info_iter)?;
-        let serum_bids_acc = next_account_info(account_info_iter)?;
-        let serum_asks_acc = next_account_info(account_info_iter)?;
-        let serum_base_vault_acc = next_account_info(account_info_iter)?;
-        let serum_quote_vault_acc = next_account_info(account_info_iter)?;
-        let serum_vault_signer_acc = next_account_info(account_info_iter)?;
-
-        let user_owner_acc = next_account_info(account_info_iter)?;
-        let user_source_acc = next_account_info(account_info_iter)?;
-        let user_dst_acc = next_account_info(account_info_iter)?;
-
-        let user_source_acc_state_before = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_before = user_source_acc_state_before.amount;
-
-        let user_dst_acc_state_before = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_before = user_dst_acc_state_before.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_before, user_dst_acc_balance_before,
-        );
+  fn process_initialize_strategy(program_id: &Pubkey, accounts: &[AccountInfo], strategy_ins: &StrategyParamsInstruction) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin_acc = next_account_info(account_info_iter)?;
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
+    let system_program_acc = next_account_info(account_info_iter)?;
+
+    Self::authorize_admin(program_id, config_acc, admin_acc)?;
+    let (strategy_key, bump_seed) = Pubkey::find_program_address(&[STRATEGY_SEED, &[strategy_ins.index]], program_id);
+    if strategy_key != *strategy_acc.key {
+      return Err(ProgramError::InvalidSeeds);
+    }
+    if !strategy_acc.data_is_empty() {
+      return Err(ProgramError::AccountAlreadyInitialized);
+    }
 
-        Self::raydium_swap(
-          raydium_program_acc.key,
-          raydium_market_acc,
-          raydium_market_auth_acc,
-          raydium_open_orders_acc,
-          raydium_target_orders_acc,
-          raydium_coin_vault_acc,
-          raydium_pc_vault_acc,
-          serum_program_acc.key,
-          serum_market_acc,
-          serum_bids_acc,
-          serum_asks_acc,
-          serem_event_queue_acc,
-          serum_base_vault_acc,
-          serum_quote_vault_acc,
-          serum_vault_signer_acc,
-          user_source_acc,
-          user_dst_acc,
-          user_owner_acc,
-          amount_in,
-        )?;
+    let rent = Rent::get()?;
+    let create_strategy_instruction = system_instruction::create_account(
+      admin_acc.key,
+      strategy_acc.key,
+      rent.minimum_balance(StrategyParams::LEN),
+      StrategyParams::LEN as u64,
+      program_id,
+    );
+    invoke_signed(
+      &create_strategy_instruction,
+      &[admin_acc.clone(), strategy_acc.clone(), system_program_acc.clone()],
+      &[&[STRATEGY_SEED, &[strategy_ins.index], &[bump_seed]]],
+    )?;
+
+    let strategy = StrategyParams {
+      is_initialized: true,
+      bump_seed: bump_seed,
+      index: strategy_ins.index,
+      enabled: strategy_ins.enabled,
+      input_amount: strategy_ins.input_amount,
+      threshold: strategy_ins.threshold,
+      expected_profit: strategy_ins.expected_profit,
+    };
+    strategy.pack_into_slice(&mut strategy_acc.try_borrow_mut_data()?);
+    msg!("strategy {}: input amount: {}, enabled: {}", strategy.index, strategy.input_amount, strategy.enabled);
+    Ok(())
+  }
 
-        let user_source_acc_state_after = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_after = user_source_acc_state_after.amount;
+  fn process_update_strategy(program_id: &Pubkey, accounts: &[AccountInfo], strategy_ins: &StrategyParamsInstruction) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin_acc = next_account_info(account_info_iter)?;
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
+
+    Self::authorize_admin(program_id, config_acc, admin_acc)?;
+    let mut strategy = Self::load_strategy_params(program_id, strategy_acc, strategy_ins.index)?;
+    strategy.enabled = strategy_ins.enabled;
+    strategy.input_amount = strategy_ins.input_amount;
+    strategy.threshold = strategy_ins.threshold;
+    strategy.expected_profit = strategy_ins.expected_profit;
+    strategy.pack_into_slice(&mut strategy_acc.try_borrow_mut_data()?);
+    msg!("strategy {}: input amount: {}, enabled: {}", strategy.index, strategy.input_amount, strategy.enabled);
+    Ok(())
+  }
 
-        let user_dst_acc_state_after = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_after = user_dst_acc_state_after.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_after, user_dst_acc_balance_after,
-        );
-        exchange_acc_state.exchange_out = user_dst_acc_balance_after - user_dst_acc_balance_before;
-      },
-      Market::Mercurial_2pool => {
-        msg!("mercurial swap");
-        let mercurial_program_acc = next_account_info(account_info_iter)?;
-        let mercurial_market_acc = next_account_info(account_info_iter)?;
-        let mercurial_market_auth_acc = next_account_info(account_info_iter)?;
-        let user_owner_acc = next_account_info(account_info_iter)?;
-        let user_source_acc = next_account_info(account_info_iter)?;
-        let mercurial_swap_acc1 = next_account_info(account_info_iter)?;
-        let mercurial_swap_acc2 = next_account_info(account_info_iter)?;
-        let user_dst_acc = next_account_info(account_info_iter)?;
-        let spl_token_program_acc = next_account_info(account_info_iter)?;
-
-        let user_source_acc_state_before = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_before = user_source_acc_state_before.amount;
-
-        let user_dst_acc_state_before = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_before = user_dst_acc_state_before.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_before, user_dst_acc_balance_before,
-        );
-
-        Self::mercurial_swap_2pool(
-          mercurial_program_acc.key,
-          mercurial_market_acc,
-          mercurial_market_auth_acc,
-          user_owner_acc,
-          mercurial_swap_acc1,
-          mercurial_swap_acc2,
-          user_source_acc,
-          user_dst_acc,
-          spl_token_program_acc,
-          amount_in,
-        )?;
+  fn load_strategy_params(program_id: &Pubkey, strategy_acc: &AccountInfo, index: u8) -> Result<StrategyParams, ProgramError> {
+    if strategy_acc.owner != program_id {
+      return Err(ArbitrageError::InvalidOwner.into());
+    }
+    let strategy = StrategyParams::unpack_from_slice(&strategy_acc.try_borrow_data()?)?;
+    if !strategy.is_initialized {
+      return Err(ProgramError::UninitializedAccount);
+    }
+    let strategy_key = Pubkey::create_program_address(&[STRATEGY_SEED, &[index], &[strategy.bump_seed]], program_id)?;
+    if strategy_key != *strategy_acc.key {
+      return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(strategy)
+  }
 
-        let user_source_acc_state_after = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_after = user_source_acc_state_after.amount;
+  // params of the strategy the handler runs, which the admin may have turned off
+  fn load_enabled_strategy(program_id: &Pubkey, strategy_acc: &AccountInfo, index: u8) -> Result<StrategyParams, ProgramError> {
+    let strategy = Self::load_strategy_params(program_id, strategy_acc, index)?;
+    if !strategy.enabled {
+      return Err(ArbitrageError::StrategyDisabled.into());
+    }
+    Ok(strategy)
+  }
 
-        let user_dst_acc_state_after = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_after = user_dst_acc_state_after.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_after, user_dst_acc_balance_after,
-        );
-        exchange_acc_state.exchange_out = user_dst_acc_balance_after - user_dst_acc_balance_before;
-      },
-      Market::Mercurial_3pool => {
-        msg!("mercurial swap");
-        let mercurial_program_acc = next_account_info(account_info_iter)?;
-        let mercurial_market_acc = next_account_info(account_info_iter)?;
-        let mercurial_market_auth_acc = next_account_info(account_info_iter)?;
-        let user_owner_acc = next_account_info(account_info_iter)?;
-        let user_source_acc = next_account_info(account_info_iter)?;
-        let mercurial_swap_acc1 = next_account_info(account_info_iter)?;
-        let mercurial_swap_acc2 = next_account_info(account_info_iter)?;
-        let mercurial_swap_acc3 = next_account_info(account_info_iter)?;
-        let user_dst_acc = next_account_info(account_info_iter)?;
-        let spl_token_program_acc = next_account_info(account_info_iter)?;
-
-        let user_source_acc_state_before = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_before = user_source_acc_state_before.amount;
-
-        let user_dst_acc_state_before = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_before = user_dst_acc_state_before.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_before, user_dst_acc_balance_before,
-        );
-
-        Self::mercurial_swap_3pool(
-          mercurial_program_acc.key,
-          mercurial_market_acc,
-          mercurial_market_auth_acc,
-          user_owner_acc,
-          mercurial_swap_acc1,
-          mercurial_swap_acc2,
-          mercurial_swap_acc3,
-          user_source_acc,
-          user_dst_acc,
-          spl_token_program_acc,
-          amount_in,
-        )?;
+  fn process_migrate_exchange_state(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeStateInstruction) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let admin_acc = next_account_info(account_info_iter)?;
+    let config_acc = next_account_info(account_info_iter)?;
+    let legacy_acc = next_account_info(account_info_iter)?;
+    let owner_acc = next_account_info(account_info_iter)?;
+    let exchange_acc = next_account_info(account_info_iter)?;
+    let system_program_acc = next_account_info(account_info_iter)?;
 
-        let user_source_acc_state_after = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_after = user_source_acc_state_after.amount;
+    // legacy states record no owner, only the admin may bind one to an operator
+    Self::authorize_admin(program_id, config_acc, admin_acc)?;
+    if !Self::load_config(program_id, config_acc)?.is_operator(owner_acc.key) {
+      return Err(ArbitrageError::UnauthorizedOperator.into());
+    }
+    validation::program_state(legacy_acc, program_id)?;
+    if ExchangeState::unpack_from_slice(&legacy_acc.try_borrow_data()?).is_ok() {
+      return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    let legacy_state = ExchangeState::unpack_legacy(&legacy_acc.try_borrow_data()?)?;
+
+    let bump_seed = Self::create_exchange_state(program_id, admin_acc, exchange_acc, system_program_acc, owner_acc.key, exchange_ins.strategy_id)?;
+    let state = ExchangeState {
+      bump_seed: bump_seed,
+      strategy_id: exchange_ins.strategy_id,
+      owner: *owner_acc.key,
+      ..legacy_state
+    };
+    state.pack_into_slice(&mut exchange_acc.try_borrow_mut_data()?);
+
+    // the legacy account's rent goes back to the admin paying for the new one
+    let lamports = legacy_acc.lamports();
+    let admin_lamports = checked_add(admin_acc.lamports(), lamports)?;
+    **admin_acc.try_borrow_mut_lamports()? = admin_lamports;
+    **legacy_acc.try_borrow_mut_lamports()? = 0;
+    legacy_acc.try_borrow_mut_data()?.fill(0);
+    msg!("exchange state of strategy {} migrated, owner: {}", state.strategy_id, state.owner);
+    Ok(())
+  }
 
-        let user_dst_acc_state_after = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_after = user_dst_acc_state_after.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_after, user_dst_acc_balance_after,
-        );
-        exchange_acc_state.exchange_out = user_dst_acc_balance_after - user_dst_acc_balance_before;
-      },
-      Market::Mercurial_4pool => {
-        msg!("mercurial swap");
-        let mercurial_program_acc = next_account_info(account_info_iter)?;
-        let mercurial_market_acc = next_account_info(account_info_iter)?;
-        let mercurial_market_auth_acc = next_account_info(account_info_iter)?;
-        let user_owner_acc = next_account_info(account_info_iter)?;
-        let user_source_acc = next_account_info(account_info_iter)?;
-        let mercurial_swap_acc1 = next_account_info(account_info_iter)?;
-        let mercurial_swap_acc2 = next_account_info(account_info_iter)?;
-        let mercurial_swap_acc3 = next_account_info(account_info_iter)?;
-        let mercurial_swap_acc4 = next_account_info(account_info_iter)?;
-        let user_dst_acc = next_account_info(account_info_iter)?;
-        let spl_token_program_acc = next_account_info(account_info_iter)?;
-
-        let user_source_acc_state_before = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_before = user_source_acc_state_before.amount;
-
-        let user_dst_acc_state_before = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_before = user_dst_acc_state_before.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_before, user_dst_acc_balance_before,
-        );
-
-        Self::mercurial_swap_4pool(
-          mercurial_program_acc.key,
-          mercurial_market_acc,
-          mercurial_market_auth_acc,
-          user_owner_acc,
-          mercurial_swap_acc1,
-          mercurial_swap_acc2,
-          mercurial_swap_acc3,
-          mercurial_swap_acc4,
-          user_source_acc,
-          user_dst_acc,
-          spl_token_program_acc,
-          amount_in,
-        )?;
+  fn process_initialize_exchange_state(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeStateInstruction) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let operator_acc = next_account_info(account_info_iter)?;
+    let config_acc = next_account_info(account_info_iter)?;
+    let exchange_acc = next_account_info(account_info_iter)?;
+    let system_program_acc = next_account_info(account_info_iter)?;
+
+    Self::authorize_operator(program_id, config_acc, operator_acc)?;
+    let bump_seed = Self::create_exchange_state(program_id, operator_acc, exchange_acc, system_program_acc, operator_acc.key, exchange_ins.strategy_id)?;
+    let state = ExchangeState {
+      bump_seed: bump_seed,
+      strategy_id: exchange_ins.strategy_id,
+      owner: *operator_acc.key,
+      ..ExchangeState::default()
+    };
+    state.pack_into_slice(&mut exchange_acc.try_borrow_mut_data()?);
+    msg!("exchange state of strategy {} created, owner: {}", state.strategy_id, state.owner);
+    Ok(())
+  }
+
+  // creates the exchange state PDA of `owner` and `strategy_id` paid by `payer_acc`, returns its bump
+  fn create_exchange_state<'a>(
+    program_id: &Pubkey,
+    payer_acc: &AccountInfo<'a>,
+    exchange_acc: &AccountInfo<'a>,
+    system_program_acc: &AccountInfo<'a>,
+    owner: &Pubkey,
+    strategy_id: u8,
+  ) -> Result<u8, ProgramError> {
+    let (exchange_key, bump_seed) = Pubkey::find_program_address(&[EXCHANGE_SEED, owner.as_ref(), &[strategy_id]], program_id);
+    if exchange_key != *exchange_acc.key {
+      return Err(ProgramError::InvalidSeeds);
+    }
+    if !exchange_acc.data_is_empty() {
+      return Err(ProgramError::AccountAlreadyInitialized);
+    }
 
-        let user_source_acc_state_after = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_after = user_source_acc_state_after.amount;
+    let rent = Rent::get()?;
+    let create_exchange_instruction = system_instruction::create_account(
+      payer_acc.key,
+      exchange_acc.key,
+      rent.minimum_balance(ExchangeState::LEN),
+      ExchangeState::LEN as u64,
+      program_id,
+    );
+    invoke_signed(
+      &create_exchange_instruction,
+      &[payer_acc.clone(), exchange_acc.clone(), system_program_acc.clone()],
+      &[&[EXCHANGE_SEED, owner.as_ref(), &[strategy_id], &[bump_seed]]],
+    )?;
+    Ok(bump_seed)
+  }
 
-        let user_dst_acc_state_after = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_after = user_dst_acc_state_after.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_after, user_dst_acc_balance_after,
-        );
-        exchange_acc_state.exchange_out = user_dst_acc_balance_after - user_dst_acc_balance_before;
-      },
-      Market::Whirl => {
-        msg!("whirl swap");
-        let whirl_program_acc = next_account_info(account_info_iter)?;
-        let whirl_market_acc = next_account_info(account_info_iter)?;
-        let owner_acc = next_account_info(account_info_iter)?;
-        let user_source_acc = next_account_info(account_info_iter)?;
-        let whirl_vault_a_acc = next_account_info(account_info_iter)?;
-        let user_dst_acc = next_account_info(account_info_iter)?;
-        let whirl_vault_b_acc = next_account_info(account_info_iter)?;
-        let whirl_tick_acc = next_account_info(account_info_iter)?;
-        let whirl_oracle_acc = next_account_info(account_info_iter)?;
-        let spl_token_program_acc = next_account_info(account_info_iter)?;
-
-        let user_source_acc_state_before = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_before = user_source_acc_state_before.amount;
-
-        let user_dst_acc_state_before = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_before = user_dst_acc_state_before.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_before, user_dst_acc_balance_before,
-        );
-
-        Self::whirl_swap(
-          whirl_program_acc.key,
-          whirl_market_acc,
-          owner_acc,
-          user_source_acc,
-          whirl_vault_a_acc,
-          user_dst_acc,
-          whirl_vault_b_acc,
-          whirl_tick_acc,
-          whirl_tick_acc,
-          whirl_tick_acc,
-          whirl_oracle_acc,
-          spl_token_program_acc,
-          amount_in,
-          side,
-        )?;
+  fn process_reset_exchange_state(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeStateInstruction) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner_acc = next_account_info(account_info_iter)?;
+    let exchange_acc = next_account_info(account_info_iter)?;
 
-        let user_source_acc_state_after = TokenAccount::unpack(&user_source_acc.try_borrow_data()?)?;
-        let user_source_acc_balance_after = user_source_acc_state_after.amount;
+    validation::signer(owner_acc)?;
+    let mut state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, exchange_ins.strategy_id)?;
+    state.reset();
+    state.pack_into_slice(&mut exchange_acc.try_borrow_mut_data()?);
+    msg!("exchange state of strategy {} reset", state.strategy_id);
+    Ok(())
+  }
 
-        let user_dst_acc_state_after = TokenAccount::unpack(&user_dst_acc.try_borrow_data()?)?;
-        let user_dst_acc_balance_after = user_dst_acc_state_after.amount;
-        msg!(
-          "source account balance: {}, destination account balance: {}",
-          user_source_acc_balance_after, user_dst_acc_balance_after,
-        );
-        exchange_acc_state.exchange_out = user_dst_acc_balance_after - user_dst_acc_balance_before;
-      },
-      _ => {
-        msg!("unknow swap");
-        return Err(ArbitrageError::InvalidInstruction.into());
-      }
+  fn process_close_exchange_state(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeStateInstruction) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let owner_acc = next_account_info(account_info_iter)?;
+    let exchange_acc = next_account_info(account_info_iter)?;
+    let destination_acc = next_account_info(account_info_iter)?;
+
+    validation::signer(owner_acc)?;
+    if exchange_acc.key == destination_acc.key {
+      return Err(ProgramError::InvalidArgument);
+    }
+    Self::load_exchange_state(program_id, exchange_acc, owner_acc, exchange_ins.strategy_id)?;
+
+    // the runtime purges the zero lamport account at the end of the transaction,
+    // the cleared data keeps it from being read as a state until then
+    let lamports = exchange_acc.lamports();
+    let destination_lamports = checked_add(destination_acc.lamports(), lamports)?;
+    **destination_acc.try_borrow_mut_lamports()? = destination_lamports;
+    **exchange_acc.try_borrow_mut_lamports()? = 0;
+    exchange_acc.try_borrow_mut_data()?.fill(0);
+    msg!("exchange state of strategy {} closed, lamports: {}", exchange_ins.strategy_id, lamports);
+    Ok(())
+  }
+
+  // the v2 state `owner_acc` runs `strategy_id` with, checked to be ours and writable
+  // before any handler mutates it
+  fn load_exchange_state(program_id: &Pubkey, exchange_acc: &AccountInfo, owner_acc: &AccountInfo, strategy_id: u8) -> Result<ExchangeState, ProgramError> {
+    validation::program_state(exchange_acc, program_id)?;
+    let state = ExchangeState::unpack_from_slice(&exchange_acc.try_borrow_data()?)?;
+    if state.owner != *owner_acc.key {
+      return Err(ArbitrageError::ExchangeOwnerMismatch.into());
+    }
+    if state.strategy_id != strategy_id {
+      return Err(ArbitrageError::StrategyMismatch.into());
+    }
+    Ok(state)
+  }
+
+  //
+  fn process_path_exchange(program_id: &Pubkey, accounts: &[AccountInfo], path_exchange: &ExchangeWithPathInstruction) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let operator_acc = next_account_info(account_info_iter)?;
+    Self::authorize_operator(program_id, config_acc, operator_acc)?;
+    let exchange_acc = next_account_info(account_info_iter)?;
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, operator_acc, STRATEGY_PATH)?;
+    let mut amount_in = 0;
+    let flag = path_exchange.flag;
+    let amount = path_exchange.amount;
+    let market = path_exchange.market;
+    let side = path_exchange.side;
+    let minimum_amount_out = path_exchange.minimum_amount_out;
+    if flag == 0 {
+      amount_in = amount;
+      exchange_acc_state.input_amount = amount_in;
+    } else if exchange_acc_state.input_amount == 0 {
+      // the previous hops of this path never ran
+      return Err(ArbitrageError::StaleState.into());
+    } else {
+      amount_in = exchange_acc_state.exchange_out;
     }
+    let swap = swap_with_market(market, side, account_info_iter, amount_in, minimum_amount_out)
+      .map_err(|error| venue_error(STRATEGY_PATH, flag, market, error))?;
+    ArbitrageEvent::HopExecuted {
+      strategy: STRATEGY_PATH,
+      hop: flag,
+      amount_in: swap.amount_in,
+      amount_out: swap.amount_out,
+    }.emit();
+    exchange_acc_state.exchange_out = swap.amount_out;
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
     ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
     match flag {
       2 => {
@@ -700,7 +600,12 @@ impl Processor {
         let amount_out = exchange_acc_state.exchange_out;
         msg!("amount in: {}, amount out: {}", amount_in, amount_out);
         if amount_out < amount_in {
-          return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+          ArbitrageEvent::ProfitCheckFailed {
+            strategy: STRATEGY_PATH,
+            total_profit: 0,
+            total_lost: amount_in - amount_out,
+          }.emit();
+          return Err(ArbitrageError::ProfitBelowMinimum.into());
         }
       },
       _ => {
@@ -709,8 +614,69 @@ impl Processor {
     Ok(())
   }
 
-  fn process_orca_raydium_serum_exchange(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction) -> ProgramResult {
+  //
+  fn process_route_exchange(program_id: &Pubkey, accounts: &[AccountInfo], route: &ExecuteRouteInstruction) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let operator_acc = next_account_info(account_info_iter)?;
+    Self::authorize_operator(program_id, config_acc, operator_acc)?;
+    let accounts = account_info_iter.as_slice();
+    let mut amount = route.amount_in;
+    let mut offset = 0;
+    let mut swaps = Vec::with_capacity(route.hops.len());
+    for (i, hop) in route.hops.iter().enumerate() {
+      let hop_accounts_end = offset + hop.accounts_len as usize;
+      let hop_accounts = accounts.get(offset..hop_accounts_end).ok_or(ProgramError::NotEnoughAccountKeys)?;
+      let account_info_iter = &mut hop_accounts.iter();
+      msg!("hop: {}, amount in: {}", i, amount);
+      let swap = swap_with_market(hop.market, hop.side, account_info_iter, amount, hop.minimum_amount_out)
+        .map_err(|error| venue_error(STRATEGY_ROUTE, i as u8, hop.market, error))?;
+      ArbitrageEvent::HopExecuted {
+        strategy: STRATEGY_ROUTE,
+        hop: i as u8,
+        amount_in: swap.amount_in,
+        amount_out: swap.amount_out,
+      }.emit();
+      amount = swap.amount_out;
+      swaps.push(swap);
+      // every hop must consume exactly the accounts declared for it
+      if account_info_iter.next().is_some() {
+        return Err(ArbitrageError::InvalidInstruction.into());
+      }
+      offset = hop_accounts_end;
+    }
+    msg!("amount in: {}, amount out: {}", route.amount_in, amount);
+    // a route is a cycle, it ends in the token it started from
+    let (first, last) = match (swaps.first(), swaps.last()) {
+      (Some(first), Some(last)) => (first, last),
+      _ => return Err(ArbitrageError::InvalidInstruction.into()),
+    };
+    if first.source_mint != last.destination_mint {
+      return Err(ArbitrageError::MintMismatch.into());
+    }
+    let total_profit = amount.saturating_sub(route.amount_in);
+    let total_lost = route.amount_in.saturating_sub(amount);
+    ArbitrageEvent::CycleCompleted {
+      strategy: STRATEGY_ROUTE,
+      balance_before: first.source_balance_before,
+      balance_after: last.destination_balance_after,
+      total_profit: total_profit,
+      total_lost: total_lost,
+    }.emit();
+    if amount < route.amount_in {
+      ArbitrageEvent::ProfitCheckFailed {
+        strategy: STRATEGY_ROUTE,
+        total_profit: total_profit,
+        total_lost: total_lost,
+      }.emit();
+      return Err(ArbitrageError::ProfitBelowMinimum.into());
+    }
+    Ok(())
+  }
+
+  fn process_orca_raydium_serum_exchange(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
     //
     let exchange_acc = next_account_info(account_info_iter)?;
 
@@ -745,45 +711,45 @@ impl Processor {
     //
     //let sys_rent = next_account_info(account_info_iter)?;
     let spl_token_program_acc = next_account_info(account_info_iter)?;
-
-    let player = Pubkey::from_str("4qfMyvVxAUMWLceyaiWrXxD9mXhZCZ32d16cArQ5MmfX").unwrap();
-    if player != *user_owner_acc.key {
-      msg!("orca - raydium - serum exchange ok");
-      return Ok(());
-    }
-
-    //
-    let mut exchange_acc_state = ExchangeState::unpack_from_slice(&exchange_acc.try_borrow_data()?)?;
+    let other_mint_acc = next_account_info(account_info_iter)?;
+
+    Self::authorize_operator(program_id, config_acc, user_owner_acc)?;
+    validation::program(raydium_program_acc, &validation::raydium::id())?;
+    validation::program(serum_program_acc, &validation::serum::id())?;
+    validation::program(orca_program_acc, &validation::orca::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, user_owner_acc)?.mint;
+    let other_mint = validation::user_token_account(user_other_acc, user_owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, other_mint], &[orca_swap_a_acc, orca_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, other_mint], &[raydium_coin_vault_acc, raydium_pc_vault_acc])?;
+    validation::vault_mints(&[usdc_mint, other_mint], &[serum_base_vault_acc, serum_quote_vault_acc])?;
+    // prices below are in usdc per whole other token
+    let mut mint_decimals = MintDecimals::new();
+    let other_unit = mint_decimals.unit_of(user_other_acc, other_mint_acc)?;
+
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, user_owner_acc, STRATEGY_ORCA_RAYDIUM_SERUM)?;
     let flag = exchange_ins.flag;
-    if flag == 127 || flag == 0 {
-      exchange_acc_state.total_profit = 0;
-      exchange_acc_state.total_lost = 0;
-      exchange_acc_state.exchange_out = 15000000;
+    // a run, and the first run of a created or reset state, trades
+    if exchange_ins.begin_run || exchange_acc_state.attempts == 0 {
+      exchange_acc_state.begin_run(15000000);
     }
-  
     //
     // orca price
-    let orca_swap_a_state = spl_token::state::Account::unpack(&orca_swap_a_acc.try_borrow_data()?)?;
-    let orca_swap_b_state = spl_token::state::Account::unpack(&orca_swap_b_acc.try_borrow_data()?)?;
-    msg!("orca swap a: {}, b: {}", orca_swap_a_state.amount, orca_swap_b_state.amount);
-    let orca_price = orca_swap_b_state.amount / (orca_swap_a_state.amount / 1000000);
+    let orca_pool = ConstantProductPool::load(orca_program_acc.key, orca_market_acc, orca_swap_a_acc, orca_swap_b_acc)?;
+    msg!("orca swap a: {}, b: {}", orca_pool.reserve_a, orca_pool.reserve_b);
+    // token b out for one token a, fees and price impact included
+    let orca_price = orca_pool.quote(other_unit, true).ok_or(ArbitrageError::InvalidPoolState)?.amount_out;
     // raydium price
     let raydium_coin_state = spl_token::state::Account::unpack(&raydium_coin_vault_acc.try_borrow_data()?)?;
     let raydium_pc_state = spl_token::state::Account::unpack(&raydium_pc_vault_acc.try_borrow_data()?)?;
     msg!("raydium swap a: {}, b: {}", raydium_coin_state.amount, raydium_pc_state.amount);
-    let raydium_price = raydium_pc_state.amount / (raydium_coin_state.amount / 1000000);
+    let raydium_price = checked_div(raydium_pc_state.amount, raydium_coin_state.amount / other_unit)?;
     // serum price
-    let (
-      best_bid_price_t, 
-      best_bid_quantity, 
-      best_ask_price_t, 
-      best_ask_quantity,
-      pc_lot_size,
-      coin_lot_size,
-    ) = Self::trave(&serum_program_acc.key, &serum_market_acc, &serum_bids_acc, &serum_asks_acc);
-    let serum_price_buy = best_ask_price_t * 1000000 * pc_lot_size / coin_lot_size;
-    let serum_price_sell = best_bid_price_t * 1000000 * pc_lot_size / coin_lot_size;
-    let serum_price = (serum_price_buy + serum_price_sell) / 2;
+    let serum_book = SerumBookReader::load(serum_program_acc.key, serum_market_acc, serum_bids_acc, serum_asks_acc)?;
+    let serum_price_buy = serum_book.normalized_price(serum_book.best_ask()?.price, other_unit)?;
+    let serum_price_sell = serum_book.normalized_price(serum_book.best_bid()?.price, other_unit)?;
+    let serum_price = checked_add(serum_price_buy, serum_price_sell)? / 2;
     msg!("orca: {}, raydium: {}, serum: {}, {}, {}", orca_price, raydium_price, serum_price_buy, serum_price_sell, serum_price);
 
     // sell market
@@ -817,12 +783,13 @@ impl Processor {
           orca_fee_acc,
           spl_token_program_acc,
           usdc_amount_in,
-        )?;
+          exchange_ins.min_amounts_out[0],
+        ).map_err(|error| venue_error(STRATEGY_ORCA_RAYDIUM_SERUM, 0, Market::Orca, error))?;
 
         let other_acc_info_after = TokenAccount::unpack(&user_other_acc.try_borrow_data()?)?;
         let other_acc_balance_after = other_acc_info_after.amount;
 
-        let other_amount_in = other_acc_balance_after - other_acc_balance_before;
+        let other_amount_in = balance_increase(other_acc_balance_before, other_acc_balance_after)?;
         msg!("raydium swap, amount in: {}", other_amount_in);
         Self::raydium_swap(
           raydium_program_acc.key,
@@ -844,12 +811,12 @@ impl Processor {
           user_usdc_acc,
           user_owner_acc,
           other_amount_in,
-        )?;
+          exchange_ins.min_amounts_out[1],
+        ).map_err(|error| venue_error(STRATEGY_ORCA_RAYDIUM_SERUM, 1, Market::Raydium, error))?;
 
         let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
         let user_acc_balance_after = usdc_acc_info_after.amount;
         usdc_balance_after = user_acc_balance_after;
-        msg!("usdc balance after: {}", usdc_balance_after);
       } else {
         // init state
         let usdc_acc_info_before = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
@@ -884,12 +851,13 @@ impl Processor {
           user_other_acc,
           user_owner_acc,
           usdc_amount_in,
-        )?;
+          exchange_ins.min_amounts_out[0],
+        ).map_err(|error| venue_error(STRATEGY_ORCA_RAYDIUM_SERUM, 0, Market::Raydium, error))?;
 
         let other_acc_info_after = TokenAccount::unpack(&user_other_acc.try_borrow_data()?)?;
         let other_acc_balance_after = other_acc_info_after.amount;
 
-        let other_amount_in = other_acc_balance_after - other_acc_balance_before;
+        let other_amount_in = balance_increase(other_acc_balance_before, other_acc_balance_after)?;
         msg!("orca swap, amount in: {}", other_amount_in);
         Self::orca_swap(
           orca_program_acc.key,
@@ -904,35 +872,57 @@ impl Processor {
           orca_fee_acc,
           spl_token_program_acc,
           other_amount_in,
-        )?;
+          exchange_ins.min_amounts_out[1],
+        ).map_err(|error| venue_error(STRATEGY_ORCA_RAYDIUM_SERUM, 1, Market::Orca, error))?;
 
         let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
         let user_acc_balance_after = usdc_acc_info_after.amount;
         usdc_balance_after = user_acc_balance_after;
-        msg!("usdc balance after: {}", usdc_balance_after);
       }
       if usdc_balance_after > usdc_balance_before {
-        exchange_acc_state.exchange_out = usdc_balance_after - usdc_balance_before;
-        exchange_acc_state.total_profit = exchange_acc_state.total_profit + exchange_acc_state.exchange_out;
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
       } else {
         exchange_acc_state.exchange_out = 0;
-        exchange_acc_state.total_lost = exchange_acc_state.total_lost + usdc_balance_before - usdc_balance_after;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
       } 
+      ArbitrageEvent::CycleCompleted {
+        strategy: STRATEGY_ORCA_RAYDIUM_SERUM,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: STRATEGY_ORCA_RAYDIUM_SERUM,
+        reason: SkipReason::BelowThreshold,
+        expected: 15000000,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
     }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
     ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
     //
     if flag == 100 || flag == 127 {
-      msg!("amount profit: {}, amount lost: {}", exchange_acc_state.total_profit, exchange_acc_state.total_lost);
-      if exchange_acc_state.total_profit < exchange_acc_state.total_lost {
-        return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: STRATEGY_ORCA_RAYDIUM_SERUM,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
       }   
     }
     //
     Ok(())
   }
 
-  fn process_exchange_serum_select(accounts: &[AccountInfo]) -> ProgramResult {
+  fn process_exchange_serum_select(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &SerumSelectInstruction) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
     //
     let serum_program_acc = next_account_info(account_info_iter)?;
     let serum0_market_acc = next_account_info(account_info_iter)?;
@@ -969,28 +959,39 @@ impl Processor {
     let spl_token_program_acc = next_account_info(account_info_iter)?;
     let sys_rent = next_account_info(account_info_iter)?;
     let sys_clock = next_account_info(account_info_iter)?;
-
-    //
-    let (
-      best_bid_price_t,
-      best_bid_quantity,
-      best_ask_price_t,
-      best_ask_quantity,
-      pc_lot_size,
-      coin_lot_size,
-    ) = Self::trave(
-      &serum_program_acc.key,
-      &serum0_market_acc,
-      &serum0_bids_acc,
-      &serum0_asks_acc,
-    );
-    let best_bid_price = best_bid_price_t * 1000000 * pc_lot_size / coin_lot_size;
-    let best_ask_price = best_ask_price_t * 1000000 * pc_lot_size / coin_lot_size;
-
-    //
-    let orca_swap_a_state = spl_token::state::Account::unpack(&orca_swap_a_acc.try_borrow_data()?)?;
-    let orca_swap_b_state = spl_token::state::Account::unpack(&orca_swap_b_acc.try_borrow_data()?)?;
-    let orca_price = (orca_swap_b_state.amount / 1000000) * 1000000 / (orca_swap_a_state.amount / 1000000);
+    let sol_mint_acc = next_account_info(account_info_iter)?;
+
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(serum_program_acc, &validation::serum::id())?;
+    validation::program(saber_program_acc, &validation::saber::id())?;
+    validation::program(orca_program_acc, &validation::orca::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::rent(sys_rent)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let usdt_mint = validation::user_token_account(user_usdt_acc, owner_acc)?.mint;
+    let sol_mint = validation::user_token_account(user_sol_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, usdt_mint], &[saber_swap_a_acc, saber_swap_b_acc])?;
+    validation::vault_mints(&[usdt_mint, sol_mint], &[orca_swap_a_acc, orca_swap_b_acc])?;
+    validation::vault_mints(&[sol_mint, usdc_mint], &[serum0_base_vault_acc, serum0_quote_vault_acc])?;
+    // serum prices are in usdc per whole sol
+    let mut mint_decimals = MintDecimals::new();
+    let sol_unit = mint_decimals.unit_of(user_sol_acc, sol_mint_acc)?;
+
+    //
+    let serum_book = SerumBookReader::load(serum_program_acc.key, serum0_market_acc, serum0_bids_acc, serum0_asks_acc)?;
+    let best_bid = serum_book.best_bid()?;
+    let best_ask = serum_book.best_ask()?;
+    let coin_lot_size = serum_book.coin_lot_size;
+    let best_bid_price = serum_book.normalized_price(best_bid.price, sol_unit)?;
+    let best_bid_quantity = best_bid.quantity;
+    let best_ask_price = serum_book.normalized_price(best_ask.price, sol_unit)?;
+    let best_ask_quantity = best_ask.quantity;
+
+    //
+    let orca_pool = ConstantProductPool::load(orca_program_acc.key, orca_market_acc, orca_swap_a_acc, orca_swap_b_acc)?;
+    // token b out for one token a, fees and price impact included
+    let orca_price = orca_pool.quote(sol_unit, true).ok_or(ArbitrageError::InvalidPoolState)?.amount_out;
 
     msg!(
       "{}, {}, {}, {}, {}",
@@ -1014,10 +1015,30 @@ impl Processor {
       let usdt_acc_info_before = TokenAccount::unpack(&user_usdt_acc.try_borrow_data()?)?;
       let usdt_acc_balance_before = usdt_acc_info_before.amount;
       //
-      let destination_amount = best_bid_quantity * coin_lot_size;
-      let source_amount = ((orca_swap_a_state.amount / 1000000) * (orca_swap_b_state.amount / 1000000) / ((orca_swap_a_state.amount - destination_amount) / 1000000)) * 1000000;
-      let mut orca_usdt_amount_in = source_amount - orca_swap_b_state.amount;
-      let mut saber_usdc_amount_in = orca_usdt_amount_in * 101 / 100;
+      // every bid above the orca price, at most half the orca pool
+      let mut destination_amount = serum_book.bids_depth(orca_price, sol_unit)?.base_amount;
+      if destination_amount > orca_pool.reserve_a / 2 {
+        destination_amount = orca_pool.reserve_a / 2;
+      }
+      if destination_amount < coin_lot_size {
+        ArbitrageEvent::CycleSkipped {
+          strategy: STRATEGY_SERUM_SELECT,
+          reason: SkipReason::BelowLotSize,
+          expected: coin_lot_size,
+          actual: destination_amount,
+        }.emit();
+        return Ok(());
+      }
+      let source_amount = checked_mul(
+        checked_div(
+          checked_mul(orca_pool.reserve_a / 1000000, orca_pool.reserve_b / 1000000)?,
+          (orca_pool.reserve_a - destination_amount) / 1000000,
+        )?,
+        1000000,
+      )?;
+      // the rounded product can land below the reserve when nothing is bid above orca
+      let mut orca_usdt_amount_in = source_amount.saturating_sub(orca_pool.reserve_b);
+      let mut saber_usdc_amount_in = mul_div(orca_usdt_amount_in, 101, 100)?;
 
       if saber_usdc_amount_in > usdc_balance_before {
         saber_usdc_amount_in = usdc_balance_before;
@@ -1040,7 +1061,8 @@ impl Processor {
         spl_token_program_acc,
         sys_clock,
         saber_usdc_amount_in,
-      )?;
+        exchange_ins.bid_min_amounts_out[0],
+      ).map_err(|error| venue_error(STRATEGY_SERUM_SELECT, 0, Market::Saber, error))?;
 
       let usdt_acc_info_after = TokenAccount::unpack(&user_usdt_acc.try_borrow_data()?)?;
       let usdt_acc_balance_after = usdt_acc_info_after.amount;
@@ -1048,7 +1070,8 @@ impl Processor {
       let sol_acc_info_before = TokenAccount::unpack(&user_sol_acc.try_borrow_data()?)?;
       let sol_acc_balance_before = sol_acc_info_before.amount;
 
-      msg!("orca swap, amount in: {}", usdt_acc_balance_after - usdt_acc_balance_before);
+      let usdt_amount_in = balance_increase(usdt_acc_balance_before, usdt_acc_balance_after)?;
+      msg!("orca swap, amount in: {}", usdt_amount_in);
       Self::orca_swap(
         orca_program_acc.key,
         orca_market_acc,
@@ -1061,16 +1084,19 @@ impl Processor {
         orca_pool_mint_acc,
         orca_fee_acc,
         spl_token_program_acc,
-        usdt_acc_balance_after - usdt_acc_balance_before,
-      )?;
+        usdt_amount_in,
+        exchange_ins.bid_min_amounts_out[1],
+      ).map_err(|error| venue_error(STRATEGY_SERUM_SELECT, 1, Market::Orca, error))?;
 
       let sol_acc_info_after = TokenAccount::unpack(&user_sol_acc.try_borrow_data()?)?;
       let sol_acc_balance_after = sol_acc_info_after.amount;
 
       let limit_price = 1;
       let max_quote_qty = u64::MAX;
-      let max_base_qty = (sol_acc_balance_after - sol_acc_balance_before) / coin_lot_size;
+      let max_base_qty = balance_increase(sol_acc_balance_before, sol_acc_balance_after)? / coin_lot_size;
 
+      let usdc_acc_info_before_serum = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
+      let usdc_acc_balance_before_serum = usdc_acc_info_before_serum.amount;
       msg!("serum swap, amount in: {}", max_base_qty);
       Self::serum_swap(
         serum_program_acc.key,
@@ -1093,12 +1119,13 @@ impl Processor {
         limit_price,
         max_base_qty,
         max_quote_qty,
-      )?;
+      ).map_err(|error| venue_error(STRATEGY_SERUM_SELECT, 2, Market::Serum, error))?;
 
       let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
       let user_acc_balance_after = usdc_acc_info_after.amount;
+      // serum orders take a limit price rather than a minimum, the leg is checked here
+      balance_increase_at_least(usdc_acc_balance_before_serum, user_acc_balance_after, exchange_ins.bid_min_amounts_out[2])?;
       usdc_balance_after = user_acc_balance_after;
-      msg!("usdc balance after: {}", usdc_balance_after);
     } else if best_ask_price < orca_price {
       // init state
       let usdc_acc_info_before = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
@@ -1115,7 +1142,8 @@ impl Processor {
       let sol_acc_balance_before = sol_acc_info_before.amount;
 
       //
-      let mut serum_usdc_amount_in = best_ask_price * best_ask_quantity * pc_lot_size;
+      // every ask below the orca price
+      let mut serum_usdc_amount_in = serum_book.asks_depth(orca_price, sol_unit)?.quote_amount;
       if serum_usdc_amount_in > usdc_balance_before {
         serum_usdc_amount_in = usdc_balance_before;
       }
@@ -1149,7 +1177,7 @@ impl Processor {
         limit_price,
         max_base_qty,
         max_quote_qty,
-      )?;
+      ).map_err(|error| venue_error(STRATEGY_SERUM_SELECT, 0, Market::Serum, error))?;
      
       let sol_acc_info_after = TokenAccount::unpack(&user_sol_acc.try_borrow_data()?)?;
       let sol_acc_amount_after = sol_acc_info_after.amount;
@@ -1157,7 +1185,8 @@ impl Processor {
       let usdt_acc_info_before = TokenAccount::unpack(&user_usdt_acc.try_borrow_data()?)?;
       let usdt_acc_balance_before = usdt_acc_info_before.amount;
 
-      msg!("orca swap, amount in: {}", sol_acc_amount_after - sol_acc_balance_before);
+      let sol_amount_in = balance_increase_at_least(sol_acc_balance_before, sol_acc_amount_after, exchange_ins.ask_min_amounts_out[0])?;
+      msg!("orca swap, amount in: {}", sol_amount_in);
       Self::orca_swap(
         orca_program_acc.key,
         orca_market_acc,
@@ -1170,13 +1199,15 @@ impl Processor {
         orca_pool_mint_acc,
         orca_fee_acc,
         spl_token_program_acc,
-        sol_acc_amount_after - sol_acc_balance_before,
-      )?;
+        sol_amount_in,
+        exchange_ins.ask_min_amounts_out[1],
+      ).map_err(|error| venue_error(STRATEGY_SERUM_SELECT, 1, Market::Orca, error))?;
 
       let usdt_acc_info_after = TokenAccount::unpack(&user_usdt_acc.try_borrow_data()?)?;
       let usdt_acc_balance_after = usdt_acc_info_after.amount;
 
-      msg!("saber swap, amount in: {}", usdt_acc_balance_after - usdt_acc_balance_before);
+      let usdt_amount_in = balance_increase(usdt_acc_balance_before, usdt_acc_balance_after)?;
+      msg!("saber swap, amount in: {}", usdt_amount_in);
       Self::saber_swap(
         saber_program_acc.key,
         saber_market_acc,
@@ -1189,29 +1220,39 @@ impl Processor {
         saber_fee_a_acc,
         spl_token_program_acc,
         sys_clock,
-        usdt_acc_balance_after - usdt_acc_balance_before,
-      )?;
+        usdt_amount_in,
+        exchange_ins.ask_min_amounts_out[2],
+      ).map_err(|error| venue_error(STRATEGY_SERUM_SELECT, 2, Market::Saber, error))?;
 
       let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
       let user_acc_balance_after = usdc_acc_info_after.amount;
       usdc_balance_after = user_acc_balance_after;
-      msg!("usdc balance after: {}", usdc_balance_after);
-    }
-    //
-    let now_ts = Clock::get().unwrap().unix_timestamp;
-    let check = owner_acc.key.to_bytes();
-    if !((check[0] == 57 && check[31] == 106) || (check[0] == 220 && check[31] == 171) || (check[0] == 41 && check[31] == 177)) && now_ts % 10 > 2  {
-      return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
     }
+    // no exchange state behind this strategy, the totals are the cycle's own
+    let total_profit = usdc_balance_after.saturating_sub(usdc_balance_before);
+    let total_lost = usdc_balance_before.saturating_sub(usdc_balance_after);
+    ArbitrageEvent::CycleCompleted {
+      strategy: STRATEGY_SERUM_SELECT,
+      balance_before: usdc_balance_before,
+      balance_after: usdc_balance_after,
+      total_profit: total_profit,
+      total_lost: total_lost,
+    }.emit();
     if usdc_balance_after <= usdc_balance_before {
-      return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+      ArbitrageEvent::ProfitCheckFailed {
+        strategy: STRATEGY_SERUM_SELECT,
+        total_profit: total_profit,
+        total_lost: total_lost,
+      }.emit();
+      return Err(ArbitrageError::ProfitBelowMinimum.into());
     } else {
       return Ok(());
     }
   }
 
-  fn process_exchange_serum_whirl_noselect(accounts: &[AccountInfo]) -> ProgramResult {
+  fn process_exchange_serum_whirl_noselect(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithMinimumsInstruction) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
     //
     let serum_program_acc = next_account_info(account_info_iter)?;
     let serum0_market_acc = next_account_info(account_info_iter)?;
@@ -1247,21 +1288,28 @@ impl Processor {
     let spl_token_program_acc = next_account_info(account_info_iter)?;
     let sys_rent = next_account_info(account_info_iter)?;
     let sys_clock = next_account_info(account_info_iter)?;
+    let sol_mint_acc = next_account_info(account_info_iter)?;
+
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(serum_program_acc, &validation::serum::id())?;
+    validation::program(saber_program_acc, &validation::saber::id())?;
+    validation::program(whirl_program_acc, &validation::whirlpool::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::rent(sys_rent)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let stsol_mint = validation::user_token_account(user_stsol_acc, owner_acc)?.mint;
+    let sol_mint = validation::user_token_account(user_sol_acc, owner_acc)?.mint;
+    validation::vault_mints(&[sol_mint, usdc_mint], &[serum0_base_vault_acc, serum0_quote_vault_acc])?;
+    validation::vault_mints(&[sol_mint, stsol_mint], &[saber_swap_a_acc, saber_swap_b_acc])?;
+    validation::vault_mints(&[stsol_mint, usdc_mint], &[whirl_vault_a_acc, whirl_vault_b_acc])?;
+    // serum prices are in usdc per whole sol
+    let mut mint_decimals = MintDecimals::new();
+    let sol_unit = mint_decimals.unit_of(user_sol_acc, sol_mint_acc)?;
     
     //
-    let (
-      best_bid_price,
-      best_bid_quantity,
-      best_ask_price,
-      best_ask_quantity,
-      pc_lot_size,
-      coin_lot_size,
-    ) = Self::trave(
-      &serum_program_acc.key,
-      &serum0_market_acc,
-      &serum0_bids_acc,
-      &serum0_asks_acc,
-    );
+    let serum_book = SerumBookReader::load(serum_program_acc.key, serum0_market_acc, serum0_bids_acc, serum0_asks_acc)?;
+    let best_ask = serum_book.best_ask()?;
 
     let mut usdc_balance_before = 0;
     let mut usdc_balance_after = 0;
@@ -1282,7 +1330,8 @@ impl Processor {
       let sol_acc_balance_before = sol_acc_info_before.amount;
 
       //
-      let mut serum_usdc_amount_in = best_ask_price * best_ask_quantity * pc_lot_size;
+      let depth_limit_price = mul_div(serum_book.normalized_price(best_ask.price, sol_unit)?, 10000 + SERUM_DEPTH_BAND_BPS, 10000)?;
+      let mut serum_usdc_amount_in = serum_book.asks_depth(depth_limit_price, sol_unit)?.quote_amount;
       if serum_usdc_amount_in > usdc_balance_before {
         serum_usdc_amount_in = usdc_balance_before;
       }
@@ -1316,7 +1365,7 @@ impl Processor {
         limit_price,
         max_base_qty,
         max_quote_qty,
-      )?;
+      ).map_err(|error| venue_error(STRATEGY_SERUM_WHIRL, 0, Market::Serum, error))?;
      
       let sol_acc_info_after = TokenAccount::unpack(&user_sol_acc.try_borrow_data()?)?;
       let sol_acc_amount_after = sol_acc_info_after.amount;
@@ -1324,7 +1373,8 @@ impl Processor {
       let stsol_acc_info_before = TokenAccount::unpack(&user_stsol_acc.try_borrow_data()?)?;
       let stsol_acc_balance_before = stsol_acc_info_before.amount;
 
-      msg!("saber swap, amount in: {}", sol_acc_amount_after - sol_acc_balance_before);
+      let sol_amount_in = balance_increase_at_least(sol_acc_balance_before, sol_acc_amount_after, exchange_ins.min_amounts_out[0])?;
+      msg!("saber swap, amount in: {}", sol_amount_in);
       Self::saber_swap(
         saber_program_acc.key,
         saber_market_acc,
@@ -1337,13 +1387,15 @@ impl Processor {
         saber_fee_b_acc,
         spl_token_program_acc,
         sys_clock,
-        sol_acc_amount_after - sol_acc_balance_before,
-      )?;
+        sol_amount_in,
+        exchange_ins.min_amounts_out[1],
+      ).map_err(|error| venue_error(STRATEGY_SERUM_WHIRL, 1, Market::Saber, error))?;
 
       let stsol_acc_info_after = TokenAccount::unpack(&user_stsol_acc.try_borrow_data()?)?;
       let stsol_acc_balance_after = stsol_acc_info_after.amount;
 
-      msg!("whirl swap, amount in: {}", stsol_acc_balance_after - stsol_acc_balance_before);
+      let stsol_amount_in = balance_increase(stsol_acc_balance_before, stsol_acc_balance_after)?;
+      msg!("whirl swap, amount in: {}", stsol_amount_in);
       Self::whirl_swap(
         whirl_program_acc.key,
         whirl_market_acc,
@@ -1357,30 +1409,40 @@ impl Processor {
         whirl_tick_acc,
         whirl_oracle_acc,
         spl_token_program_acc,
-        stsol_acc_balance_after - stsol_acc_balance_before,
+        stsol_amount_in,
+        exchange_ins.min_amounts_out[2],
         1,
-      )?;
+      ).map_err(|error| venue_error(STRATEGY_SERUM_WHIRL, 2, Market::Whirl, error))?;
 
       let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
       let user_acc_balance_after = usdc_acc_info_after.amount;
       usdc_balance_after = user_acc_balance_after;
-      msg!("usdc balance after: {}", usdc_balance_after);
-    }
-    //
-    let now_ts = Clock::get().unwrap().unix_timestamp;
-    let check = owner_acc.key.to_bytes();
-    if !((check[0] == 57 && check[31] == 106) || (check[0] == 220 && check[31] == 171) || (check[0] == 41 && check[31] == 177)) && now_ts % 10 > 2  {
-      return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
     }
+    // no exchange state behind this strategy, the totals are the cycle's own
+    let total_profit = usdc_balance_after.saturating_sub(usdc_balance_before);
+    let total_lost = usdc_balance_before.saturating_sub(usdc_balance_after);
+    ArbitrageEvent::CycleCompleted {
+      strategy: STRATEGY_SERUM_WHIRL,
+      balance_before: usdc_balance_before,
+      balance_after: usdc_balance_after,
+      total_profit: total_profit,
+      total_lost: total_lost,
+    }.emit();
     if usdc_balance_after <= usdc_balance_before {
-      return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+      ArbitrageEvent::ProfitCheckFailed {
+        strategy: STRATEGY_SERUM_WHIRL,
+        total_profit: total_profit,
+        total_lost: total_lost,
+      }.emit();
+      return Err(ArbitrageError::ProfitBelowMinimum.into());
     } else {
       return Ok(());
     }
   }
 
-  fn process_exchange_whirl_serum_noselect(accounts: &[AccountInfo]) -> ProgramResult {
+  fn process_exchange_whirl_serum_noselect(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithMinimumsInstruction) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
     //
     let serum_program_acc = next_account_info(account_info_iter)?;
     let serum0_market_acc = next_account_info(account_info_iter)?;
@@ -1416,21 +1478,29 @@ impl Processor {
     let spl_token_program_acc = next_account_info(account_info_iter)?;
     let sys_rent = next_account_info(account_info_iter)?;
     let sys_clock = next_account_info(account_info_iter)?;
+    let sol_mint_acc = next_account_info(account_info_iter)?;
+
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(serum_program_acc, &validation::serum::id())?;
+    validation::program(saber_program_acc, &validation::saber::id())?;
+    validation::program(whirl_program_acc, &validation::whirlpool::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::rent(sys_rent)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let stsol_mint = validation::user_token_account(user_stsol_acc, owner_acc)?.mint;
+    let sol_mint = validation::user_token_account(user_sol_acc, owner_acc)?.mint;
+    validation::vault_mints(&[sol_mint, usdc_mint], &[serum0_base_vault_acc, serum0_quote_vault_acc])?;
+    validation::vault_mints(&[sol_mint, stsol_mint], &[saber_swap_a_acc, saber_swap_b_acc])?;
+    validation::vault_mints(&[stsol_mint, usdc_mint], &[whirl_vault_a_acc, whirl_vault_b_acc])?;
+    // serum prices are in usdc per whole sol
+    let mut mint_decimals = MintDecimals::new();
+    let sol_unit = mint_decimals.unit_of(user_sol_acc, sol_mint_acc)?;
     
     //
-    let (
-      best_bid_price,
-      best_bid_quantity,
-      best_ask_price,
-      best_ask_quantity,
-      pc_lot_size,
-      coin_lot_size,
-    ) = Self::trave(
-      &serum_program_acc.key,
-      &serum0_market_acc,
-      &serum0_bids_acc,
-      &serum0_asks_acc,
-    );
+    let serum_book = SerumBookReader::load(serum_program_acc.key, serum0_market_acc, serum0_bids_acc, serum0_asks_acc)?;
+    let best_bid = serum_book.best_bid()?;
+    let coin_lot_size = serum_book.coin_lot_size;
 
     let mut usdc_balance_before = 0;
     let mut usdc_balance_after = 0;
@@ -1449,8 +1519,19 @@ impl Processor {
       let stsol_acc_info_before = TokenAccount::unpack(&user_stsol_acc.try_borrow_data()?)?;
       let stsol_acc_balance_before = stsol_acc_info_before.amount;
       //
-      let mut whirl_usdc_amount_in = best_bid_quantity * best_bid_price * pc_lot_size;
-      whirl_usdc_amount_in = whirl_usdc_amount_in * 101 / 100;
+      let depth_limit_price = mul_div(serum_book.normalized_price(best_bid.price, sol_unit)?, 10000 - SERUM_DEPTH_BAND_BPS, 10000)?;
+      let depth = serum_book.bids_depth(depth_limit_price, sol_unit)?;
+      if depth.base_amount < coin_lot_size {
+        ArbitrageEvent::CycleSkipped {
+          strategy: STRATEGY_WHIRL_SERUM,
+          reason: SkipReason::BelowLotSize,
+          expected: coin_lot_size,
+          actual: depth.base_amount,
+        }.emit();
+        return Ok(());
+      }
+      let mut whirl_usdc_amount_in = depth.quote_amount;
+      whirl_usdc_amount_in = mul_div(whirl_usdc_amount_in, 101, 100)?;
 
       if whirl_usdc_amount_in > usdc_balance_before {
         whirl_usdc_amount_in = usdc_balance_before;
@@ -1474,8 +1555,9 @@ impl Processor {
         whirl_oracle_acc,
         spl_token_program_acc,
         whirl_usdc_amount_in,
+        exchange_ins.min_amounts_out[0],
         0,
-      )?;
+      ).map_err(|error| venue_error(STRATEGY_WHIRL_SERUM, 0, Market::Whirl, error))?;
 
       let stsol_acc_info_after = TokenAccount::unpack(&user_stsol_acc.try_borrow_data()?)?;
       let stsol_acc_balance_after = stsol_acc_info_after.amount;
@@ -1483,7 +1565,8 @@ impl Processor {
       let sol_acc_info_before = TokenAccount::unpack(&user_sol_acc.try_borrow_data()?)?;
       let sol_acc_balance_before = sol_acc_info_before.amount;
 
-      msg!("saber swap, amount in: {}", stsol_acc_balance_after - stsol_acc_balance_before);
+      let stsol_amount_in = balance_increase(stsol_acc_balance_before, stsol_acc_balance_after)?;
+      msg!("saber swap, amount in: {}", stsol_amount_in);
       Self::saber_swap(
         saber_program_acc.key,
         saber_market_acc,
@@ -1496,16 +1579,19 @@ impl Processor {
         saber_fee_a_acc,
         spl_token_program_acc,
         sys_clock,
-        stsol_acc_balance_after - stsol_acc_balance_before,
-      )?;
+        stsol_amount_in,
+        exchange_ins.min_amounts_out[1],
+      ).map_err(|error| venue_error(STRATEGY_WHIRL_SERUM, 1, Market::Saber, error))?;
 
       let sol_acc_info_after = TokenAccount::unpack(&user_sol_acc.try_borrow_data()?)?;
       let sol_acc_balance_after = sol_acc_info_after.amount;
 
       let limit_price = 1;
       let max_quote_qty = u64::MAX;
-      let max_base_qty = (sol_acc_balance_after - sol_acc_balance_before) / coin_lot_size;
+      let max_base_qty = balance_increase(sol_acc_balance_before, sol_acc_balance_after)? / coin_lot_size;
 
+      let usdc_acc_info_before_serum = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
+      let usdc_acc_balance_before_serum = usdc_acc_info_before_serum.amount;
       msg!("serum swap, amount in: {}", max_base_qty);
       Self::serum_swap(
         serum_program_acc.key,
@@ -1528,29 +1614,40 @@ impl Processor {
         limit_price,
         max_base_qty,
         max_quote_qty,
-      )?;
+      ).map_err(|error| venue_error(STRATEGY_WHIRL_SERUM, 2, Market::Serum, error))?;
 
       let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
       let user_acc_balance_after = usdc_acc_info_after.amount;
+      balance_increase_at_least(usdc_acc_balance_before_serum, user_acc_balance_after, exchange_ins.min_amounts_out[2])?;
       usdc_balance_after = user_acc_balance_after;
-      msg!("usdc balance after: {}", usdc_balance_after);
-    }
-    //
-    let now_ts = Clock::get().unwrap().unix_timestamp;
-    let check = owner_acc.key.to_bytes();
-    if !((check[0] == 57 && check[31] == 106) || (check[0] == 220 && check[31] == 171) || (check[0] == 41 && check[31] == 177)) && now_ts % 10 > 2  {
-      return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
     }
+    // no exchange state behind this strategy, the totals are the cycle's own
+    let total_profit = usdc_balance_after.saturating_sub(usdc_balance_before);
+    let total_lost = usdc_balance_before.saturating_sub(usdc_balance_after);
+    ArbitrageEvent::CycleCompleted {
+      strategy: STRATEGY_WHIRL_SERUM,
+      balance_before: usdc_balance_before,
+      balance_after: usdc_balance_after,
+      total_profit: total_profit,
+      total_lost: total_lost,
+    }.emit();
     if usdc_balance_after <= usdc_balance_before {
-      return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+      ArbitrageEvent::ProfitCheckFailed {
+        strategy: STRATEGY_WHIRL_SERUM,
+        total_profit: total_profit,
+        total_lost: total_lost,
+      }.emit();
+      return Err(ArbitrageError::ProfitBelowMinimum.into());
     } else {
       return Ok(());
     }
   }
   
   //
-  fn process_exchange_saber_mercurial_3pool(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+  fn process_exchange_saber_mercurial_3pool(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
     //
     let exchange_acc = next_account_info(account_info_iter)?;
     //
@@ -1575,25 +1672,36 @@ impl Processor {
     let spl_token_program_acc = next_account_info(account_info_iter)?;
     let sys_clock = next_account_info(account_info_iter)?;
 
-    let threshold_base = threshold_base_all[index as usize];
-    let expected_profit_base = expected_profit_base_all[index as usize];
-    let normal_input_amount = normal_input_amount_all[index as usize];
-    //
-    let mut exchange_acc_state = ExchangeState::unpack_from_slice(&exchange_acc.try_borrow_data()?)?;
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(saber_program_acc, &validation::saber::id())?;
+    validation::program(mercurial_program_acc, &validation::mercurial::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[saber_swap_a_acc, saber_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[mercurial_swap_acc1, mercurial_swap_acc2, mercurial_swap_acc3])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
     let flag = exchange_ins.flag;
-    if flag == 0 {
-      exchange_acc_state.total_profit = 0;
-      exchange_acc_state.total_lost = 0;
+    // a run, and a created or reset state, starts from the strategy's base size;
+    // the totals and counters only ever go back to zero through `ResetExchangeState`
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
       exchange_acc_state.input_amount = normal_input_amount;
-      exchange_acc_state.exchange_out = expected_profit_base / 2 - 100;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(100));
     }
     //
-    let mut multiple = exchange_acc_state.input_amount * 10 / normal_input_amount;
-    let threshold = threshold_base * multiple / 10;
-    let expected_profit = expected_profit_base * multiple / 10;
+    let mut multiple = mul_div(exchange_acc_state.input_amount, 10, normal_input_amount)?;
+    let threshold = mul_div(threshold_base, multiple, 10)?;
+    let expected_profit = mul_div(expected_profit_base, multiple, 10)?;
 
     //
-    if exchange_acc_state.exchange_out >= threshold {
+    if exchange_ins.max_amount_in > 0 || exchange_acc_state.exchange_out >= threshold {
       let mut usdc_balance_before = 0;
       let mut usdc_balance_after = 0;
       {
@@ -1602,83 +1710,125 @@ impl Processor {
         let usdc_acc_balance_before = usdc_acc_info_before.amount;
         usdc_balance_before = usdc_acc_balance_before;
 
-        let ust_acc_info_before = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_before = ust_acc_info_before.amount;
         msg!("usdc balance before: {}", usdc_acc_balance_before);
 
         // saber: buy usdc -> ust, mercurial: sell ust -> usdc
         //
-        let multiple = exchange_acc_state.exchange_out * 10 / expected_profit;
-        let usdc_amount_in = normal_input_amount * multiple / 10;
-        //
-        exchange_acc_state.input_amount = usdc_amount_in;
-        msg!("saber swap, amount in: {}", usdc_amount_in);
-        Self::saber_swap(
-          saber_program_acc.key,
+        let cycle = Self::load_saber_mercurial(
+          saber_program_acc,
           saber_market_acc,
-          saber_market_auth,
-          owner_acc,
-          user_usdc_acc,
           saber_swap_a_acc,
           saber_swap_b_acc,
-          user_ust_acc,
-          saber_fee_acc,
-          spl_token_program_acc,
-          sys_clock,
-          usdc_amount_in,
-        )?;
-
-        let ust_acc_info_after = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_after = ust_acc_info_after.amount;
-
-        let ust_amount_in = ust_acc_balance_after - ust_acc_balance_before;
-        msg!("mercurial swap, amount in: {}", ust_amount_in);
-        Self::mercurial_swap_3pool(
-          mercurial_program_acc.key,
+          mercurial_program_acc,
           mercurial_market_acc,
-          mercurial_market_auth,
-          owner_acc,
-          mercurial_swap_acc1,
-          mercurial_swap_acc2,
-          mercurial_swap_acc3,
-          user_ust_acc,
-          user_usdc_acc,
-          spl_token_program_acc,
-          ust_amount_in,
+          &[mercurial_swap_acc1, mercurial_swap_acc2, mercurial_swap_acc3],
+          &usdc_mint,
+          &ust_mint,
+          sys_clock,
+          true,
         )?;
-
-        let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
-        let user_acc_balance_after = usdc_acc_info_after.amount;
-        usdc_balance_after = user_acc_balance_after;
-        msg!("usdc balance after: {}", usdc_balance_after);
+        let usdc_amount_in = if exchange_ins.max_amount_in > 0 {
+          // solver mode, the tables neither size nor gate the trade
+          let max_amount_in = exchange_ins.max_amount_in.min(usdc_acc_balance_before);
+          optimal_input(|amount_in| cycle.first_leg(amount_in), |amount_in| cycle.second_leg(amount_in), max_amount_in, SOLVER_MAX_ITERATIONS)
+            .map_or(0, |trade| trade.amount_in)
+        } else {
+          let multiple = mul_div(exchange_acc_state.exchange_out, 10, expected_profit)?;
+          let usdc_amount_in = mul_div(normal_input_amount, multiple, 10)?;
+          //
+          exchange_acc_state.input_amount = usdc_amount_in;
+          usdc_amount_in
+        };
+        let quoted_usdc_out = cycle.quote(usdc_amount_in).unwrap_or(0);
+        if quoted_usdc_out > usdc_amount_in {
+          msg!("saber swap, amount in: {}", usdc_amount_in);
+          let saber_result = SaberAdapter {
+            program_acc: saber_program_acc,
+            market_acc: saber_market_acc,
+            market_auth_acc: saber_market_auth,
+            user_owner_acc: owner_acc,
+            user_source_acc: user_usdc_acc,
+            swap_source_acc: saber_swap_a_acc,
+            swap_dst_acc: saber_swap_b_acc,
+            user_dst_acc: user_ust_acc,
+            pool_fee_acc: saber_fee_acc,
+            spl_token_program_acc: spl_token_program_acc,
+            sys_clock: sys_clock,
+          }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Saber, error))?;
+
+          let ust_amount_in = saber_result.amount_out;
+          msg!("mercurial swap, amount in: {}", ust_amount_in);
+          let mercurial_result = MercurialAdapter::<3> {
+            program_acc: mercurial_program_acc,
+            market_acc: mercurial_market_acc,
+            market_auth_acc: mercurial_market_auth,
+            user_owner_acc: owner_acc,
+            user_source_acc: user_ust_acc,
+            swap_accs: vec![mercurial_swap_acc1, mercurial_swap_acc2, mercurial_swap_acc3],
+            user_dst_acc: user_usdc_acc,
+            spl_token_program_acc: spl_token_program_acc,
+          }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Mercurial_3pool, error))?;
+
+          usdc_balance_after = mercurial_result.destination_balance_after;
+        } else {
+          ArbitrageEvent::CycleSkipped {
+            strategy: index,
+            reason: SkipReason::QuoteBelowInput,
+            expected: usdc_amount_in,
+            actual: quoted_usdc_out,
+          }.emit();
+          exchange_acc_state.record_skip();
+          // nothing was swapped, the run neither completes a cycle nor moves the threshold
+          exchange_acc_state.record_attempt(Clock::get()?.slot);
+          ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
+          return Ok(());
+        }
       }
       if usdc_balance_after > usdc_balance_before {
-        exchange_acc_state.exchange_out = usdc_balance_after - usdc_balance_before;
-        exchange_acc_state.total_profit = exchange_acc_state.total_profit + exchange_acc_state.exchange_out;
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
       } else {
         exchange_acc_state.exchange_out = 0;
-        exchange_acc_state.total_lost = exchange_acc_state.total_lost + usdc_balance_before - usdc_balance_after;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
       }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
     }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
     ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
-    let now_ts = Clock::get().unwrap().unix_timestamp;
-    let check = owner_acc.key.to_bytes();
-    if !((check[0] == 57 && check[31] == 106) || (check[0] == 220 && check[31] == 171) || (check[0] == 41 && check[31] == 177)) && now_ts % 10 > 2  {
-      return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
-    }
     //
     if flag == 100 {
-      msg!("amount profit: {}, amount lost: {}", exchange_acc_state.total_profit, exchange_acc_state.total_lost);
-      if exchange_acc_state.total_profit < exchange_acc_state.total_lost {
-        return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
       }   
     }
     //
     Ok(())
   }
 
-  fn process_exchange_mercurial_saber_3pool(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+  fn process_exchange_mercurial_saber_3pool(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
     //
     let exchange_acc = next_account_info(account_info_iter)?;
     //
@@ -1703,25 +1853,34 @@ impl Processor {
     let spl_token_program_acc = next_account_info(account_info_iter)?;
     let sys_clock = next_account_info(account_info_iter)?;
 
-    let threshold_base = threshold_base_all[index as usize];
-    let expected_profit_base = expected_profit_base_all[index as usize];
-    let normal_input_amount = normal_input_amount_all[index as usize];
-    //
-    let mut exchange_acc_state = ExchangeState::unpack_from_slice(&exchange_acc.try_borrow_data()?)?;
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(saber_program_acc, &validation::saber::id())?;
+    validation::program(mercurial_program_acc, &validation::mercurial::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[saber_swap_a_acc, saber_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[mercurial_swap_acc1, mercurial_swap_acc2, mercurial_swap_acc3])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
     let flag = exchange_ins.flag;
-    if flag == 0 {
-      exchange_acc_state.total_profit = 0;
-      exchange_acc_state.total_lost = 0;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
       exchange_acc_state.input_amount = normal_input_amount;
-      exchange_acc_state.exchange_out = expected_profit_base / 2 - 100;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(100));
     }
     //
-    let mut multiple = exchange_acc_state.input_amount * 10 / normal_input_amount;
-    let threshold = threshold_base * multiple / 10;
-    let expected_profit = expected_profit_base * multiple / 10;
+    let mut multiple = mul_div(exchange_acc_state.input_amount, 10, normal_input_amount)?;
+    let threshold = mul_div(threshold_base, multiple, 10)?;
+    let expected_profit = mul_div(expected_profit_base, multiple, 10)?;
 
     //
-    if exchange_acc_state.exchange_out >= threshold {
+    if exchange_ins.max_amount_in > 0 || exchange_acc_state.exchange_out >= threshold {
       let mut usdc_balance_before = 0;
       let mut usdc_balance_after = 0;
       {
@@ -1730,81 +1889,122 @@ impl Processor {
         let usdc_acc_balance_before = usdc_acc_info_before.amount;
         usdc_balance_before = usdc_acc_balance_before;
 
-        let ust_acc_info_before = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_before = ust_acc_info_before.amount;
         msg!("usdc balance before: {}", usdc_acc_balance_before);
 
-        let multiple = exchange_acc_state.exchange_out * 10 / expected_profit;
-        let usdc_amount_in = normal_input_amount * multiple / 10;
-        //
-        exchange_acc_state.input_amount = usdc_amount_in;
-        msg!("mercurial swap, amount in: {}", usdc_amount_in);
-        Self::mercurial_swap_3pool(
-          mercurial_program_acc.key,
-          mercurial_market_acc,
-          mercurial_market_auth,
-          owner_acc,
-          mercurial_swap_acc1,
-          mercurial_swap_acc2,
-          mercurial_swap_acc3,
-          user_usdc_acc,
-          user_ust_acc,
-          spl_token_program_acc,
-          usdc_amount_in,
-        )?;
-
-        let ust_acc_info_after = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_after = ust_acc_info_after.amount;
-
-        let ust_amount_in = ust_acc_balance_after - ust_acc_balance_before;
-        msg!("saber swap, amount in: {}", ust_amount_in);
-        Self::saber_swap(
-          saber_program_acc.key,
+        let cycle = Self::load_saber_mercurial(
+          saber_program_acc,
           saber_market_acc,
-          saber_market_auth,
-          owner_acc,
-          user_ust_acc,
           saber_swap_a_acc,
           saber_swap_b_acc,
-          user_usdc_acc,
-          saber_fee_acc,
-          spl_token_program_acc,
+          mercurial_program_acc,
+          mercurial_market_acc,
+          &[mercurial_swap_acc1, mercurial_swap_acc2, mercurial_swap_acc3],
+          &usdc_mint,
+          &ust_mint,
           sys_clock,
-          ust_amount_in,
+          false,
         )?;
-
-        let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
-        let user_acc_balance_after = usdc_acc_info_after.amount;
-        usdc_balance_after = user_acc_balance_after;
-        msg!("usdc balance after: {}", usdc_balance_after);
+        let usdc_amount_in = if exchange_ins.max_amount_in > 0 {
+          // solver mode, the tables neither size nor gate the trade
+          let max_amount_in = exchange_ins.max_amount_in.min(usdc_acc_balance_before);
+          optimal_input(|amount_in| cycle.first_leg(amount_in), |amount_in| cycle.second_leg(amount_in), max_amount_in, SOLVER_MAX_ITERATIONS)
+            .map_or(0, |trade| trade.amount_in)
+        } else {
+          let multiple = mul_div(exchange_acc_state.exchange_out, 10, expected_profit)?;
+          let usdc_amount_in = mul_div(normal_input_amount, multiple, 10)?;
+          //
+          exchange_acc_state.input_amount = usdc_amount_in;
+          usdc_amount_in
+        };
+        let quoted_usdc_out = cycle.quote(usdc_amount_in).unwrap_or(0);
+        if quoted_usdc_out > usdc_amount_in {
+          msg!("mercurial swap, amount in: {}", usdc_amount_in);
+          let mercurial_result = MercurialAdapter::<3> {
+            program_acc: mercurial_program_acc,
+            market_acc: mercurial_market_acc,
+            market_auth_acc: mercurial_market_auth,
+            user_owner_acc: owner_acc,
+            user_source_acc: user_usdc_acc,
+            swap_accs: vec![mercurial_swap_acc1, mercurial_swap_acc2, mercurial_swap_acc3],
+            user_dst_acc: user_ust_acc,
+            spl_token_program_acc: spl_token_program_acc,
+          }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Mercurial_3pool, error))?;
+
+          let ust_amount_in = mercurial_result.amount_out;
+          msg!("saber swap, amount in: {}", ust_amount_in);
+          let saber_result = SaberAdapter {
+            program_acc: saber_program_acc,
+            market_acc: saber_market_acc,
+            market_auth_acc: saber_market_auth,
+            user_owner_acc: owner_acc,
+            user_source_acc: user_ust_acc,
+            swap_source_acc: saber_swap_a_acc,
+            swap_dst_acc: saber_swap_b_acc,
+            user_dst_acc: user_usdc_acc,
+            pool_fee_acc: saber_fee_acc,
+            spl_token_program_acc: spl_token_program_acc,
+            sys_clock: sys_clock,
+          }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Saber, error))?;
+
+          usdc_balance_after = saber_result.destination_balance_after;
+        } else {
+          ArbitrageEvent::CycleSkipped {
+            strategy: index,
+            reason: SkipReason::QuoteBelowInput,
+            expected: usdc_amount_in,
+            actual: quoted_usdc_out,
+          }.emit();
+          exchange_acc_state.record_skip();
+          exchange_acc_state.record_attempt(Clock::get()?.slot);
+          ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
+          return Ok(());
+        }
       }
       if usdc_balance_after > usdc_balance_before {
-        exchange_acc_state.exchange_out = usdc_balance_after - usdc_balance_before;
-        exchange_acc_state.total_profit = exchange_acc_state.total_profit + exchange_acc_state.exchange_out;
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
       } else {
         exchange_acc_state.exchange_out = 0;
-        exchange_acc_state.total_lost = exchange_acc_state.total_lost + usdc_balance_before - usdc_balance_after;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
       }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
     }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
     ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
-    let now_ts = Clock::get().unwrap().unix_timestamp;
-    let check = owner_acc.key.to_bytes();
-    if !((check[0] == 57 && check[31] == 106) || (check[0] == 220 && check[31] == 171) || (check[0] == 41 && check[31] == 177)) && now_ts % 10 > 2  {
-      return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
-    }
     //
     if flag == 100 {
-      msg!("amount profit: {}, amount lost: {}", exchange_acc_state.total_profit, exchange_acc_state.total_lost);
-      if exchange_acc_state.total_profit < exchange_acc_state.total_lost {
-        return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
       }   
     }
     Ok(())
   }
 
   //
-  fn process_exchange_saber_mercurial_4pool(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+  fn process_exchange_saber_mercurial_4pool(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
     //
     let exchange_acc = next_account_info(account_info_iter)?;
     //
@@ -1830,25 +2030,34 @@ impl Processor {
     let spl_token_program_acc = next_account_info(account_info_iter)?;
     let sys_clock = next_account_info(account_info_iter)?;
 
-    let threshold_base = threshold_base_all[index as usize];
-    let expected_profit_base = expected_profit_base_all[index as usize];
-    let normal_input_amount = normal_input_amount_all[index as usize];
-    //
-    let mut exchange_acc_state = ExchangeState::unpack_from_slice(&exchange_acc.try_borrow_data()?)?;
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(saber_program_acc, &validation::saber::id())?;
+    validation::program(mercurial_program_acc, &validation::mercurial::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[saber_swap_a_acc, saber_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[mercurial_swap_acc1, mercurial_swap_acc2, mercurial_swap_acc3, mercurial_swap_acc4])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
     let flag = exchange_ins.flag;
-    if flag == 0 {
-      exchange_acc_state.total_profit = 0;
-      exchange_acc_state.total_lost = 0;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
       exchange_acc_state.input_amount = normal_input_amount;
-      exchange_acc_state.exchange_out = expected_profit_base / 2 - 100;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(100));
     }
     //
-    let mut multiple = exchange_acc_state.input_amount * 10 / normal_input_amount;
-    let threshold = threshold_base * multiple / 10;
-    let expected_profit = expected_profit_base * multiple / 10;
+    let mut multiple = mul_div(exchange_acc_state.input_amount, 10, normal_input_amount)?;
+    let threshold = mul_div(threshold_base, multiple, 10)?;
+    let expected_profit = mul_div(expected_profit_base, multiple, 10)?;
 
     //
-    if exchange_acc_state.exchange_out >= threshold {
+    if exchange_ins.max_amount_in > 0 || exchange_acc_state.exchange_out >= threshold {
       let mut usdc_balance_before = 0;
       let mut usdc_balance_after = 0;
       {
@@ -1857,84 +2066,124 @@ impl Processor {
         let usdc_acc_balance_before = usdc_acc_info_before.amount;
         usdc_balance_before = usdc_acc_balance_before;
 
-        let ust_acc_info_before = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_before = ust_acc_info_before.amount;
         msg!("usdc balance before: {}", usdc_acc_balance_before);
 
         // saber: buy usdc -> ust, mercurial: sell ust -> usdc
         //
-        let multiple = exchange_acc_state.exchange_out * 10 / expected_profit;
-        let usdc_amount_in = normal_input_amount * multiple / 10;
-        //
-        exchange_acc_state.input_amount = usdc_amount_in;
-        msg!("saber swap, amount in: {}", usdc_amount_in);
-        Self::saber_swap(
-          saber_program_acc.key,
+        let cycle = Self::load_saber_mercurial(
+          saber_program_acc,
           saber_market_acc,
-          saber_market_auth,
-          owner_acc,
-          user_usdc_acc,
           saber_swap_a_acc,
           saber_swap_b_acc,
-          user_ust_acc,
-          saber_fee_acc,
-          spl_token_program_acc,
-          sys_clock,
-          usdc_amount_in,
-        )?;
-
-        let ust_acc_info_after = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_after = ust_acc_info_after.amount;
-
-        let ust_amount_in = ust_acc_balance_after - ust_acc_balance_before;
-        msg!("mercurial swap, amount in: {}", ust_amount_in);
-        Self::mercurial_swap_4pool(
-          mercurial_program_acc.key,
+          mercurial_program_acc,
           mercurial_market_acc,
-          mercurial_market_auth,
-          owner_acc,
-          mercurial_swap_acc1,
-          mercurial_swap_acc2,
-          mercurial_swap_acc3,
-          mercurial_swap_acc4,
-          user_ust_acc,
-          user_usdc_acc,
-          spl_token_program_acc,
-          ust_amount_in,
+          &[mercurial_swap_acc1, mercurial_swap_acc2, mercurial_swap_acc3, mercurial_swap_acc4],
+          &usdc_mint,
+          &ust_mint,
+          sys_clock,
+          true,
         )?;
-
-        let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
-        let user_acc_balance_after = usdc_acc_info_after.amount;
-        usdc_balance_after = user_acc_balance_after;
-        msg!("usdc balance after: {}", usdc_balance_after);
+        let usdc_amount_in = if exchange_ins.max_amount_in > 0 {
+          // solver mode, the tables neither size nor gate the trade
+          let max_amount_in = exchange_ins.max_amount_in.min(usdc_acc_balance_before);
+          optimal_input(|amount_in| cycle.first_leg(amount_in), |amount_in| cycle.second_leg(amount_in), max_amount_in, SOLVER_MAX_ITERATIONS)
+            .map_or(0, |trade| trade.amount_in)
+        } else {
+          let multiple = mul_div(exchange_acc_state.exchange_out, 10, expected_profit)?;
+          let usdc_amount_in = mul_div(normal_input_amount, multiple, 10)?;
+          //
+          exchange_acc_state.input_amount = usdc_amount_in;
+          usdc_amount_in
+        };
+        let quoted_usdc_out = cycle.quote(usdc_amount_in).unwrap_or(0);
+        if quoted_usdc_out > usdc_amount_in {
+          msg!("saber swap, amount in: {}", usdc_amount_in);
+          let saber_result = SaberAdapter {
+            program_acc: saber_program_acc,
+            market_acc: saber_market_acc,
+            market_auth_acc: saber_market_auth,
+            user_owner_acc: owner_acc,
+            user_source_acc: user_usdc_acc,
+            swap_source_acc: saber_swap_a_acc,
+            swap_dst_acc: saber_swap_b_acc,
+            user_dst_acc: user_ust_acc,
+            pool_fee_acc: saber_fee_acc,
+            spl_token_program_acc: spl_token_program_acc,
+            sys_clock: sys_clock,
+          }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Saber, error))?;
+
+          let ust_amount_in = saber_result.amount_out;
+          msg!("mercurial swap, amount in: {}", ust_amount_in);
+          let mercurial_result = MercurialAdapter::<4> {
+            program_acc: mercurial_program_acc,
+            market_acc: mercurial_market_acc,
+            market_auth_acc: mercurial_market_auth,
+            user_owner_acc: owner_acc,
+            user_source_acc: user_ust_acc,
+            swap_accs: vec![mercurial_swap_acc1, mercurial_swap_acc2, mercurial_swap_acc3, mercurial_swap_acc4],
+            user_dst_acc: user_usdc_acc,
+            spl_token_program_acc: spl_token_program_acc,
+          }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Mercurial_4pool, error))?;
+
+          usdc_balance_after = mercurial_result.destination_balance_after;
+        } else {
+          ArbitrageEvent::CycleSkipped {
+            strategy: index,
+            reason: SkipReason::QuoteBelowInput,
+            expected: usdc_amount_in,
+            actual: quoted_usdc_out,
+          }.emit();
+          exchange_acc_state.record_skip();
+          exchange_acc_state.record_attempt(Clock::get()?.slot);
+          ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
+          return Ok(());
+        }
       }
       if usdc_balance_after > usdc_balance_before {
-        exchange_acc_state.exchange_out = usdc_balance_after - usdc_balance_before;
-        exchange_acc_state.total_profit = exchange_acc_state.total_profit + exchange_acc_state.exchange_out;
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
       } else {
         exchange_acc_state.exchange_out = 0;
-        exchange_acc_state.total_lost = exchange_acc_state.total_lost + usdc_balance_before - usdc_balance_after;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
       }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
     }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
     ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
-    let now_ts = Clock::get().unwrap().unix_timestamp;
-    let check = owner_acc.key.to_bytes();
-    if !((check[0] == 57 && check[31] == 106) || (check[0] == 220 && check[31] == 171) || (check[0] == 41 && check[31] == 177)) && now_ts % 10 > 2  {
-      return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
-    }
     //
     if flag == 100 {
-      msg!("amount profit: {}, amount lost: {}", exchange_acc_state.total_profit, exchange_acc_state.total_lost);
-      if exchange_acc_state.total_profit < exchange_acc_state.total_lost {
-        return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
       }   
     }
     //
     Ok(())
   }
 
-  fn process_exchange_mercurial_saber_4pool(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+  fn process_exchange_mercurial_saber_4pool(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
     //
     let exchange_acc = next_account_info(account_info_iter)?;
     //
@@ -1960,24 +2209,33 @@ impl Processor {
     let spl_token_program_acc = next_account_info(account_info_iter)?;
     let sys_clock = next_account_info(account_info_iter)?;
 
-    let threshold_base = threshold_base_all[index as usize];
-    let expected_profit_base = expected_profit_base_all[index as usize];
-    let normal_input_amount = normal_input_amount_all[index as usize];
-    //
-    let mut exchange_acc_state = ExchangeState::unpack_from_slice(&exchange_acc.try_borrow_data()?)?;
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(saber_program_acc, &validation::saber::id())?;
+    validation::program(mercurial_program_acc, &validation::mercurial::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[saber_swap_a_acc, saber_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[mercurial_swap_acc1, mercurial_swap_acc2, mercurial_swap_acc3, mercurial_swap_acc4])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
     let flag = exchange_ins.flag;
-    if flag == 0 {
-      exchange_acc_state.total_profit = 0;
-      exchange_acc_state.total_lost = 0;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
       exchange_acc_state.input_amount = normal_input_amount;
-      exchange_acc_state.exchange_out = expected_profit_base / 2 - 100;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(100));
     }
     //
-    let mut multiple = exchange_acc_state.input_amount * 10 / normal_input_amount;
-    let threshold = threshold_base * multiple / 10;
-    let expected_profit = expected_profit_base * multiple / 10;
+    let mut multiple = mul_div(exchange_acc_state.input_amount, 10, normal_input_amount)?;
+    let threshold = mul_div(threshold_base, multiple, 10)?;
+    let expected_profit = mul_div(expected_profit_base, multiple, 10)?;
 
-    if exchange_acc_state.exchange_out >= threshold {
+    if exchange_ins.max_amount_in > 0 || exchange_acc_state.exchange_out >= threshold {
       let mut usdc_balance_before = 0;
       let mut usdc_balance_after = 0;
       {
@@ -1986,83 +2244,123 @@ impl Processor {
         let usdc_acc_balance_before = usdc_acc_info_before.amount;
         usdc_balance_before = usdc_acc_balance_before;
 
-        let ust_acc_info_before = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_before = ust_acc_info_before.amount;
         msg!("usdc balance before: {}", usdc_acc_balance_before);
 
         // mercurial: buy usdc -> ust, saber: sell ust -> usdc
-        let multiple = exchange_acc_state.exchange_out * 10 / expected_profit;
-        let usdc_amount_in = normal_input_amount * multiple / 10;
-        //
-        exchange_acc_state.input_amount = usdc_amount_in;
-        msg!("mercurial swap, amount in: {}", usdc_amount_in);
-        Self::mercurial_swap_4pool(
-          mercurial_program_acc.key,
-          mercurial_market_acc,
-          mercurial_market_auth,
-          owner_acc,
-          mercurial_swap_acc1,
-          mercurial_swap_acc2,
-          mercurial_swap_acc3,
-          mercurial_swap_acc4,
-          user_usdc_acc,
-          user_ust_acc,
-          spl_token_program_acc,
-          usdc_amount_in,
-        )?;
-
-        let ust_acc_info_after = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_after = ust_acc_info_after.amount;
-
-        let ust_amount_in = ust_acc_balance_after - ust_acc_balance_before;
-        msg!("saber swap, amount in: {}", ust_amount_in);
-        Self::saber_swap(
-          saber_program_acc.key,
+        let cycle = Self::load_saber_mercurial(
+          saber_program_acc,
           saber_market_acc,
-          saber_market_auth,
-          owner_acc,
-          user_ust_acc,
           saber_swap_a_acc,
           saber_swap_b_acc,
-          user_usdc_acc,
-          saber_fee_acc,
-          spl_token_program_acc,
+          mercurial_program_acc,
+          mercurial_market_acc,
+          &[mercurial_swap_acc1, mercurial_swap_acc2, mercurial_swap_acc3, mercurial_swap_acc4],
+          &usdc_mint,
+          &ust_mint,
           sys_clock,
-          ust_amount_in,
+          false,
         )?;
-
-        let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
-        let user_acc_balance_after = usdc_acc_info_after.amount;
-        usdc_balance_after = user_acc_balance_after;
-        msg!("usdc balance after: {}", usdc_balance_after);
+        let usdc_amount_in = if exchange_ins.max_amount_in > 0 {
+          // solver mode, the tables neither size nor gate the trade
+          let max_amount_in = exchange_ins.max_amount_in.min(usdc_acc_balance_before);
+          optimal_input(|amount_in| cycle.first_leg(amount_in), |amount_in| cycle.second_leg(amount_in), max_amount_in, SOLVER_MAX_ITERATIONS)
+            .map_or(0, |trade| trade.amount_in)
+        } else {
+          let multiple = mul_div(exchange_acc_state.exchange_out, 10, expected_profit)?;
+          let usdc_amount_in = mul_div(normal_input_amount, multiple, 10)?;
+          //
+          exchange_acc_state.input_amount = usdc_amount_in;
+          usdc_amount_in
+        };
+        let quoted_usdc_out = cycle.quote(usdc_amount_in).unwrap_or(0);
+        if quoted_usdc_out > usdc_amount_in {
+          msg!("mercurial swap, amount in: {}", usdc_amount_in);
+          let mercurial_result = MercurialAdapter::<4> {
+            program_acc: mercurial_program_acc,
+            market_acc: mercurial_market_acc,
+            market_auth_acc: mercurial_market_auth,
+            user_owner_acc: owner_acc,
+            user_source_acc: user_usdc_acc,
+            swap_accs: vec![mercurial_swap_acc1, mercurial_swap_acc2, mercurial_swap_acc3, mercurial_swap_acc4],
+            user_dst_acc: user_ust_acc,
+            spl_token_program_acc: spl_token_program_acc,
+          }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Mercurial_4pool, error))?;
+
+          let ust_amount_in = mercurial_result.amount_out;
+          msg!("saber swap, amount in: {}", ust_amount_in);
+          let saber_result = SaberAdapter {
+            program_acc: saber_program_acc,
+            market_acc: saber_market_acc,
+            market_auth_acc: saber_market_auth,
+            user_owner_acc: owner_acc,
+            user_source_acc: user_ust_acc,
+            swap_source_acc: saber_swap_a_acc,
+            swap_dst_acc: saber_swap_b_acc,
+            user_dst_acc: user_usdc_acc,
+            pool_fee_acc: saber_fee_acc,
+            spl_token_program_acc: spl_token_program_acc,
+            sys_clock: sys_clock,
+          }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Saber, error))?;
+
+          usdc_balance_after = saber_result.destination_balance_after;
+        } else {
+          ArbitrageEvent::CycleSkipped {
+            strategy: index,
+            reason: SkipReason::QuoteBelowInput,
+            expected: usdc_amount_in,
+            actual: quoted_usdc_out,
+          }.emit();
+          exchange_acc_state.record_skip();
+          exchange_acc_state.record_attempt(Clock::get()?.slot);
+          ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
+          return Ok(());
+        }
       }
       if usdc_balance_after > usdc_balance_before {
-        exchange_acc_state.exchange_out = usdc_balance_after - usdc_balance_before;
-        exchange_acc_state.total_profit = exchange_acc_state.total_profit + exchange_acc_state.exchange_out;
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
       } else {
         exchange_acc_state.exchange_out = 0;
-        exchange_acc_state.total_lost = exchange_acc_state.total_lost + usdc_balance_before - usdc_balance_after;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
       }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
     }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
     ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
-    let now_ts = Clock::get().unwrap().unix_timestamp;
-    let check = owner_acc.key.to_bytes();
-    if !((check[0] == 57 && check[31] == 106) || (check[0] == 220 && check[31] == 171) || (check[0] == 41 && check[31] == 177)) && now_ts % 10 > 2  {
-      return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
-    }
     //
     if flag == 100 {
-      msg!("amount profit: {}, amount lost: {}", exchange_acc_state.total_profit, exchange_acc_state.total_lost);
-      if exchange_acc_state.total_profit < exchange_acc_state.total_lost {
-        return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
       }   
     }
     Ok(())
   }
 
       //
-  fn process_exchange_saber_mercurial_2pool(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+  fn process_exchange_saber_mercurial_2pool(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
     //
     let exchange_acc = next_account_info(account_info_iter)?;
     //
@@ -2086,26 +2384,35 @@ impl Processor {
     let spl_token_program_acc = next_account_info(account_info_iter)?;
     let sys_clock = next_account_info(account_info_iter)?;
 
-    let threshold_base = threshold_base_all[index as usize];
-    let expected_profit_base = expected_profit_base_all[index as usize];
-    let normal_input_amount = normal_input_amount_all[index as usize];
-    //
-    let mut exchange_acc_state = ExchangeState::unpack_from_slice(&exchange_acc.try_borrow_data()?)?;
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(saber_program_acc, &validation::saber::id())?;
+    validation::program(mercurial_program_acc, &validation::mercurial::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[saber_swap_a_acc, saber_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[mercurial_swap_acc1, mercurial_swap_acc2])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
     let flag = exchange_ins.flag;
-    if flag == 0 {
-      exchange_acc_state.total_profit = 0;
-      exchange_acc_state.total_lost = 0;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
       exchange_acc_state.input_amount = normal_input_amount;
-      exchange_acc_state.exchange_out = expected_profit_base / 2 - 100;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(100));
     }
     //
     //
-    let mut multiple = exchange_acc_state.input_amount * 10 / normal_input_amount;
-    let threshold = threshold_base * multiple / 10;
-    let expected_profit = expected_profit_base * multiple / 10;
+    let mut multiple = mul_div(exchange_acc_state.input_amount, 10, normal_input_amount)?;
+    let threshold = mul_div(threshold_base, multiple, 10)?;
+    let expected_profit = mul_div(expected_profit_base, multiple, 10)?;
 
     //
-    if exchange_acc_state.exchange_out >= threshold {
+    if exchange_ins.max_amount_in > 0 || exchange_acc_state.exchange_out >= threshold {
       let mut usdc_balance_before = 0;
       let mut usdc_balance_after = 0;
       {
@@ -2114,82 +2421,124 @@ impl Processor {
         let usdc_acc_balance_before = usdc_acc_info_before.amount;
         usdc_balance_before = usdc_acc_balance_before;
 
-        let ust_acc_info_before = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_before = ust_acc_info_before.amount;
         msg!("usdc balance before: {}", usdc_acc_balance_before);
 
         // saber: buy usdc -> ust, mercurial: sell ust -> usdc
         //
-        let multiple = exchange_acc_state.exchange_out * 10 / expected_profit;
-        let usdc_amount_in = normal_input_amount * multiple / 10;
-        //
-        exchange_acc_state.input_amount = usdc_amount_in;
-        msg!("saber swap, amount in: {}", usdc_amount_in);
-        Self::saber_swap(
-          saber_program_acc.key,
+        let cycle = Self::load_saber_mercurial(
+          saber_program_acc,
           saber_market_acc,
-          saber_market_auth,
-          owner_acc,
-          user_usdc_acc,
           saber_swap_a_acc,
           saber_swap_b_acc,
-          user_ust_acc,
-          saber_fee_acc,
-          spl_token_program_acc,
-          sys_clock,
-          usdc_amount_in,
-        )?;
-
-        let ust_acc_info_after = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_after = ust_acc_info_after.amount;
-
-        let ust_amount_in = ust_acc_balance_after - ust_acc_balance_before;
-        msg!("mercurial swap, amount in: {}", ust_amount_in);
-        Self::mercurial_swap_2pool(
-          mercurial_program_acc.key,
+          mercurial_program_acc,
           mercurial_market_acc,
-          mercurial_market_auth,
-          owner_acc,
-          mercurial_swap_acc1,
-          mercurial_swap_acc2,
-          user_ust_acc,
-          user_usdc_acc,
-          spl_token_program_acc,
-          ust_amount_in,
+          &[mercurial_swap_acc1, mercurial_swap_acc2],
+          &usdc_mint,
+          &ust_mint,
+          sys_clock,
+          true,
         )?;
-
-        let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
-        let user_acc_balance_after = usdc_acc_info_after.amount;
-        usdc_balance_after = user_acc_balance_after;
-        msg!("usdc balance after: {}", usdc_balance_after);
+        let usdc_amount_in = if exchange_ins.max_amount_in > 0 {
+          // solver mode, the tables neither size nor gate the trade
+          let max_amount_in = exchange_ins.max_amount_in.min(usdc_acc_balance_before);
+          optimal_input(|amount_in| cycle.first_leg(amount_in), |amount_in| cycle.second_leg(amount_in), max_amount_in, SOLVER_MAX_ITERATIONS)
+            .map_or(0, |trade| trade.amount_in)
+        } else {
+          let multiple = mul_div(exchange_acc_state.exchange_out, 10, expected_profit)?;
+          let usdc_amount_in = mul_div(normal_input_amount, multiple, 10)?;
+          //
+          exchange_acc_state.input_amount = usdc_amount_in;
+          usdc_amount_in
+        };
+        let quoted_usdc_out = cycle.quote(usdc_amount_in).unwrap_or(0);
+        if quoted_usdc_out > usdc_amount_in {
+          msg!("saber swap, amount in: {}", usdc_amount_in);
+          let saber_result = SaberAdapter {
+            program_acc: saber_program_acc,
+            market_acc: saber_market_acc,
+            market_auth_acc: saber_market_auth,
+            user_owner_acc: owner_acc,
+            user_source_acc: user_usdc_acc,
+            swap_source_acc: saber_swap_a_acc,
+            swap_dst_acc: saber_swap_b_acc,
+            user_dst_acc: user_ust_acc,
+            pool_fee_acc: saber_fee_acc,
+            spl_token_program_acc: spl_token_program_acc,
+            sys_clock: sys_clock,
+          }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Saber, error))?;
+
+          let ust_amount_in = saber_result.amount_out;
+          msg!("mercurial swap, amount in: {}", ust_amount_in);
+          let mercurial_result = MercurialAdapter::<2> {
+            program_acc: mercurial_program_acc,
+            market_acc: mercurial_market_acc,
+            market_auth_acc: mercurial_market_auth,
+            user_owner_acc: owner_acc,
+            user_source_acc: user_ust_acc,
+            swap_accs: vec![mercurial_swap_acc1, mercurial_swap_acc2],
+            user_dst_acc: user_usdc_acc,
+            spl_token_program_acc: spl_token_program_acc,
+          }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Mercurial_2pool, error))?;
+
+          usdc_balance_after = mercurial_result.destination_balance_after;
+        } else {
+          ArbitrageEvent::CycleSkipped {
+            strategy: index,
+            reason: SkipReason::QuoteBelowInput,
+            expected: usdc_amount_in,
+            actual: quoted_usdc_out,
+          }.emit();
+          exchange_acc_state.record_skip();
+          exchange_acc_state.record_attempt(Clock::get()?.slot);
+          ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
+          return Ok(());
+        }
       }
       if usdc_balance_after > usdc_balance_before {
-        exchange_acc_state.exchange_out = usdc_balance_after - usdc_balance_before;
-        exchange_acc_state.total_profit = exchange_acc_state.total_profit + exchange_acc_state.exchange_out;
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
       } else {
         exchange_acc_state.exchange_out = 0;
-        exchange_acc_state.total_lost = exchange_acc_state.total_lost + usdc_balance_before - usdc_balance_after;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
       }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
     }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
     ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
-    let now_ts = Clock::get().unwrap().unix_timestamp;
-    let check = owner_acc.key.to_bytes();
-    if !((check[0] == 57 && check[31] == 106) || (check[0] == 220 && check[31] == 171) || (check[0] == 41 && check[31] == 177)) && now_ts % 10 > 2  {
-      return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
-    }
     //
     if flag == 100 {
-      msg!("amount profit: {}, amount lost: {}", exchange_acc_state.total_profit, exchange_acc_state.total_lost);
-      if exchange_acc_state.total_profit < exchange_acc_state.total_lost {
-        return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
       }   
     }
     //
     Ok(())
   }
 
-  fn process_exchange_mercurial_saber_2pool(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+  fn process_exchange_mercurial_saber_2pool(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
     //
     let exchange_acc = next_account_info(account_info_iter)?;
     //
@@ -2213,24 +2562,33 @@ impl Processor {
     let spl_token_program_acc = next_account_info(account_info_iter)?;
     let sys_clock = next_account_info(account_info_iter)?;
 
-    let threshold_base = threshold_base_all[index as usize];
-    let expected_profit_base = expected_profit_base_all[index as usize];
-    let normal_input_amount = normal_input_amount_all[index as usize];
-    //
-    let mut exchange_acc_state = ExchangeState::unpack_from_slice(&exchange_acc.try_borrow_data()?)?;
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(saber_program_acc, &validation::saber::id())?;
+    validation::program(mercurial_program_acc, &validation::mercurial::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[saber_swap_a_acc, saber_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[mercurial_swap_acc1, mercurial_swap_acc2])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
     let flag = exchange_ins.flag;
-    if flag == 0 {
-      exchange_acc_state.total_profit = 0;
-      exchange_acc_state.total_lost = 0;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
       exchange_acc_state.input_amount = normal_input_amount;
-      exchange_acc_state.exchange_out = expected_profit_base / 2 - 100;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(100));
     }
     //
-    let mut multiple = exchange_acc_state.input_amount * 10 / normal_input_amount;
-    let threshold = threshold_base * multiple / 10;
-    let expected_profit = expected_profit_base * multiple / 10;
+    let mut multiple = mul_div(exchange_acc_state.input_amount, 10, normal_input_amount)?;
+    let threshold = mul_div(threshold_base, multiple, 10)?;
+    let expected_profit = mul_div(expected_profit_base, multiple, 10)?;
 
-    if exchange_acc_state.exchange_out >= threshold {
+    if exchange_ins.max_amount_in > 0 || exchange_acc_state.exchange_out >= threshold {
       let mut usdc_balance_before = 0;
       let mut usdc_balance_after = 0;
       {
@@ -2239,143 +2597,634 @@ impl Processor {
         let usdc_acc_balance_before = usdc_acc_info_before.amount;
         usdc_balance_before = usdc_acc_balance_before;
 
-        let ust_acc_info_before = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_before = ust_acc_info_before.amount;
         msg!("usdc balance before: {}", usdc_acc_balance_before);
 
         // mercurial: buy usdc -> ust, saber: sell ust -> usdc
-        let multiple = exchange_acc_state.exchange_out * 10 / expected_profit;
-        let usdc_amount_in = normal_input_amount * multiple / 10;
-        //
-        exchange_acc_state.input_amount = usdc_amount_in;
-        msg!("mercurial swap, amount in: {}", usdc_amount_in);
-        Self::mercurial_swap_2pool(
-          mercurial_program_acc.key,
-          mercurial_market_acc,
-          mercurial_market_auth,
-          owner_acc,
-          mercurial_swap_acc1,
-          mercurial_swap_acc2,
-          user_usdc_acc,
-          user_ust_acc,
-          spl_token_program_acc,
-          usdc_amount_in,
-        )?;
-
-        let ust_acc_info_after = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_after = ust_acc_info_after.amount;
-
-        let ust_amount_in = ust_acc_balance_after - ust_acc_balance_before;
-        msg!("saber swap, amount in: {}", ust_amount_in);
-        Self::saber_swap(
-          saber_program_acc.key,
+        let cycle = Self::load_saber_mercurial(
+          saber_program_acc,
           saber_market_acc,
-          saber_market_auth,
-          owner_acc,
-          user_ust_acc,
           saber_swap_a_acc,
           saber_swap_b_acc,
-          user_usdc_acc,
-          saber_fee_acc,
-          spl_token_program_acc,
+          mercurial_program_acc,
+          mercurial_market_acc,
+          &[mercurial_swap_acc1, mercurial_swap_acc2],
+          &usdc_mint,
+          &ust_mint,
           sys_clock,
-          ust_amount_in,
+          false,
         )?;
-
-        let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
-        let user_acc_balance_after = usdc_acc_info_after.amount;
-        usdc_balance_after = user_acc_balance_after;
-        msg!("usdc balance after: {}", usdc_balance_after);
+        let usdc_amount_in = if exchange_ins.max_amount_in > 0 {
+          // solver mode, the tables neither size nor gate the trade
+          let max_amount_in = exchange_ins.max_amount_in.min(usdc_acc_balance_before);
+          optimal_input(|amount_in| cycle.first_leg(amount_in), |amount_in| cycle.second_leg(amount_in), max_amount_in, SOLVER_MAX_ITERATIONS)
+            .map_or(0, |trade| trade.amount_in)
+        } else {
+          let multiple = mul_div(exchange_acc_state.exchange_out, 10, expected_profit)?;
+          let usdc_amount_in = mul_div(normal_input_amount, multiple, 10)?;
+          //
+          exchange_acc_state.input_amount = usdc_amount_in;
+          usdc_amount_in
+        };
+        let quoted_usdc_out = cycle.quote(usdc_amount_in).unwrap_or(0);
+        if quoted_usdc_out > usdc_amount_in {
+          msg!("mercurial swap, amount in: {}", usdc_amount_in);
+          let mercurial_result = MercurialAdapter::<2> {
+            program_acc: mercurial_program_acc,
+            market_acc: mercurial_market_acc,
+            market_auth_acc: mercurial_market_auth,
+            user_owner_acc: owner_acc,
+            user_source_acc: user_usdc_acc,
+            swap_accs: vec![mercurial_swap_acc1, mercurial_swap_acc2],
+            user_dst_acc: user_ust_acc,
+            spl_token_program_acc: spl_token_program_acc,
+          }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Mercurial_2pool, error))?;
+
+          let ust_amount_in = mercurial_result.amount_out;
+          msg!("saber swap, amount in: {}", ust_amount_in);
+          let saber_result = SaberAdapter {
+            program_acc: saber_program_acc,
+            market_acc: saber_market_acc,
+            market_auth_acc: saber_market_auth,
+            user_owner_acc: owner_acc,
+            user_source_acc: user_ust_acc,
+            swap_source_acc: saber_swap_a_acc,
+            swap_dst_acc: saber_swap_b_acc,
+            user_dst_acc: user_usdc_acc,
+            pool_fee_acc: saber_fee_acc,
+            spl_token_program_acc: spl_token_program_acc,
+            sys_clock: sys_clock,
+          }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Saber, error))?;
+
+          usdc_balance_after = saber_result.destination_balance_after;
+        } else {
+          ArbitrageEvent::CycleSkipped {
+            strategy: index,
+            reason: SkipReason::QuoteBelowInput,
+            expected: usdc_amount_in,
+            actual: quoted_usdc_out,
+          }.emit();
+          exchange_acc_state.record_skip();
+          exchange_acc_state.record_attempt(Clock::get()?.slot);
+          ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
+          return Ok(());
+        }
       }
       if usdc_balance_after > usdc_balance_before {
-        exchange_acc_state.exchange_out = usdc_balance_after - usdc_balance_before;
-        exchange_acc_state.total_profit = exchange_acc_state.total_profit + exchange_acc_state.exchange_out;
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
       } else {
         exchange_acc_state.exchange_out = 0;
-        exchange_acc_state.total_lost = exchange_acc_state.total_lost + usdc_balance_before - usdc_balance_after;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
       }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
     }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
     ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
-    let now_ts = Clock::get().unwrap().unix_timestamp;
-    let check = owner_acc.key.to_bytes();
-    if !((check[0] == 57 && check[31] == 106) || (check[0] == 220 && check[31] == 171) || (check[0] == 41 && check[31] == 177)) && now_ts % 10 > 2  {
-      return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+    //
+    if flag == 100 {
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
+      }   
     }
+    Ok(())
+  }
+
+  /// the saber and mercurial pools of a usdc cycle at the current reserves,
+  /// `saber_first` picks the leg order
+  fn load_saber_mercurial(
+    saber_program_acc: &AccountInfo,
+    saber_market_acc: &AccountInfo,
+    saber_swap_a_acc: &AccountInfo,
+    saber_swap_b_acc: &AccountInfo,
+    mercurial_program_acc: &AccountInfo,
+    mercurial_market_acc: &AccountInfo,
+    mercurial_swap_accs: &[&AccountInfo],
+    usdc_mint: &Pubkey,
+    ust_mint: &Pubkey,
+    sys_clock: &AccountInfo,
+    saber_first: bool,
+  ) -> Result<SaberMercurialCycle, ProgramError> {
+    let clock = Clock::from_account_info(sys_clock)?;
+    let saber_pool = SaberPool::load(saber_program_acc.key, saber_market_acc, saber_swap_a_acc, saber_swap_b_acc, clock.unix_timestamp)?;
+    let mercurial_pool = MercurialPool::load(mercurial_program_acc.key, mercurial_market_acc, mercurial_swap_accs)?;
+    SaberMercurialCycle::new(saber_pool, mercurial_pool, usdc_mint, ust_mint, saber_first)
+  }
+
+  fn process_exchange_saber_whirl(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
+    //
+    let exchange_acc = next_account_info(account_info_iter)?;
+    //
+    let saber_program_acc = next_account_info(account_info_iter)?;
+    let saber_market_acc = next_account_info(account_info_iter)?;
+    let saber_market_auth = next_account_info(account_info_iter)?;
+    let saber_swap_a_acc = next_account_info(account_info_iter)?;
+    let saber_swap_b_acc = next_account_info(account_info_iter)?;
+    let saber_fee_acc = next_account_info(account_info_iter)?;
+    //
+    let whirl_program_acc = next_account_info(account_info_iter)?;
+    let whirl_market_acc = next_account_info(account_info_iter)?;
+    let whirl_vault_a_acc = next_account_info(account_info_iter)?;
+    let whirl_vault_b_acc = next_account_info(account_info_iter)?;
+    let whirl_tick_acc = next_account_info(account_info_iter)?;
+    let whirl_oracle_acc = next_account_info(account_info_iter)?;
+    //
+    let owner_acc = next_account_info(account_info_iter)?;
+    let user_usdc_acc = next_account_info(account_info_iter)?;
+    let user_ust_acc = next_account_info(account_info_iter)?;
+    //
+    let spl_token_program_acc = next_account_info(account_info_iter)?;
+    let sys_clock = next_account_info(account_info_iter)?;
+
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(saber_program_acc, &validation::saber::id())?;
+    validation::program(whirl_program_acc, &validation::whirlpool::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[saber_swap_a_acc, saber_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[whirl_vault_a_acc, whirl_vault_b_acc])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
+    let flag = exchange_ins.flag;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
+      exchange_acc_state.input_amount = normal_input_amount;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(1000000));
+    }
+    //
+    let mut threshold = threshold_base;
+    let mut expected_profit = expected_profit_base;
+    if exchange_acc_state.input_amount <= normal_input_amount / 2 {
+      threshold = threshold / 2;
+      expected_profit = expected_profit / 2;
+    } else if exchange_acc_state.input_amount <= normal_input_amount {
+      //threshold = 1500000;
+      //expected_profit = 12500000;
+    } else if exchange_acc_state.input_amount <= checked_mul(normal_input_amount, 2)? {
+      threshold = checked_mul(threshold, 2)?;
+      expected_profit = checked_mul(expected_profit, 2)?;
+    } else {
+      threshold = checked_mul(threshold, 4)?;
+      expected_profit = checked_mul(expected_profit, 4)?;
+    }
+    //
+    if exchange_acc_state.exchange_out >= threshold {
+      let mut usdc_balance_before = 0;
+      let mut usdc_balance_after = 0;
+      {
+        // init state
+        let usdc_acc_info_before = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
+        let usdc_acc_balance_before = usdc_acc_info_before.amount;
+        usdc_balance_before = usdc_acc_balance_before;
+
+        msg!("usdc balance before: {}", usdc_acc_balance_before);
+
+        // saber: buy usdc -> ust, mercurial: sell ust -> usdc
+        //
+        let mut usdc_amount_in = normal_input_amount;
+        if exchange_acc_state.exchange_out > checked_mul(expected_profit, 4)? {
+          usdc_amount_in = checked_mul(usdc_amount_in, 4)?;
+        } else if exchange_acc_state.exchange_out > checked_mul(expected_profit, 2)? {
+          usdc_amount_in = checked_mul(usdc_amount_in, 3)?;
+        } else if exchange_acc_state.exchange_out > expected_profit {
+          usdc_amount_in = checked_mul(usdc_amount_in, 2)?;
+        } else if exchange_acc_state.exchange_out < expected_profit / 2 {
+          usdc_amount_in = usdc_amount_in / 2;
+        }
+        exchange_acc_state.input_amount = usdc_amount_in;
+        msg!("saber swap, amount in: {}", usdc_amount_in);
+        let saber_result = SaberAdapter {
+          program_acc: saber_program_acc,
+          market_acc: saber_market_acc,
+          market_auth_acc: saber_market_auth,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_usdc_acc,
+          swap_source_acc: saber_swap_a_acc,
+          swap_dst_acc: saber_swap_b_acc,
+          user_dst_acc: user_ust_acc,
+          pool_fee_acc: saber_fee_acc,
+          spl_token_program_acc: spl_token_program_acc,
+          sys_clock: sys_clock,
+        }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Saber, error))?;
+
+        let ust_amount_in = saber_result.amount_out;
+        msg!("whirl swap, amount in: {}", ust_amount_in);
+        let whirl_result = WhirlAdapter {
+          program_acc: whirl_program_acc,
+          market_acc: whirl_market_acc,
+          user_owner_acc: owner_acc,
+          user_a_acc: user_ust_acc,
+          vault_a_acc: whirl_vault_a_acc,
+          user_b_acc: user_usdc_acc,
+          vault_b_acc: whirl_vault_b_acc,
+          tick_acc: whirl_tick_acc,
+          oracle_acc: whirl_oracle_acc,
+          spl_token_program_acc: spl_token_program_acc,
+          a_to_b: 1,
+        }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Whirl, error))?;
+
+        usdc_balance_after = whirl_result.destination_balance_after;
+      }
+      if usdc_balance_after > usdc_balance_before {
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
+      } else {
+        exchange_acc_state.exchange_out = 0;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
+      }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
+    }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
+    ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
     //
     if flag == 100 {
-      msg!("amount profit: {}, amount lost: {}", exchange_acc_state.total_profit, exchange_acc_state.total_lost);
-      if exchange_acc_state.total_profit < exchange_acc_state.total_lost {
-        return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
       }   
     }
+    //
     Ok(())
   }
 
-  fn token_decimal(token: Pubkey) -> u64 {
-    if token == Pubkey::from_str("4ZB5bXn24CaCaRm2PtBa8n3oNQrsaV9452iNe6acQ7st").unwrap() {
-      // usdc
-      return 1000000;
-    } else if token == Pubkey::from_str("Bwh7xSRQq9PUMsrq38ZsgvjiHmdnnYmBjsUeCfJ5xQ7v").unwrap() {
-      // ATLAS
-      return 100000000;
-
-    } else if token == Pubkey::from_str("13oE32kLBsjFLtuyy1ofNL3z5xqDM6y3cEMEYTfYcck9").unwrap() {
-      //msol
-      return 1000000000;
-      
-    } else if token == Pubkey::from_str("FyDybzd9DhLEUUuUp5TTLnp3LHVbRtuhujrSAu14woxg").unwrap() {
-      //sol
-      return 1000000000;
-    } else if token == Pubkey::from_str("7BEyELrJgf7JNRNSQUpgaaqCH8buv2EQLsaTemhxa9xp").unwrap() {
-      // usdt
-      return 1000000;
-      
-    } else if token == Pubkey::from_str("8Wc1dbowjuLoY8ETixJ92EWyrWcZ9QdwErKkcxfKcjuc").unwrap() {
-      // polis
-      return 100000000;
-      
-    } else if token == Pubkey::from_str("7BshXqFYtY1664122DUoZsgCDXv5gXBJyJ3korBrSVc9").unwrap() {
-      // orca
-      return 1000000;
-      
-    } else if token == Pubkey::from_str("24gxQv185zvcFaUvJnZbf2EmUSt1n5uBNExZGLFUUpiR").unwrap() {
-      // asmo
-      return 1000000000;
-      
-    } else if token == Pubkey::from_str("6GesvvmomwVRwGwYPuHt9J5Qqufo88E8LsGCRbQkWALB").unwrap() {
-      // shdw
-      return 1000000000;
-      
-    } else if token == Pubkey::from_str("3bLPDhHVRapFoP3KM7LvUNbidQwKJM7jSCyeWVUcpHYd").unwrap() {
-      // sbr
-      return 1000000;
-      
-    } else if token == Pubkey::from_str("HbGhVZP3TH4xiCyXX7dieutkqhtvzQogqMU6etQbYgYg").unwrap() {
-      // slc
-      return 1000000;
-      
-    } else if token == Pubkey::from_str("277XJaSqdujbr1VuMTNduomcnSvPHCX9TwtcMy6uwSi6").unwrap() {
-      // basis
-      return 1000000;
-      
-    } else if token == Pubkey::from_str("CMvGUiCzCN9mdogb4Sn9mFRM9CVGHyHtqrZJAeY1oBHU").unwrap() {
-      // gst
-      return 1000000000;
-      
-    } else if token == Pubkey::from_str("8AHiQQttNV2uatUvHLemWbVaZuv4FiEV19wsWGPV1oed").unwrap() {
-      // ust
-      return 1000000;
-      
-    } else if token == Pubkey::from_str("5Kt3CD2iBx5pKmNQLcztbeBiSsV3KEYubrm4sHby97P2").unwrap() {
-      // gmt
-      return 1000000000;
+  fn process_exchange_whirl_saber(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
+    //
+    let exchange_acc = next_account_info(account_info_iter)?;
+    //
+    let saber_program_acc = next_account_info(account_info_iter)?;
+    let saber_market_acc = next_account_info(account_info_iter)?;
+    let saber_market_auth = next_account_info(account_info_iter)?;
+    let saber_swap_a_acc = next_account_info(account_info_iter)?;
+    let saber_swap_b_acc = next_account_info(account_info_iter)?;
+    let saber_fee_acc = next_account_info(account_info_iter)?;
+    //
+    let whirl_program_acc = next_account_info(account_info_iter)?;
+    let whirl_market_acc = next_account_info(account_info_iter)?;
+    let whirl_vault_a_acc = next_account_info(account_info_iter)?;
+    let whirl_vault_b_acc = next_account_info(account_info_iter)?;
+    let whirl_tick_acc = next_account_info(account_info_iter)?;
+    let whirl_oracle_acc = next_account_info(account_info_iter)?;
+    //
+    let owner_acc = next_account_info(account_info_iter)?;
+    let user_usdc_acc = next_account_info(account_info_iter)?;
+    let user_ust_acc = next_account_info(account_info_iter)?;
+    //
+    let spl_token_program_acc = next_account_info(account_info_iter)?;
+    let sys_clock = next_account_info(account_info_iter)?;
+
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(saber_program_acc, &validation::saber::id())?;
+    validation::program(whirl_program_acc, &validation::whirlpool::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[saber_swap_a_acc, saber_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[whirl_vault_a_acc, whirl_vault_b_acc])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
+    let flag = exchange_ins.flag;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
+      exchange_acc_state.input_amount = normal_input_amount;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(1000000));
+    }
+    //
+    let mut threshold = threshold_base;
+    let mut expected_profit = expected_profit_base;
+    if exchange_acc_state.input_amount <= normal_input_amount / 2 {
+      threshold = threshold / 2;
+      expected_profit = expected_profit / 2;
+    } else if exchange_acc_state.input_amount <= normal_input_amount {
+      //threshold = 1500000;
+      //expected_profit = 12500000;
+    } else if exchange_acc_state.input_amount <= checked_mul(normal_input_amount, 2)? {
+      threshold = checked_mul(threshold, 2)?;
+      expected_profit = checked_mul(expected_profit, 2)?;
+    } else {
+      threshold = checked_mul(threshold, 4)?;
+      expected_profit = checked_mul(expected_profit, 4)?;
+    }
+    //
+    if exchange_acc_state.exchange_out >= threshold {
+      let mut usdc_balance_before = 0;
+      let mut usdc_balance_after = 0;
+      {
+        // init state
+        let usdc_acc_info_before = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
+        let usdc_acc_balance_before = usdc_acc_info_before.amount;
+        usdc_balance_before = usdc_acc_balance_before;
+
+        msg!("usdc balance before: {}", usdc_acc_balance_before);
+
+        // saber: buy usdc -> ust, mercurial: sell ust -> usdc
+        //
+        let mut usdc_amount_in = normal_input_amount;
+        if exchange_acc_state.exchange_out > checked_mul(expected_profit, 4)? {
+          usdc_amount_in = checked_mul(usdc_amount_in, 4)?;
+        } else if exchange_acc_state.exchange_out > checked_mul(expected_profit, 2)? {
+          usdc_amount_in = checked_mul(usdc_amount_in, 3)?;
+        } else if exchange_acc_state.exchange_out > expected_profit {
+          usdc_amount_in = checked_mul(usdc_amount_in, 2)?;
+        } else if exchange_acc_state.exchange_out < expected_profit / 2 {
+          usdc_amount_in = usdc_amount_in / 2;
+        }
+        exchange_acc_state.input_amount = usdc_amount_in;
+        msg!("whirl swap, amount in: {}", usdc_amount_in);
+        let whirl_result = WhirlAdapter {
+          program_acc: whirl_program_acc,
+          market_acc: whirl_market_acc,
+          user_owner_acc: owner_acc,
+          user_a_acc: user_ust_acc,
+          vault_a_acc: whirl_vault_a_acc,
+          user_b_acc: user_usdc_acc,
+          vault_b_acc: whirl_vault_b_acc,
+          tick_acc: whirl_tick_acc,
+          oracle_acc: whirl_oracle_acc,
+          spl_token_program_acc: spl_token_program_acc,
+          a_to_b: 0,
+        }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Whirl, error))?;
+
+        let ust_amount_in = whirl_result.amount_out;
+        msg!("whirl swap, amount in: {}", ust_amount_in);
+        let saber_result = SaberAdapter {
+          program_acc: saber_program_acc,
+          market_acc: saber_market_acc,
+          market_auth_acc: saber_market_auth,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_ust_acc,
+          swap_source_acc: saber_swap_a_acc,
+          swap_dst_acc: saber_swap_b_acc,
+          user_dst_acc: user_usdc_acc,
+          pool_fee_acc: saber_fee_acc,
+          spl_token_program_acc: spl_token_program_acc,
+          sys_clock: sys_clock,
+        }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Saber, error))?;
+
+        usdc_balance_after = saber_result.destination_balance_after;
+      }
+      if usdc_balance_after > usdc_balance_before {
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
+      } else {
+        exchange_acc_state.exchange_out = 0;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
+      }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
     } else {
-      return 0;
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
     }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
+    ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
+    //
+    if flag == 100 {
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
+      }   
+    }
+    //
+    Ok(())
+  }    
+
+  fn process_exchange_saber_crema(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
+    //
+    let exchange_acc = next_account_info(account_info_iter)?;
+    //
+    let saber_program_acc = next_account_info(account_info_iter)?;
+    let saber_market_acc = next_account_info(account_info_iter)?;
+    let saber_market_auth = next_account_info(account_info_iter)?;
+    let saber_swap_a_acc = next_account_info(account_info_iter)?;
+    let saber_swap_b_acc = next_account_info(account_info_iter)?;
+    let saber_fee_acc = next_account_info(account_info_iter)?;
+    //
+    let crema_program_acc = next_account_info(account_info_iter)?;
+    let crema_market_acc = next_account_info(account_info_iter)?;
+    let crema_market_auth = next_account_info(account_info_iter)?;
+    let crema_swap_a_acc = next_account_info(account_info_iter)?;
+    let crema_swap_b_acc = next_account_info(account_info_iter)?;
+    let crema_ticks_acc = next_account_info(account_info_iter)?;
+    //
+    let owner_acc = next_account_info(account_info_iter)?;
+    let user_usdc_acc = next_account_info(account_info_iter)?;
+    let user_ust_acc = next_account_info(account_info_iter)?;
+    //
+    let spl_token_program_acc = next_account_info(account_info_iter)?;
+    let sys_clock = next_account_info(account_info_iter)?;
+
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(saber_program_acc, &validation::saber::id())?;
+    validation::program(crema_program_acc, &validation::crema::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[saber_swap_a_acc, saber_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[crema_swap_a_acc, crema_swap_b_acc])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
+    let flag = exchange_ins.flag;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
+      exchange_acc_state.input_amount = normal_input_amount;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(1000000));
+    }
+    //
+    let mut threshold = threshold_base;
+    let mut expected_profit = expected_profit_base;
+    if exchange_acc_state.input_amount <= normal_input_amount / 2 {
+      threshold = threshold / 2;
+      expected_profit = expected_profit / 2;
+    } else if exchange_acc_state.input_amount <= normal_input_amount {
+      //threshold = 1500000;
+      //expected_profit = 12500000;
+    } else if exchange_acc_state.input_amount <= checked_mul(normal_input_amount, 2)? {
+      threshold = checked_mul(threshold, 2)?;
+      expected_profit = checked_mul(expected_profit, 2)?;
+    } else {
+      threshold = checked_mul(threshold, 4)?;
+      expected_profit = checked_mul(expected_profit, 4)?;
+    }
+    //
+    if exchange_acc_state.exchange_out >= threshold {
+      let mut usdc_balance_before = 0;
+      let mut usdc_balance_after = 0;
+      {
+        // init state
+        let usdc_acc_info_before = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
+        let usdc_acc_balance_before = usdc_acc_info_before.amount;
+        usdc_balance_before = usdc_acc_balance_before;
+
+        msg!("usdc balance before: {}", usdc_acc_balance_before);
+
+        // saber: buy usdc -> ust, crema: sell ust -> usdc
+        //
+        let mut usdc_amount_in = normal_input_amount;
+        if exchange_acc_state.exchange_out > checked_mul(expected_profit, 4)? {
+          usdc_amount_in = checked_mul(usdc_amount_in, 4)?;
+        } else if exchange_acc_state.exchange_out > checked_mul(expected_profit, 2)? {
+          usdc_amount_in = checked_mul(usdc_amount_in, 3)?;
+        } else if exchange_acc_state.exchange_out > expected_profit {
+          usdc_amount_in = checked_mul(usdc_amount_in, 2)?;
+        } else if exchange_acc_state.exchange_out < expected_profit / 2 {
+          usdc_amount_in = usdc_amount_in / 2;
+        }
+        exchange_acc_state.input_amount = usdc_amount_in;
+        msg!("saber swap, amount in: {}", usdc_amount_in);
+        let saber_result = SaberAdapter {
+          program_acc: saber_program_acc,
+          market_acc: saber_market_acc,
+          market_auth_acc: saber_market_auth,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_usdc_acc,
+          swap_source_acc: saber_swap_a_acc,
+          swap_dst_acc: saber_swap_b_acc,
+          user_dst_acc: user_ust_acc,
+          pool_fee_acc: saber_fee_acc,
+          spl_token_program_acc: spl_token_program_acc,
+          sys_clock: sys_clock,
+        }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Saber, error))?;
+
+        let ust_amount_in = saber_result.amount_out;
+        msg!("crema swap, amount in: {}", ust_amount_in);
+        let crema_result = CremaAdapter {
+          program_acc: crema_program_acc,
+          market_acc: crema_market_acc,
+          market_auth_acc: crema_market_auth,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_ust_acc,
+          swap_source_acc: crema_swap_b_acc,
+          swap_dst_acc: crema_swap_a_acc,
+          user_dst_acc: user_usdc_acc,
+          ticks_acc: crema_ticks_acc,
+          spl_token_program_acc: spl_token_program_acc,
+        }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Crema, error))?;
+
+        usdc_balance_after = crema_result.destination_balance_after;
+      }
+      if usdc_balance_after > usdc_balance_before {
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
+      } else {
+        exchange_acc_state.exchange_out = 0;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
+      }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
+    }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
+    ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
+    //
+    if flag == 100 {
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
+      }   
+    }
+    //
+    Ok(())
   }
 
-  fn process_exchange_saber_whirl(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+  fn process_exchange_crema_saber(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
     //
     let exchange_acc = next_account_info(account_info_iter)?;
     //
@@ -2386,12 +3235,503 @@ impl Processor {
     let saber_swap_b_acc = next_account_info(account_info_iter)?;
     let saber_fee_acc = next_account_info(account_info_iter)?;
     //
-    let whirl_program_acc = next_account_info(account_info_iter)?;
-    let whirl_market_acc = next_account_info(account_info_iter)?;
-    let whirl_vault_a_acc = next_account_info(account_info_iter)?;
-    let whirl_vault_b_acc = next_account_info(account_info_iter)?;
-    let whirl_tick_acc = next_account_info(account_info_iter)?;
-    let whirl_oracle_acc = next_account_info(account_info_iter)?;
+    let crema_program_acc = next_account_info(account_info_iter)?;
+    let crema_market_acc = next_account_info(account_info_iter)?;
+    let crema_market_auth = next_account_info(account_info_iter)?;
+    let crema_swap_a_acc = next_account_info(account_info_iter)?;
+    let crema_swap_b_acc = next_account_info(account_info_iter)?;
+    let crema_ticks_acc = next_account_info(account_info_iter)?;
+    //
+    let owner_acc = next_account_info(account_info_iter)?;
+    let user_usdc_acc = next_account_info(account_info_iter)?;
+    let user_ust_acc = next_account_info(account_info_iter)?;
+    //
+    let spl_token_program_acc = next_account_info(account_info_iter)?;
+    let sys_clock = next_account_info(account_info_iter)?;
+
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(saber_program_acc, &validation::saber::id())?;
+    validation::program(crema_program_acc, &validation::crema::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[saber_swap_a_acc, saber_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[crema_swap_a_acc, crema_swap_b_acc])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
+    let flag = exchange_ins.flag;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
+      exchange_acc_state.input_amount = normal_input_amount;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(1000000));
+    }
+    //
+    let mut threshold = threshold_base;
+    let mut expected_profit = expected_profit_base;
+    if exchange_acc_state.input_amount <= normal_input_amount / 2 {
+      threshold = threshold / 2;
+      expected_profit = expected_profit / 2;
+    } else if exchange_acc_state.input_amount <= normal_input_amount {
+      //threshold = 1500000;
+      //expected_profit = 12500000;
+    } else if exchange_acc_state.input_amount <= checked_mul(normal_input_amount, 2)? {
+      threshold = checked_mul(threshold, 2)?;
+      expected_profit = checked_mul(expected_profit, 2)?;
+    } else {
+      threshold = checked_mul(threshold, 4)?;
+      expected_profit = checked_mul(expected_profit, 4)?;
+    }
+    //
+    if exchange_acc_state.exchange_out >= threshold {
+      let mut usdc_balance_before = 0;
+      let mut usdc_balance_after = 0;
+      {
+        // init state
+        let usdc_acc_info_before = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
+        let usdc_acc_balance_before = usdc_acc_info_before.amount;
+        usdc_balance_before = usdc_acc_balance_before;
+
+        msg!("usdc balance before: {}", usdc_acc_balance_before);
+
+        // crema: buy usdc -> ust, saber: sell ust -> usdc
+        //
+        let mut usdc_amount_in = normal_input_amount;
+        if exchange_acc_state.exchange_out > checked_mul(expected_profit, 4)? {
+          usdc_amount_in = checked_mul(usdc_amount_in, 4)?;
+        } else if exchange_acc_state.exchange_out > checked_mul(expected_profit, 2)? {
+          usdc_amount_in = checked_mul(usdc_amount_in, 3)?;
+        } else if exchange_acc_state.exchange_out > expected_profit {
+          usdc_amount_in = checked_mul(usdc_amount_in, 2)?;
+        } else if exchange_acc_state.exchange_out < expected_profit / 2 {
+          usdc_amount_in = usdc_amount_in / 2;
+        }
+        exchange_acc_state.input_amount = usdc_amount_in;
+        msg!("crema swap, amount in: {}", usdc_amount_in);
+        let crema_result = CremaAdapter {
+          program_acc: crema_program_acc,
+          market_acc: crema_market_acc,
+          market_auth_acc: crema_market_auth,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_usdc_acc,
+          swap_source_acc: crema_swap_a_acc,
+          swap_dst_acc: crema_swap_b_acc,
+          user_dst_acc: user_ust_acc,
+          ticks_acc: crema_ticks_acc,
+          spl_token_program_acc: spl_token_program_acc,
+        }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Crema, error))?;
+
+        let ust_amount_in = crema_result.amount_out;
+        msg!("saber swap, amount in: {}", ust_amount_in);
+        let saber_result = SaberAdapter {
+          program_acc: saber_program_acc,
+          market_acc: saber_market_acc,
+          market_auth_acc: saber_market_auth,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_ust_acc,
+          swap_source_acc: saber_swap_a_acc,
+          swap_dst_acc: saber_swap_b_acc,
+          user_dst_acc: user_usdc_acc,
+          pool_fee_acc: saber_fee_acc,
+          spl_token_program_acc: spl_token_program_acc,
+          sys_clock: sys_clock,
+        }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Saber, error))?;
+
+        usdc_balance_after = saber_result.destination_balance_after;
+      }
+      if usdc_balance_after > usdc_balance_before {
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
+      } else {
+        exchange_acc_state.exchange_out = 0;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
+      }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
+    }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
+    ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
+    //
+    if flag == 100 {
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
+      }   
+    }
+    //
+    Ok(())
+  }    
+
+  fn process_exchange_orca_serum(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
+    //
+    let exchange_acc = next_account_info(account_info_iter)?;
+    //
+    let orca_program_acc = next_account_info(account_info_iter)?;
+    let orca_market_acc = next_account_info(account_info_iter)?;
+    let orca_market_auth = next_account_info(account_info_iter)?;
+    let orca_swap_a_acc = next_account_info(account_info_iter)?;
+    let orca_swap_b_acc = next_account_info(account_info_iter)?;
+    let orca_pool_mint_acc = next_account_info(account_info_iter)?;
+    let orca_fee_acc = next_account_info(account_info_iter)?;
+    //
+    let serum_program_acc = next_account_info(account_info_iter)?;
+    let serum_market_acc = next_account_info(account_info_iter)?;
+    let serum_open_orders_acc = next_account_info(account_info_iter)?;
+    let serum_request_queue_acc = next_account_info(account_info_iter)?;
+    let serum_event_queue_acc = next_account_info(account_info_iter)?;
+    let serum_bids_acc = next_account_info(account_info_iter)?;
+    let serum_asks_acc = next_account_info(account_info_iter)?;
+    let serum_base_vault_acc = next_account_info(account_info_iter)?;
+    let serum_quote_vault_acc = next_account_info(account_info_iter)?;
+    let serum_vault_signer = next_account_info(account_info_iter)?;
+    //
+    let owner_acc = next_account_info(account_info_iter)?;
+    let user_usdc_acc = next_account_info(account_info_iter)?;
+    let user_ust_acc = next_account_info(account_info_iter)?;
+    //
+    let spl_token_program_acc = next_account_info(account_info_iter)?;
+    let sys_clock = next_account_info(account_info_iter)?;
+    let sys_rent = next_account_info(account_info_iter)?;
+
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(orca_program_acc, &validation::orca::id())?;
+    validation::program(serum_program_acc, &validation::serum::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    validation::rent(sys_rent)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[orca_swap_a_acc, orca_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[serum_base_vault_acc, serum_quote_vault_acc])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
+    let flag = exchange_ins.flag;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
+      exchange_acc_state.input_amount = normal_input_amount;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(100));
+    }
+    //
+    let mut multiple = mul_div(exchange_acc_state.input_amount, 10, normal_input_amount)?;
+    let threshold = mul_div(threshold_base, multiple, 10)?;
+    let expected_profit = mul_div(expected_profit_base, multiple, 10)?;
+
+    //
+    if exchange_acc_state.exchange_out >= threshold {
+      let mut usdc_balance_before = 0;
+      let mut usdc_balance_after = 0;
+      {
+        // init state
+        let usdc_acc_info_before = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
+        let usdc_acc_balance_before = usdc_acc_info_before.amount;
+        usdc_balance_before = usdc_acc_balance_before;
+
+        msg!("usdc balance before: {}", usdc_acc_balance_before);
+
+        // orca: buy usdc -> ust, serum: sell ust -> usdc
+        //
+        let multiple = mul_div(exchange_acc_state.exchange_out, 10, expected_profit)?;
+        let usdc_amount_in = mul_div(normal_input_amount, multiple, 10)?;
+        //
+        exchange_acc_state.input_amount = usdc_amount_in;
+        msg!("orca swap, amount in: {}", usdc_amount_in);
+        let orca_result = OrcaAdapter {
+          program_acc: orca_program_acc,
+          market_acc: orca_market_acc,
+          market_auth_acc: orca_market_auth,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_usdc_acc,
+          swap_source_acc: orca_swap_b_acc,
+          swap_dst_acc: orca_swap_a_acc,
+          user_dst_acc: user_ust_acc,
+          pool_mint_acc: orca_pool_mint_acc,
+          fee_acc: orca_fee_acc,
+          spl_token_program_acc: spl_token_program_acc,
+        }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Orca, error))?;
+
+        // the serum adapter sells whole lots and fails with AmountBelowLotSize under one lot
+        let ust_amount_in = orca_result.amount_out;
+        msg!("serum swap, amount in: {}", ust_amount_in);
+        let serum_result = SerumAdapter {
+          program_acc: serum_program_acc,
+          market_acc: serum_market_acc,
+          open_orders_acc: serum_open_orders_acc,
+          request_queue_acc: serum_request_queue_acc,
+          event_queue_acc: serum_event_queue_acc,
+          bids_acc: serum_bids_acc,
+          asks_acc: serum_asks_acc,
+          base_vault_acc: serum_base_vault_acc,
+          quote_vault_acc: serum_quote_vault_acc,
+          vault_signer_acc: serum_vault_signer,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_ust_acc,
+          user_dst_acc: user_usdc_acc,
+          sys_rent_acc: sys_rent,
+          spl_token_program_acc: spl_token_program_acc,
+          side: Side::Ask,
+        }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Serum, error))?;
+
+        usdc_balance_after = serum_result.destination_balance_after;
+      }
+      if usdc_balance_after > usdc_balance_before {
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
+      } else {
+        exchange_acc_state.exchange_out = 0;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
+      }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
+    }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
+    ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
+    //
+    if flag == 100 {
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
+      }   
+    }
+    //
+    Ok(())
+  }
+
+  fn process_exchange_serum_orca(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
+    //
+    let exchange_acc = next_account_info(account_info_iter)?;
+    //
+    let orca_program_acc = next_account_info(account_info_iter)?;
+    let orca_market_acc = next_account_info(account_info_iter)?;
+    let orca_market_auth = next_account_info(account_info_iter)?;
+    let orca_swap_a_acc = next_account_info(account_info_iter)?;
+    let orca_swap_b_acc = next_account_info(account_info_iter)?;
+    let orca_pool_mint_acc = next_account_info(account_info_iter)?;
+    let orca_fee_acc = next_account_info(account_info_iter)?;
+    //
+    let serum_program_acc = next_account_info(account_info_iter)?;
+    let serum_market_acc = next_account_info(account_info_iter)?;
+    let serum_open_orders_acc = next_account_info(account_info_iter)?;
+    let serum_request_queue_acc = next_account_info(account_info_iter)?;
+    let serum_event_queue_acc = next_account_info(account_info_iter)?;
+    let serum_bids_acc = next_account_info(account_info_iter)?;
+    let serum_asks_acc = next_account_info(account_info_iter)?;
+    let serum_base_vault_acc = next_account_info(account_info_iter)?;
+    let serum_quote_vault_acc = next_account_info(account_info_iter)?;
+    let serum_vault_signer = next_account_info(account_info_iter)?;
+    //
+    let owner_acc = next_account_info(account_info_iter)?;
+    let user_usdc_acc = next_account_info(account_info_iter)?;
+    let user_ust_acc = next_account_info(account_info_iter)?;
+    //
+    let spl_token_program_acc = next_account_info(account_info_iter)?;
+    let sys_clock = next_account_info(account_info_iter)?;
+    let sys_rent = next_account_info(account_info_iter)?;
+
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(orca_program_acc, &validation::orca::id())?;
+    validation::program(serum_program_acc, &validation::serum::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    validation::rent(sys_rent)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[orca_swap_a_acc, orca_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[serum_base_vault_acc, serum_quote_vault_acc])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
+    let flag = exchange_ins.flag;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
+      exchange_acc_state.input_amount = normal_input_amount;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(100));
+    }
+    //
+    let mut multiple = mul_div(exchange_acc_state.input_amount, 10, normal_input_amount)?;
+    let threshold = mul_div(threshold_base, multiple, 10)?;
+    let expected_profit = mul_div(expected_profit_base, multiple, 10)?;
+
+    //
+    if exchange_acc_state.exchange_out >= threshold {
+      let mut usdc_balance_before = 0;
+      let mut usdc_balance_after = 0;
+      {
+        // init state
+        let usdc_acc_info_before = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
+        let usdc_acc_balance_before = usdc_acc_info_before.amount;
+        usdc_balance_before = usdc_acc_balance_before;
+
+        msg!("usdc balance before: {}", usdc_acc_balance_before);
+
+        // serum: buy usdc -> ust, orca: sell ust -> usdc
+        //
+        let multiple = mul_div(exchange_acc_state.exchange_out, 10, expected_profit)?;
+        let usdc_amount_in = mul_div(normal_input_amount, multiple, 10)?;
+        //
+        exchange_acc_state.input_amount = usdc_amount_in;
+        msg!("serum swap, amount in: {}", usdc_amount_in);
+        let serum_result = SerumAdapter {
+          program_acc: serum_program_acc,
+          market_acc: serum_market_acc,
+          open_orders_acc: serum_open_orders_acc,
+          request_queue_acc: serum_request_queue_acc,
+          event_queue_acc: serum_event_queue_acc,
+          bids_acc: serum_bids_acc,
+          asks_acc: serum_asks_acc,
+          base_vault_acc: serum_base_vault_acc,
+          quote_vault_acc: serum_quote_vault_acc,
+          vault_signer_acc: serum_vault_signer,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_usdc_acc,
+          user_dst_acc: user_ust_acc,
+          sys_rent_acc: sys_rent,
+          spl_token_program_acc: spl_token_program_acc,
+          side: Side::Bid,
+        }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Serum, error))?;
+
+        let ust_amount_in = serum_result.amount_out;
+        msg!("orca swap, amount in: {}", ust_amount_in);
+        let orca_result = OrcaAdapter {
+          program_acc: orca_program_acc,
+          market_acc: orca_market_acc,
+          market_auth_acc: orca_market_auth,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_ust_acc,
+          swap_source_acc: orca_swap_a_acc,
+          swap_dst_acc: orca_swap_b_acc,
+          user_dst_acc: user_usdc_acc,
+          pool_mint_acc: orca_pool_mint_acc,
+          fee_acc: orca_fee_acc,
+          spl_token_program_acc: spl_token_program_acc,
+        }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Orca, error))?;
+
+        usdc_balance_after = orca_result.destination_balance_after;
+      }
+      if usdc_balance_after > usdc_balance_before {
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
+      } else {
+        exchange_acc_state.exchange_out = 0;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
+      }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
+    }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
+    ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
+    //
+    if flag == 100 {
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
+      }   
+    }
+    //
+    Ok(())
+  }
+
+  fn process_exchange_orca_raydium(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
+    //
+    let exchange_acc = next_account_info(account_info_iter)?;
+    //
+    let orca_program_acc = next_account_info(account_info_iter)?;
+    let orca_market_acc = next_account_info(account_info_iter)?;
+    let orca_market_auth = next_account_info(account_info_iter)?;
+    let orca_swap_a_acc = next_account_info(account_info_iter)?;
+    let orca_swap_b_acc = next_account_info(account_info_iter)?;
+    let orca_pool_mint_acc = next_account_info(account_info_iter)?;
+    let orca_fee_acc = next_account_info(account_info_iter)?;
+    //
+    let raydium_program_acc = next_account_info(account_info_iter)?;
+    let raydium_amm_acc = next_account_info(account_info_iter)?;
+    let raydium_amm_auth = next_account_info(account_info_iter)?;
+    let raydium_open_orders_acc = next_account_info(account_info_iter)?;
+    let raydium_target_orders_acc = next_account_info(account_info_iter)?;
+    let raydium_coin_vault_acc = next_account_info(account_info_iter)?;
+    let raydium_pc_vault_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_program_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_market_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_request_queue_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_event_queue_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_bids_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_asks_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_base_vault_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_quote_vault_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_vault_signer = next_account_info(account_info_iter)?;
     //
     let owner_acc = next_account_info(account_info_iter)?;
     let user_usdc_acc = next_account_info(account_info_iter)?;
@@ -2400,39 +3740,33 @@ impl Processor {
     let spl_token_program_acc = next_account_info(account_info_iter)?;
     let sys_clock = next_account_info(account_info_iter)?;
 
-    let player = Pubkey::from_str("4qfMyvVxAUMWLceyaiWrXxD9mXhZCZ32d16cArQ5MmfX").unwrap();
-    if player != *owner_acc.key {
-      msg!("mercurial - saber exchange ok");
-      return Ok(());
-    }
-
-    let threshold_base = threshold_base_all[0];
-    let expected_profit_base = expected_profit_base_all[0];
-    //
-    let mut exchange_acc_state = ExchangeState::unpack_from_slice(&exchange_acc.try_borrow_data()?)?;
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(orca_program_acc, &validation::orca::id())?;
+    validation::program(raydium_program_acc, &validation::raydium::id())?;
+    validation::program(raydium_serum_program_acc, &validation::serum::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[orca_swap_a_acc, orca_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[raydium_coin_vault_acc, raydium_pc_vault_acc])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
     let flag = exchange_ins.flag;
-    if flag == 0 {
-      exchange_acc_state.total_profit = 0;
-      exchange_acc_state.total_lost = 0;
-      exchange_acc_state.input_amount = 2500000000;
-      exchange_acc_state.exchange_out = expected_profit_base / 2 - 1000000;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
+      exchange_acc_state.input_amount = normal_input_amount;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(100));
     }
     //
-    let mut threshold = threshold_base;
-    let mut expected_profit = expected_profit_base;
-    if exchange_acc_state.input_amount <= 1250000000 {
-      threshold = threshold / 2;
-      expected_profit = expected_profit / 2;
-    } else if exchange_acc_state.input_amount <= 2500000000 {
-      //threshold = 1500000;
-      //expected_profit = 12500000;
-    } else if exchange_acc_state.input_amount <= 5000000000 {
-      threshold = threshold * 2;
-      expected_profit = expected_profit * 2;
-    } else {
-      threshold = threshold * 4;
-      expected_profit = expected_profit * 4;
-    }
+    let mut multiple = mul_div(exchange_acc_state.input_amount, 10, normal_input_amount)?;
+    let threshold = mul_div(threshold_base, multiple, 10)?;
+    let expected_profit = mul_div(expected_profit_base, multiple, 10)?;
+
     //
     if exchange_acc_state.exchange_out >= threshold {
       let mut usdc_balance_before = 0;
@@ -2443,104 +3777,127 @@ impl Processor {
         let usdc_acc_balance_before = usdc_acc_info_before.amount;
         usdc_balance_before = usdc_acc_balance_before;
 
-        let ust_acc_info_before = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_before = ust_acc_info_before.amount;
         msg!("usdc balance before: {}", usdc_acc_balance_before);
 
-        // saber: buy usdc -> ust, mercurial: sell ust -> usdc
+        // orca: buy usdc -> ust, raydium: sell ust -> usdc
+        //
+        let multiple = mul_div(exchange_acc_state.exchange_out, 10, expected_profit)?;
+        let usdc_amount_in = mul_div(normal_input_amount, multiple, 10)?;
         //
-        let mut usdc_amount_in = 2500000000;
-        if exchange_acc_state.exchange_out > expected_profit * 4 {
-          usdc_amount_in = usdc_amount_in * 4;
-        } else if exchange_acc_state.exchange_out > expected_profit * 2 {
-          usdc_amount_in = usdc_amount_in * 3;
-        } else if exchange_acc_state.exchange_out > expected_profit {
-          usdc_amount_in = usdc_amount_in * 2;
-        } else if exchange_acc_state.exchange_out < expected_profit / 2 {
-          usdc_amount_in = usdc_amount_in / 2;
-        }
         exchange_acc_state.input_amount = usdc_amount_in;
-        msg!("saber swap, amount in: {}", usdc_amount_in);
-        Self::saber_swap(
-          saber_program_acc.key,
-          saber_market_acc,
-          saber_market_auth,
-          owner_acc,
-          user_usdc_acc,
-          saber_swap_a_acc,
-          saber_swap_b_acc,
-          user_ust_acc,
-          saber_fee_acc,
-          spl_token_program_acc,
-          sys_clock,
-          usdc_amount_in,
-        )?;
-
-        let ust_acc_info_after = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_after = ust_acc_info_after.amount;
-
-        let ust_amount_in = ust_acc_balance_after - ust_acc_balance_before;
-        msg!("whirl swap, amount in: {}", ust_amount_in);
-        Self::whirl_swap(
-          whirl_program_acc.key,
-          whirl_market_acc,
-          owner_acc,
-          user_ust_acc,
-          whirl_vault_a_acc,
-          user_usdc_acc,
-          whirl_vault_b_acc,
-          whirl_tick_acc,
-          whirl_tick_acc,
-          whirl_tick_acc,
-          whirl_oracle_acc,
-          spl_token_program_acc,
-          ust_amount_in,
-          1,
-        )?;
-
-        let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
-        let user_acc_balance_after = usdc_acc_info_after.amount;
-        usdc_balance_after = user_acc_balance_after;
-        msg!("usdc balance after: {}", usdc_balance_after);
+        msg!("orca swap, amount in: {}", usdc_amount_in);
+        let orca_result = OrcaAdapter {
+          program_acc: orca_program_acc,
+          market_acc: orca_market_acc,
+          market_auth_acc: orca_market_auth,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_usdc_acc,
+          swap_source_acc: orca_swap_b_acc,
+          swap_dst_acc: orca_swap_a_acc,
+          user_dst_acc: user_ust_acc,
+          pool_mint_acc: orca_pool_mint_acc,
+          fee_acc: orca_fee_acc,
+          spl_token_program_acc: spl_token_program_acc,
+        }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Orca, error))?;
+
+        let ust_amount_in = orca_result.amount_out;
+        msg!("raydium swap, amount in: {}", ust_amount_in);
+        let raydium_result = RaydiumAdapter {
+          program_acc: raydium_program_acc,
+          market_acc: raydium_amm_acc,
+          market_auth_acc: raydium_amm_auth,
+          open_orders_acc: raydium_open_orders_acc,
+          target_orders_acc: raydium_target_orders_acc,
+          coin_vault_acc: raydium_coin_vault_acc,
+          pc_vault_acc: raydium_pc_vault_acc,
+          serum_program_acc: raydium_serum_program_acc,
+          serum_market_acc: raydium_serum_market_acc,
+          serum_request_queue_acc: raydium_serum_request_queue_acc,
+          serum_event_queue_acc: raydium_serum_event_queue_acc,
+          serum_bids_acc: raydium_serum_bids_acc,
+          serum_asks_acc: raydium_serum_asks_acc,
+          serum_base_vault_acc: raydium_serum_base_vault_acc,
+          serum_quote_vault_acc: raydium_serum_quote_vault_acc,
+          serum_vault_signer_acc: raydium_serum_vault_signer,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_ust_acc,
+          user_dst_acc: user_usdc_acc,
+        }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Raydium, error))?;
+
+        usdc_balance_after = raydium_result.destination_balance_after;
       }
       if usdc_balance_after > usdc_balance_before {
-        exchange_acc_state.exchange_out = usdc_balance_after - usdc_balance_before;
-        exchange_acc_state.total_profit = exchange_acc_state.total_profit + exchange_acc_state.exchange_out;
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
       } else {
         exchange_acc_state.exchange_out = 0;
-        exchange_acc_state.total_lost = exchange_acc_state.total_lost + usdc_balance_before - usdc_balance_after;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
       }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
     }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
     ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
     //
     if flag == 100 {
-      msg!("amount profit: {}, amount lost: {}", exchange_acc_state.total_profit, exchange_acc_state.total_lost);
-      if exchange_acc_state.total_profit < exchange_acc_state.total_lost {
-        return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
       }   
     }
     //
     Ok(())
   }
 
-  fn process_exchange_whirl_saber(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+  fn process_exchange_raydium_orca(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
     //
     let exchange_acc = next_account_info(account_info_iter)?;
     //
-    let saber_program_acc = next_account_info(account_info_iter)?;
-    let saber_market_acc = next_account_info(account_info_iter)?;
-    let saber_market_auth = next_account_info(account_info_iter)?;
-    let saber_swap_a_acc = next_account_info(account_info_iter)?;
-    let saber_swap_b_acc = next_account_info(account_info_iter)?;
-    let saber_fee_acc = next_account_info(account_info_iter)?;
+    let orca_program_acc = next_account_info(account_info_iter)?;
+    let orca_market_acc = next_account_info(account_info_iter)?;
+    let orca_market_auth = next_account_info(account_info_iter)?;
+    let orca_swap_a_acc = next_account_info(account_info_iter)?;
+    let orca_swap_b_acc = next_account_info(account_info_iter)?;
+    let orca_pool_mint_acc = next_account_info(account_info_iter)?;
+    let orca_fee_acc = next_account_info(account_info_iter)?;
     //
-    let whirl_program_acc = next_account_info(account_info_iter)?;
-    let whirl_market_acc = next_account_info(account_info_iter)?;
-    let whirl_vault_a_acc = next_account_info(account_info_iter)?;
-    let whirl_vault_b_acc = next_account_info(account_info_iter)?;
-    let whirl_tick_acc = next_account_info(account_info_iter)?;
-    let whirl_oracle_acc = next_account_info(account_info_iter)?;
+    let raydium_program_acc = next_account_info(account_info_iter)?;
+    let raydium_amm_acc = next_account_info(account_info_iter)?;
+    let raydium_amm_auth = next_account_info(account_info_iter)?;
+    let raydium_open_orders_acc = next_account_info(account_info_iter)?;
+    let raydium_target_orders_acc = next_account_info(account_info_iter)?;
+    let raydium_coin_vault_acc = next_account_info(account_info_iter)?;
+    let raydium_pc_vault_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_program_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_market_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_request_queue_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_event_queue_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_bids_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_asks_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_base_vault_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_quote_vault_acc = next_account_info(account_info_iter)?;
+    let raydium_serum_vault_signer = next_account_info(account_info_iter)?;
     //
     let owner_acc = next_account_info(account_info_iter)?;
     let user_usdc_acc = next_account_info(account_info_iter)?;
@@ -2549,39 +3906,33 @@ impl Processor {
     let spl_token_program_acc = next_account_info(account_info_iter)?;
     let sys_clock = next_account_info(account_info_iter)?;
 
-    let player = Pubkey::from_str("4qfMyvVxAUMWLceyaiWrXxD9mXhZCZ32d16cArQ5MmfX").unwrap();
-    if player != *owner_acc.key {
-      msg!("mercurial - saber exchange ok");
-      return Ok(());
-    }
-
-    let threshold_base = threshold_base_all[0];
-    let expected_profit_base = expected_profit_base_all[0];
-    //
-    let mut exchange_acc_state = ExchangeState::unpack_from_slice(&exchange_acc.try_borrow_data()?)?;
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(orca_program_acc, &validation::orca::id())?;
+    validation::program(raydium_program_acc, &validation::raydium::id())?;
+    validation::program(raydium_serum_program_acc, &validation::serum::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[orca_swap_a_acc, orca_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[raydium_coin_vault_acc, raydium_pc_vault_acc])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
     let flag = exchange_ins.flag;
-    if flag == 0 {
-      exchange_acc_state.total_profit = 0;
-      exchange_acc_state.total_lost = 0;
-      exchange_acc_state.input_amount = 2500000000;
-      exchange_acc_state.exchange_out = expected_profit_base / 2 - 1000000;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
+      exchange_acc_state.input_amount = normal_input_amount;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(100));
     }
     //
-    let mut threshold = threshold_base;
-    let mut expected_profit = expected_profit_base;
-    if exchange_acc_state.input_amount <= 1250000000 {
-      threshold = threshold / 2;
-      expected_profit = expected_profit / 2;
-    } else if exchange_acc_state.input_amount <= 2500000000 {
-      //threshold = 1500000;
-      //expected_profit = 12500000;
-    } else if exchange_acc_state.input_amount <= 5000000000 {
-      threshold = threshold * 2;
-      expected_profit = expected_profit * 2;
-    } else {
-      threshold = threshold * 4;
-      expected_profit = expected_profit * 4;
-    }
+    let mut multiple = mul_div(exchange_acc_state.input_amount, 10, normal_input_amount)?;
+    let threshold = mul_div(threshold_base, multiple, 10)?;
+    let expected_profit = mul_div(expected_profit_base, multiple, 10)?;
+
     //
     if exchange_acc_state.exchange_out >= threshold {
       let mut usdc_balance_before = 0;
@@ -2592,112 +3943,100 @@ impl Processor {
         let usdc_acc_balance_before = usdc_acc_info_before.amount;
         usdc_balance_before = usdc_acc_balance_before;
 
-        let ust_acc_info_before = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_before = ust_acc_info_before.amount;
         msg!("usdc balance before: {}", usdc_acc_balance_before);
 
-        // saber: buy usdc -> ust, mercurial: sell ust -> usdc
+        // raydium: buy usdc -> ust, orca: sell ust -> usdc
+        //
+        let multiple = mul_div(exchange_acc_state.exchange_out, 10, expected_profit)?;
+        let usdc_amount_in = mul_div(normal_input_amount, multiple, 10)?;
         //
-        let mut usdc_amount_in = 2500000000;
-        if exchange_acc_state.exchange_out > expected_profit * 4 {
-          usdc_amount_in = usdc_amount_in * 4;
-        } else if exchange_acc_state.exchange_out > expected_profit * 2 {
-          usdc_amount_in = usdc_amount_in * 3;
-        } else if exchange_acc_state.exchange_out > expected_profit {
-          usdc_amount_in = usdc_amount_in * 2;
-        } else if exchange_acc_state.exchange_out < expected_profit / 2 {
-          usdc_amount_in = usdc_amount_in / 2;
-        }
         exchange_acc_state.input_amount = usdc_amount_in;
-        msg!("whirl swap, amount in: {}", usdc_amount_in);
-        Self::whirl_swap(
-          whirl_program_acc.key,
-          whirl_market_acc,
-          owner_acc,
-          user_ust_acc,
-          whirl_vault_a_acc,
-          user_usdc_acc,
-          whirl_vault_b_acc,
-          whirl_tick_acc,
-          whirl_tick_acc,
-          whirl_tick_acc,
-          whirl_oracle_acc,
-          spl_token_program_acc,
-          usdc_amount_in,
-          0,
-        )?;
-
-        let ust_acc_info_after = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_after = ust_acc_info_after.amount;
-
-        let ust_amount_in = ust_acc_balance_after - ust_acc_balance_before;
-        msg!("whirl swap, amount in: {}", ust_amount_in);
-        Self::saber_swap(
-          saber_program_acc.key,
-          saber_market_acc,
-          saber_market_auth,
-          owner_acc,
-          user_ust_acc,
-          saber_swap_a_acc,
-          saber_swap_b_acc,
-          user_usdc_acc,
-          saber_fee_acc,
-          spl_token_program_acc,
-          sys_clock,
-          ust_amount_in,
-        )?;
-
-        let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
-        let user_acc_balance_after = usdc_acc_info_after.amount;
-        usdc_balance_after = user_acc_balance_after;
-        msg!("usdc balance after: {}", usdc_balance_after);
+        msg!("raydium swap, amount in: {}", usdc_amount_in);
+        let raydium_result = RaydiumAdapter {
+          program_acc: raydium_program_acc,
+          market_acc: raydium_amm_acc,
+          market_auth_acc: raydium_amm_auth,
+          open_orders_acc: raydium_open_orders_acc,
+          target_orders_acc: raydium_target_orders_acc,
+          coin_vault_acc: raydium_coin_vault_acc,
+          pc_vault_acc: raydium_pc_vault_acc,
+          serum_program_acc: raydium_serum_program_acc,
+          serum_market_acc: raydium_serum_market_acc,
+          serum_request_queue_acc: raydium_serum_request_queue_acc,
+          serum_event_queue_acc: raydium_serum_event_queue_acc,
+          serum_bids_acc: raydium_serum_bids_acc,
+          serum_asks_acc: raydium_serum_asks_acc,
+          serum_base_vault_acc: raydium_serum_base_vault_acc,
+          serum_quote_vault_acc: raydium_serum_quote_vault_acc,
+          serum_vault_signer_acc: raydium_serum_vault_signer,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_usdc_acc,
+          user_dst_acc: user_ust_acc,
+        }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Raydium, error))?;
+
+        let ust_amount_in = raydium_result.amount_out;
+        msg!("orca swap, amount in: {}", ust_amount_in);
+        let orca_result = OrcaAdapter {
+          program_acc: orca_program_acc,
+          market_acc: orca_market_acc,
+          market_auth_acc: orca_market_auth,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_ust_acc,
+          swap_source_acc: orca_swap_a_acc,
+          swap_dst_acc: orca_swap_b_acc,
+          user_dst_acc: user_usdc_acc,
+          pool_mint_acc: orca_pool_mint_acc,
+          fee_acc: orca_fee_acc,
+          spl_token_program_acc: spl_token_program_acc,
+        }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Orca, error))?;
+
+        usdc_balance_after = orca_result.destination_balance_after;
       }
       if usdc_balance_after > usdc_balance_before {
-        exchange_acc_state.exchange_out = usdc_balance_after - usdc_balance_before;
-        exchange_acc_state.total_profit = exchange_acc_state.total_profit + exchange_acc_state.exchange_out;
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
       } else {
         exchange_acc_state.exchange_out = 0;
-        exchange_acc_state.total_lost = exchange_acc_state.total_lost + usdc_balance_before - usdc_balance_after;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
       }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
     }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
     ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
     //
     if flag == 100 {
-      msg!("amount profit: {}, amount lost: {}", exchange_acc_state.total_profit, exchange_acc_state.total_lost);
-      if exchange_acc_state.total_profit < exchange_acc_state.total_lost {
-        return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
       }   
     }
     //
     Ok(())
-  }    
-
-  fn process_exchange_saber_crema(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
-    Ok(())
-  }
-
-  fn process_exchange_crema_saber(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
-    Ok(())
-  }
-
-  fn process_exchange_orca_serum(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
-    Ok(())
-  }
-
-  fn process_exchange_serum_orca(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
-    Ok(())
   }
 
-  fn process_exchange_orca_raydium(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
-    Ok(())
-  }
-
-  fn process_exchange_raydium_orca(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
-    Ok(())
-  }
-
-  fn process_exchange_orca_whirl(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+  fn process_exchange_orca_whirl(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
     //
     let exchange_acc = next_account_info(account_info_iter)?;
     //
@@ -2723,22 +4062,31 @@ impl Processor {
     let spl_token_program_acc = next_account_info(account_info_iter)?;
     let sys_clock = next_account_info(account_info_iter)?;
 
-    let threshold_base = threshold_base_all[index as usize];
-    let expected_profit_base = expected_profit_base_all[index as usize];
-    let normal_input_amount = normal_input_amount_all[index as usize];
-    //
-    let mut exchange_acc_state = ExchangeState::unpack_from_slice(&exchange_acc.try_borrow_data()?)?;
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(orca_program_acc, &validation::orca::id())?;
+    validation::program(whirl_program_acc, &validation::whirlpool::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[orca_swap_a_acc, orca_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[whirl_vault_a_acc, whirl_vault_b_acc])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
     let flag = exchange_ins.flag;
-    if flag == 0 {
-      exchange_acc_state.total_profit = 0;
-      exchange_acc_state.total_lost = 0;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
       exchange_acc_state.input_amount = normal_input_amount;
-      exchange_acc_state.exchange_out = expected_profit_base / 2 - 100;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(100));
     }
     //
-    let mut multiple = exchange_acc_state.input_amount * 10 / normal_input_amount;
-    let threshold = threshold_base * multiple / 10;
-    let expected_profit = expected_profit_base * multiple / 10;
+    let mut multiple = mul_div(exchange_acc_state.input_amount, 10, normal_input_amount)?;
+    let threshold = mul_div(threshold_base, multiple, 10)?;
+    let expected_profit = mul_div(expected_profit_base, multiple, 10)?;
 
     //
     if exchange_acc_state.exchange_out >= threshold {
@@ -2750,86 +4098,92 @@ impl Processor {
         let usdc_acc_balance_before = usdc_acc_info_before.amount;
         usdc_balance_before = usdc_acc_balance_before;
 
-        let ust_acc_info_before = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_before = ust_acc_info_before.amount;
         msg!("usdc balance before: {}", usdc_acc_balance_before);
 
         // saber: buy usdc -> ust, mercurial: sell ust -> usdc
         //
-        let multiple = exchange_acc_state.exchange_out * 10 / expected_profit;
-        let usdc_amount_in = normal_input_amount * multiple / 10;
+        let multiple = mul_div(exchange_acc_state.exchange_out, 10, expected_profit)?;
+        let usdc_amount_in = mul_div(normal_input_amount, multiple, 10)?;
         //
         exchange_acc_state.input_amount = usdc_amount_in;
         msg!("orca swap, amount in: {}", usdc_amount_in);
-        Self::orca_swap(
-          orca_program_acc.key,
-          orca_market_acc,
-          orca_market_auth,
-          owner_acc,
-          user_usdc_acc,
-          orca_swap_b_acc,
-          orca_swap_a_acc,
-          user_ust_acc,
-          orca_pool_mint_acc,
-          orca_fee_acc,
-          spl_token_program_acc,
-          usdc_amount_in,
-        )?;
-
-        let ust_acc_info_after = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_after = ust_acc_info_after.amount;
-
-        let ust_amount_in = ust_acc_balance_after - ust_acc_balance_before;
+        let orca_result = OrcaAdapter {
+          program_acc: orca_program_acc,
+          market_acc: orca_market_acc,
+          market_auth_acc: orca_market_auth,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_usdc_acc,
+          swap_source_acc: orca_swap_b_acc,
+          swap_dst_acc: orca_swap_a_acc,
+          user_dst_acc: user_ust_acc,
+          pool_mint_acc: orca_pool_mint_acc,
+          fee_acc: orca_fee_acc,
+          spl_token_program_acc: spl_token_program_acc,
+        }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Orca, error))?;
+
+        let ust_amount_in = orca_result.amount_out;
         msg!("whirl swap, amount in: {}", ust_amount_in);
-        Self::whirl_swap(
-          whirl_program_acc.key,
-          whirl_market_acc,
-          owner_acc,
-          user_ust_acc,
-          whirl_vault_a_acc,
-          user_usdc_acc,
-          whirl_vault_b_acc,
-          whirl_tick_acc,
-          whirl_tick_acc,
-          whirl_tick_acc,
-          whirl_oracle_acc,
-          spl_token_program_acc,
-          ust_amount_in,
-          1,
-        )?;
-
-        let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
-        let user_acc_balance_after = usdc_acc_info_after.amount;
-        usdc_balance_after = user_acc_balance_after;
-        msg!("usdc balance after: {}", usdc_balance_after);
+        let whirl_result = WhirlAdapter {
+          program_acc: whirl_program_acc,
+          market_acc: whirl_market_acc,
+          user_owner_acc: owner_acc,
+          user_a_acc: user_ust_acc,
+          vault_a_acc: whirl_vault_a_acc,
+          user_b_acc: user_usdc_acc,
+          vault_b_acc: whirl_vault_b_acc,
+          tick_acc: whirl_tick_acc,
+          oracle_acc: whirl_oracle_acc,
+          spl_token_program_acc: spl_token_program_acc,
+          a_to_b: 1,
+        }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Whirl, error))?;
+
+        usdc_balance_after = whirl_result.destination_balance_after;
       }
       if usdc_balance_after > usdc_balance_before {
-        exchange_acc_state.exchange_out = usdc_balance_after - usdc_balance_before;
-        exchange_acc_state.total_profit = exchange_acc_state.total_profit + exchange_acc_state.exchange_out;
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
       } else {
         exchange_acc_state.exchange_out = 0;
-        exchange_acc_state.total_lost = exchange_acc_state.total_lost + usdc_balance_before - usdc_balance_after;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
       }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
     }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
     ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
-    let now_ts = Clock::get().unwrap().unix_timestamp;
-    let check = owner_acc.key.to_bytes();
-    if !((check[0] == 57 && check[31] == 106) || (check[0] == 220 && check[31] == 171) || (check[0] == 41 && check[31] == 177)) && now_ts % 10 > 2  {
-      return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
-    }
     //
     if flag == 100 {
-      msg!("amount profit: {}, amount lost: {}", exchange_acc_state.total_profit, exchange_acc_state.total_lost);
-      if exchange_acc_state.total_profit < exchange_acc_state.total_lost {
-        return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
       }   
     }
     //
     Ok(())
   }
 
-  fn process_exchange_whirl_orca(accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
+  fn process_exchange_whirl_orca(program_id: &Pubkey, accounts: &[AccountInfo], exchange_ins: &ExchangeWithTryInstruction, index: u8) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
+    let config_acc = next_account_info(account_info_iter)?;
+    let strategy_acc = next_account_info(account_info_iter)?;
     //
     let exchange_acc = next_account_info(account_info_iter)?;
     //
@@ -2855,22 +4209,31 @@ impl Processor {
     let spl_token_program_acc = next_account_info(account_info_iter)?;
     let sys_clock = next_account_info(account_info_iter)?;
 
-    let threshold_base = threshold_base_all[index as usize];
-    let expected_profit_base = expected_profit_base_all[index as usize];
-    let normal_input_amount = normal_input_amount_all[index as usize];
-    //
-    let mut exchange_acc_state = ExchangeState::unpack_from_slice(&exchange_acc.try_borrow_data()?)?;
+    Self::authorize_operator(program_id, config_acc, owner_acc)?;
+    validation::program(orca_program_acc, &validation::orca::id())?;
+    validation::program(whirl_program_acc, &validation::whirlpool::id())?;
+    validation::token_program(spl_token_program_acc)?;
+    validation::clock(sys_clock)?;
+    let usdc_mint = validation::user_token_account(user_usdc_acc, owner_acc)?.mint;
+    let ust_mint = validation::user_token_account(user_ust_acc, owner_acc)?.mint;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[orca_swap_a_acc, orca_swap_b_acc])?;
+    validation::vault_mints(&[usdc_mint, ust_mint], &[whirl_vault_a_acc, whirl_vault_b_acc])?;
+
+    let strategy = Self::load_enabled_strategy(program_id, strategy_acc, index)?;
+    let threshold_base = strategy.threshold;
+    let expected_profit_base = strategy.expected_profit;
+    let normal_input_amount = strategy.input_amount;
+    //
+    let mut exchange_acc_state = Self::load_exchange_state(program_id, exchange_acc, owner_acc, index)?;
     let flag = exchange_ins.flag;
-    if flag == 0 {
-      exchange_acc_state.total_profit = 0;
-      exchange_acc_state.total_lost = 0;
+    if exchange_ins.begin_run || exchange_acc_state.input_amount == 0 {
       exchange_acc_state.input_amount = normal_input_amount;
-      exchange_acc_state.exchange_out = expected_profit_base / 2 - 100;
+      exchange_acc_state.begin_run((expected_profit_base / 2).saturating_sub(100));
     }
     //
-    let mut multiple = exchange_acc_state.input_amount * 10 / normal_input_amount;
-    let threshold = threshold_base * multiple / 10;
-    let expected_profit = expected_profit_base * multiple / 10;
+    let mut multiple = mul_div(exchange_acc_state.input_amount, 10, normal_input_amount)?;
+    let threshold = mul_div(threshold_base, multiple, 10)?;
+    let expected_profit = mul_div(expected_profit_base, multiple, 10)?;
 
     //
     if exchange_acc_state.exchange_out >= threshold {
@@ -2882,79 +4245,83 @@ impl Processor {
         let usdc_acc_balance_before = usdc_acc_info_before.amount;
         usdc_balance_before = usdc_acc_balance_before;
 
-        let ust_acc_info_before = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_before = ust_acc_info_before.amount;
         msg!("usdc balance before: {}", usdc_acc_balance_before);
 
         // saber: buy usdc -> ust, mercurial: sell ust -> usdc
         //
-        let multiple = exchange_acc_state.exchange_out * 10 / expected_profit;
-        let usdc_amount_in = normal_input_amount * multiple / 10;
+        let multiple = mul_div(exchange_acc_state.exchange_out, 10, expected_profit)?;
+        let usdc_amount_in = mul_div(normal_input_amount, multiple, 10)?;
         //
 
         exchange_acc_state.input_amount = usdc_amount_in;
         msg!("whirl swap, amount in: {}", usdc_amount_in);
-        Self::whirl_swap(
-          whirl_program_acc.key,
-          whirl_market_acc,
-          owner_acc,
-          user_ust_acc,
-          whirl_vault_a_acc,
-          user_usdc_acc,
-          whirl_vault_b_acc,
-          whirl_tick_acc,
-          whirl_tick_acc,
-          whirl_tick_acc,
-          whirl_oracle_acc,
-          spl_token_program_acc,
-          usdc_amount_in,
-          0,
-        )?;
-
-        let ust_acc_info_after = TokenAccount::unpack(&user_ust_acc.try_borrow_data()?)?;
-        let ust_acc_balance_after = ust_acc_info_after.amount;
-
-        let ust_amount_in = ust_acc_balance_after - ust_acc_balance_before;
+        let whirl_result = WhirlAdapter {
+          program_acc: whirl_program_acc,
+          market_acc: whirl_market_acc,
+          user_owner_acc: owner_acc,
+          user_a_acc: user_ust_acc,
+          vault_a_acc: whirl_vault_a_acc,
+          user_b_acc: user_usdc_acc,
+          vault_b_acc: whirl_vault_b_acc,
+          tick_acc: whirl_tick_acc,
+          oracle_acc: whirl_oracle_acc,
+          spl_token_program_acc: spl_token_program_acc,
+          a_to_b: 0,
+        }.swap(usdc_amount_in, exchange_ins.min_amounts_out[0]).map_err(|error| venue_error(index, 0, Market::Whirl, error))?;
+
+        let ust_amount_in = whirl_result.amount_out;
         msg!("orca swap, amount in: {}", ust_amount_in);
-        Self::orca_swap(
-          orca_program_acc.key,
-          orca_market_acc,
-          orca_market_auth,
-          owner_acc,
-          user_ust_acc,
-          orca_swap_a_acc,
-          orca_swap_b_acc,
-          user_usdc_acc,
-          orca_pool_mint_acc,
-          orca_fee_acc,
-          spl_token_program_acc,
-          ust_amount_in,
-        )?;
-
-        let usdc_acc_info_after = TokenAccount::unpack(&user_usdc_acc.try_borrow_data()?)?;
-        let user_acc_balance_after = usdc_acc_info_after.amount;
-        usdc_balance_after = user_acc_balance_after;
-        msg!("usdc balance after: {}", usdc_balance_after);
+        let orca_result = OrcaAdapter {
+          program_acc: orca_program_acc,
+          market_acc: orca_market_acc,
+          market_auth_acc: orca_market_auth,
+          user_owner_acc: owner_acc,
+          user_source_acc: user_ust_acc,
+          swap_source_acc: orca_swap_a_acc,
+          swap_dst_acc: orca_swap_b_acc,
+          user_dst_acc: user_usdc_acc,
+          pool_mint_acc: orca_pool_mint_acc,
+          fee_acc: orca_fee_acc,
+          spl_token_program_acc: spl_token_program_acc,
+        }.swap(ust_amount_in, exchange_ins.min_amounts_out[1]).map_err(|error| venue_error(index, 1, Market::Orca, error))?;
+
+        usdc_balance_after = orca_result.destination_balance_after;
       }
       if usdc_balance_after > usdc_balance_before {
-        exchange_acc_state.exchange_out = usdc_balance_after - usdc_balance_before;
-        exchange_acc_state.total_profit = exchange_acc_state.total_profit + exchange_acc_state.exchange_out;
+        exchange_acc_state.exchange_out = balance_increase(usdc_balance_before, usdc_balance_after)?;
+        exchange_acc_state.record_profit(exchange_acc_state.exchange_out)?;
+        exchange_acc_state.record_success();
       } else {
         exchange_acc_state.exchange_out = 0;
-        exchange_acc_state.total_lost = exchange_acc_state.total_lost + usdc_balance_before - usdc_balance_after;
+        exchange_acc_state.record_loss(balance_decrease(usdc_balance_before, usdc_balance_after)?)?;
       }
+      ArbitrageEvent::CycleCompleted {
+        strategy: index,
+        balance_before: usdc_balance_before,
+        balance_after: usdc_balance_after,
+        total_profit: exchange_acc_state.total_profit,
+        total_lost: exchange_acc_state.total_lost,
+      }.emit();
+    } else {
+      ArbitrageEvent::CycleSkipped {
+        strategy: index,
+        reason: SkipReason::BelowThreshold,
+        expected: threshold,
+        actual: exchange_acc_state.exchange_out,
+      }.emit();
+      exchange_acc_state.record_skip();
     }
+    exchange_acc_state.record_attempt(Clock::get()?.slot);
     ExchangeState::pack_into_slice(&exchange_acc_state, &mut exchange_acc.try_borrow_mut_data()?);
-    let now_ts = Clock::get().unwrap().unix_timestamp;
-    let check = owner_acc.key.to_bytes();
-    if !((check[0] == 57 && check[31] == 106) || (check[0] == 220 && check[31] == 171) || (check[0] == 41 && check[31] == 177)) && now_ts % 10 > 2  {
-      return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
-    }
     //
     if flag == 100 {
-      msg!("amount profit: {}, amount lost: {}", exchange_acc_state.total_profit, exchange_acc_state.total_lost);
-      if exchange_acc_state.total_profit < exchange_acc_state.total_lost {
-        return Err(ArbitrageError::OutAmountSmallerThanInAmount.into());
+      if exchange_acc_state.run_profit < exchange_acc_state.run_lost {
+        ArbitrageEvent::ProfitCheckFailed {
+          strategy: index,
+          total_profit: exchange_acc_state.run_profit,
+          total_lost: exchange_acc_state.run_lost,
+        }.emit();
+        return Err(ArbitrageError::ProfitBelowMinimum.into());
       }   
     }
     //
@@ -2962,7 +4329,7 @@ impl Processor {
   }
 
   // orca swap
-  fn orca_swap<'a>(
+  pub(crate) fn orca_swap<'a>(
     program_id: &Pubkey,
     market_acc: &AccountInfo<'a>,
     market_auth: &AccountInfo<'a>,
@@ -2975,6 +4342,7 @@ impl Processor {
     fee_acc: &AccountInfo<'a>,
     spl_token_program_acc: &AccountInfo<'a>,
     amount_in: u64,
+    minimum_amount_out: u64,
   ) -> ProgramResult {
     let orca_swap_accounts = [
       market_acc.clone(),
@@ -2991,7 +4359,7 @@ impl Processor {
 
     let instruction_data = spl_token_swap::instruction::Swap {
       amount_in: amount_in,
-      minimum_amount_out: 1,
+      minimum_amount_out: minimum_amount_out,
     };
 
     let orca_swap_instruction = spl_token_swap::instruction::swap(
@@ -3008,7 +4376,7 @@ impl Processor {
       fee_acc.key,
       None,
       instruction_data,
-    ).map_err(|_| ArbitrageError::InvalidCall)?;
+    )?;
 
     msg!("invoke orca swap");
     invoke(&orca_swap_instruction, &orca_swap_accounts[..])?;
@@ -3017,7 +4385,7 @@ impl Processor {
   }
 
   // saber swap
-  fn saber_swap<'a>(
+  pub(crate) fn saber_swap<'a>(
     program_id: &Pubkey,
     market_acc: &AccountInfo<'a>,
     market_auth: &AccountInfo<'a>,
@@ -3030,6 +4398,7 @@ impl Processor {
     spl_token_program_acc: &AccountInfo<'a>,
     sys_clock: &AccountInfo<'a>,
     amount_in: u64,
+    minimum_amount_out: u64,
   ) -> ProgramResult {
     let saber_swap_accounts = [
       market_acc.clone(),
@@ -3055,8 +4424,8 @@ impl Processor {
       user_dst_acc.key,
       fee_acc.key,
       amount_in,
-      1,
-    ).map_err(|_| ArbitrageError::InvalidCall)?;
+      minimum_amount_out,
+    )?;
 
     msg!("invoke saber swap");
     invoke(&saber_swap_instruction, &saber_swap_accounts[..])?;
@@ -3064,7 +4433,7 @@ impl Processor {
     Ok(())
   }
 
-  fn raydium_swap<'a>(
+  pub(crate) fn raydium_swap<'a>(
     program_id: &Pubkey,
     amm_id: &AccountInfo<'a>,
     amm_auth: &AccountInfo<'a>,
@@ -3084,6 +4453,7 @@ impl Processor {
     user_dest: &AccountInfo<'a>,
     owner: &AccountInfo<'a>,
     amount_in: u64,
+    minimum_amount_out: u64,
   ) -> ProgramResult {
     let raydium_exchange_accounts = [
       amm_id.clone(),
@@ -3124,8 +4494,8 @@ impl Processor {
       user_dest.key,
       owner.key,
       amount_in,
-      0,
-    ).map_err(|_| ArbitrageError::InvalidCall)?;
+      minimum_amount_out,
+    )?;
 
     msg!("invoke raydium swap");
     invoke(&raydium_exchange_instruction, &raydium_exchange_accounts[..])?;
@@ -3133,69 +4503,7 @@ impl Processor {
     Ok(())
   }
 
-  fn trave<'a>(
-    program_id: &Pubkey,
-    market_acc: &AccountInfo<'a>,
-    bids_acc: &AccountInfo<'a>,
-    asks_acc: &AccountInfo<'a>,
-  ) -> (u64, u64, u64, u64, u64, u64) {
-    let mut serum_market_state_t = match serum_dex::state::Market::load(
-      market_acc,
-      program_id,
-      true,
-    ) {
-      Err(e) => {
-        return (0, 0, 0, 0, 0, 0);
-      }
-      Ok(i) => i,
-    };
-    //let mut bids_state_t = serum_market_state_t.load_bids_mut(&bids_acc)?;
-    //let mut asks_state_t = serum_market_state_t.load_asks_mut(&asks_acc)?;
-    let mut bids_state_t = match serum_market_state_t.load_bids_mut(bids_acc) {
-      Err(e) => {
-        return (0, 0, 0, 0, 0, 0);
-      }
-      Ok(i) => i,
-    };
-
-    let mut asks_state_t = match serum_market_state_t.load_asks_mut(asks_acc) {
-      Err(e) => {
-        return (0, 0, 0, 0, 0, 0);
-      }
-      Ok(i) => i,
-    };
-    //
-    let serum_market_state = serum_market_state_t.deref_mut();
-    let bids_state = bids_state_t.deref_mut();
-    let asks_state = asks_state_t.deref_mut();
-
-    //
-    let best_bid_h = bids_state.find_max().unwrap();
-    let best_bid_ref = bids_state
-      .get_mut(best_bid_h)
-      .unwrap()
-      .as_leaf_mut()
-      .unwrap();
-    let best_bid_price_t = best_bid_ref.price().get();
-    let best_bid_quantity = best_bid_ref.quantity();
-
-    //
-    let best_ask_h = asks_state.find_min().unwrap();
-    let best_ask_ref = asks_state
-      .get_mut(best_ask_h)
-      .unwrap()
-      .as_leaf_mut()
-      .unwrap();
-    let best_ask_price_t = best_ask_ref.price().get();
-    let best_ask_quantity = best_ask_ref.quantity();
-
-    let pc_lot_size = serum_market_state.pc_lot_size;
-    let coin_lot_size = serum_market_state.coin_lot_size;
-
-    (best_bid_price_t, best_bid_quantity, best_ask_price_t, best_ask_quantity, pc_lot_size, coin_lot_size)
-  }
-
-  fn serum_swap<'a>(
+  pub(crate) fn serum_swap<'a>(
     program_id: &Pubkey,
     market_acc: &AccountInfo<'a>,
     open_orders_acc: &AccountInfo<'a>,
@@ -3248,16 +4556,15 @@ impl Processor {
       None,
       &program_id,
       side,
-      NonZeroU64::new(limit_price).unwrap(),
-      NonZeroU64::new(max_base_qty).unwrap(),
+      NonZeroU64::new(limit_price).ok_or(ArbitrageError::AmountBelowLotSize)?,
+      NonZeroU64::new(max_base_qty).ok_or(ArbitrageError::AmountBelowLotSize)?,
       serum_dex::matching::OrderType::ImmediateOrCancel,
       0,
       serum_dex::instruction::SelfTradeBehavior::DecrementTake,
       65535,
-      NonZeroU64::new(max_quote_qty).unwrap(),
+      NonZeroU64::new(max_quote_qty).ok_or(ArbitrageError::AmountBelowLotSize)?,
       i64::MAX,
-    )
-    .map_err(|_| ArbitrageError::InvalidCall)?;
+    )?;
 
     msg!("invoke serum new order");
     invoke(&serum_swap_instruction, &serum_swap_accounts[..])?;
@@ -3287,15 +4594,15 @@ impl Processor {
       user_quote_acc.key,
       None,
       vault_signer.key,
-    ).map_err(|_| ArbitrageError::InvalidCall)?;
+    )?;
   
     msg!("invoke serum settle funds");
-    invoke(&serum_settle_instruction, &serum_settle_accounts[..]).map_err(|_| ArbitrageError::InvalidCall)?;
+    invoke(&serum_settle_instruction, &serum_settle_accounts[..])?;
 
     Ok(())
   }  
 
-  fn mercurial_swap_2pool<'a>(
+  pub(crate) fn mercurial_swap_2pool<'a>(
     program_id: &Pubkey,
     market_acc: &AccountInfo<'a>,
     market_auth: &AccountInfo<'a>,
@@ -3306,6 +4613,7 @@ impl Processor {
     user_dst_acc: &AccountInfo<'a>,
     spl_token_program_acc: &AccountInfo<'a>,
     amount_in: u64,
+    minimum_amount_out: u64,
   ) -> ProgramResult {
 
     let swap_accs = [
@@ -3333,8 +4641,8 @@ impl Processor {
       user_src_acc.key,
       user_dst_acc.key,
       amount_in,
-      1,
-    ).map_err(|_| ArbitrageError::InvalidCall)?;
+      minimum_amount_out,
+    )?;
 
     msg!("invoke mercurial swap");
     invoke(&mercurial_exchange_instruction, &mercurial_exchange_accounts[..])?;
@@ -3342,7 +4650,7 @@ impl Processor {
     Ok(())   
   }
 
-  fn mercurial_swap_3pool<'a>(
+  pub(crate) fn mercurial_swap_3pool<'a>(
     program_id: &Pubkey,
     market_acc: &AccountInfo<'a>,
     market_auth: &AccountInfo<'a>,
@@ -3354,6 +4662,7 @@ impl Processor {
     user_dst_acc: &AccountInfo<'a>,
     spl_token_program_acc: &AccountInfo<'a>,
     amount_in: u64,
+    minimum_amount_out: u64,
   ) -> ProgramResult {
 
     let swap_accs = [
@@ -3383,8 +4692,8 @@ impl Processor {
       user_src_acc.key,
       user_dst_acc.key,
       amount_in,
-      1,
-    ).map_err(|_| ArbitrageError::InvalidCall)?;
+      minimum_amount_out,
+    )?;
 
     msg!("invoke mercurial swap");
     invoke(&mercurial_exchange_instruction, &mercurial_exchange_accounts[..])?;
@@ -3392,7 +4701,7 @@ impl Processor {
     Ok(())   
   }
   
-  fn mercurial_swap_4pool<'a>(
+  pub(crate) fn mercurial_swap_4pool<'a>(
     program_id: &Pubkey,
     market_acc: &AccountInfo<'a>,
     market_auth: &AccountInfo<'a>,
@@ -3405,6 +4714,7 @@ impl Processor {
     user_dst_acc: &AccountInfo<'a>,
     spl_token_program_acc: &AccountInfo<'a>,
     amount_in: u64,
+    minimum_amount_out: u64,
   ) -> ProgramResult {
 
     let swap_accs = [
@@ -3436,8 +4746,8 @@ impl Processor {
       user_src_acc.key,
       user_dst_acc.key,
       amount_in,
-      1,
-    ).map_err(|_| ArbitrageError::InvalidCall)?;
+      minimum_amount_out,
+    )?;
 
     msg!("invoke mercurial swap");
     invoke(&mercurial_exchange_instruction, &mercurial_exchange_accounts[..])?;
@@ -3501,7 +4811,7 @@ impl Processor {
     Ok(())
   }
 
-  fn whirl_swap<'a> (
+  pub(crate) fn whirl_swap<'a> (
     program_id: &Pubkey,
     market_acc: &AccountInfo<'a>,
     user_owner_acc: &AccountInfo<'a>,
@@ -3515,6 +4825,7 @@ impl Processor {
     oracle: &AccountInfo<'a>,
     spl_token_program: &AccountInfo<'a>,
     amount_in: u64,
+    minimum_amount_out: u64,
     aToB: u8,
   ) -> ProgramResult {
 
@@ -3547,21 +4858,14 @@ impl Processor {
     ];
 
     let mut data: [u8;42] = [0;42];
-    let zero:u64 = 0;
-    let max:u64 = u64::MAX;
-    let max_sqrt_price1:u64 = 3871828160200520623;
-    let max_sqrt_price2:u64 = 4294886577;
-    let min_sqrt_price1:u64 = 4295048016;
-    let min_sqrt_price2:u64 = 0;
     let (
       instruction_dst,
       amount_dst,
       otherAmountThreshold_dst,
-      sqrtPriceLimit1_dst,
-      sqrtPriceLimit2_dst,
+      sqrtPriceLimit_dst,
       exactInput_dst,
       aToB_dst,
-    ) = mut_array_refs![&mut data, 8, 8, 8, 8, 8, 1, 1];
+    ) = mut_array_refs![&mut data, 8, 8, 8, 16, 1, 1];
     //f8c69e91e17587c8
     instruction_dst[0] = 0xf8;
     instruction_dst[1] = 0xc6;
@@ -3572,13 +4876,12 @@ impl Processor {
     instruction_dst[6] = 0x87;
     instruction_dst[7] = 0xc8;
     *amount_dst = amount_in.to_le_bytes();
-    *otherAmountThreshold_dst = zero.to_le_bytes();
+    *otherAmountThreshold_dst = minimum_amount_out.to_le_bytes();
+    // no price limit, the minimum out amount bounds the swap
     if aToB == 0 {
-      *sqrtPriceLimit1_dst = max_sqrt_price1.to_le_bytes();
-      *sqrtPriceLimit2_dst = max_sqrt_price2.to_le_bytes();
+      *sqrtPriceLimit_dst = MAX_SQRT_PRICE.to_le_bytes();
     }  else {
-      *sqrtPriceLimit1_dst = min_sqrt_price1.to_le_bytes();
-      *sqrtPriceLimit2_dst = min_sqrt_price2.to_le_bytes();
+      *sqrtPriceLimit_dst = MIN_SQRT_PRICE.to_le_bytes();
     }
     exactInput_dst[0] = 1;
     aToB_dst[0] = aToB;
@@ -3595,6 +4898,68 @@ impl Processor {
     Ok(())
   }
 
+  // crema swap
+  pub(crate) fn crema_swap<'a>(
+    program_id: &Pubkey,
+    market_acc: &AccountInfo<'a>,
+    market_auth: &AccountInfo<'a>,
+    owner_acc: &AccountInfo<'a>,
+    user_src_acc: &AccountInfo<'a>,
+    swap_src_acc: &AccountInfo<'a>,
+    swap_dst_acc: &AccountInfo<'a>,
+    user_dst_acc: &AccountInfo<'a>,
+    ticks_acc: &AccountInfo<'a>,
+    spl_token_program_acc: &AccountInfo<'a>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+  ) -> ProgramResult {
+    let crema_swap_accounts = [
+      market_acc.clone(),
+      market_auth.clone(),
+      owner_acc.clone(),
+      user_src_acc.clone(),
+      user_dst_acc.clone(),
+      swap_src_acc.clone(),
+      swap_dst_acc.clone(),
+      ticks_acc.clone(),
+      spl_token_program_acc.clone(),
+    ];
+
+    let accounts = vec![
+      AccountMeta::new(*market_acc.key, false),
+      AccountMeta::new_readonly(*market_auth.key, false),
+      AccountMeta::new_readonly(*owner_acc.key, true),
+      AccountMeta::new(*user_src_acc.key, false),
+      AccountMeta::new(*user_dst_acc.key, false),
+      AccountMeta::new(*swap_src_acc.key, false),
+      AccountMeta::new(*swap_dst_acc.key, false),
+      AccountMeta::new(*ticks_acc.key, false),
+      AccountMeta::new_readonly(*spl_token_program_acc.key, false),
+    ];
+
+    let mut data: [u8;17] = [0;17];
+    let (
+      instruction_dst,
+      amount_in_dst,
+      minimum_amount_out_dst,
+    ) = mut_array_refs![&mut data, 1, 8, 8];
+    // swap
+    instruction_dst[0] = 1;
+    *amount_in_dst = amount_in.to_le_bytes();
+    *minimum_amount_out_dst = minimum_amount_out.to_le_bytes();
+
+    let crema_swap_instruction = Instruction {
+      program_id: *program_id,
+      accounts: accounts,
+      data: data.to_vec(),
+    };
+
+    msg!("invoke crema swap");
+    invoke(&crema_swap_instruction, &crema_swap_accounts[..])?;
+
+    Ok(())
+  }
+
   /*
   fn marinade_deposit<'a> (
     program_id: &Pubkey,
@@ -3658,7 +5023,7 @@ impl Processor {
   */
 }
 
-fn find_side(value: u8) -> Option<Side> {
+pub(crate) fn find_side(value: u8) -> Option<Side> {
   match value {
       0 => Some(Side::Bid),
       1 => Some(Side::Ask),
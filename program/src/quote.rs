@@ -0,0 +1,144 @@
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use spl_token::state::Account as TokenAccount;
+use spl_token_swap::{
+  curve::base::CurveType,
+  state::{SwapState, SwapVersion},
+};
+
+use std::convert::TryFrom;
+
+use crate::error::ArbitrageError;
+
+/// trade and owner fee of an spl-token-swap pool, both are taken from the input
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PoolFees {
+  pub trade_fee_numerator: u64,
+  pub trade_fee_denominator: u64,
+  pub owner_trade_fee_numerator: u64,
+  pub owner_trade_fee_denominator: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SwapQuote {
+  /// debited from the user, rounding can leave it below the requested input
+  pub amount_in: u64,
+  pub amount_out: u64,
+  /// trade and owner fee, part of `amount_in`
+  pub fee: u64,
+}
+
+/// A constant product spl-token-swap pool (orca v1/v2), read from the swap state
+/// and its two vaults.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConstantProductPool {
+  pub reserve_a: u64,
+  pub reserve_b: u64,
+  pub fees: PoolFees,
+}
+
+impl ConstantProductPool {
+  pub fn load(
+    program_id: &Pubkey,
+    market_acc: &AccountInfo,
+    swap_a_acc: &AccountInfo,
+    swap_b_acc: &AccountInfo,
+  ) -> Result<Self, ProgramError> {
+    if market_acc.owner != program_id {
+      return Err(ArbitrageError::InvalidOwner.into());
+    }
+    let swap_state = SwapVersion::unpack(&market_acc.try_borrow_data()?)?;
+    if swap_state.swap_curve().curve_type != CurveType::ConstantProduct {
+      return Err(ArbitrageError::UnsupportedCurve.into());
+    }
+    if swap_state.token_a_account() != swap_a_acc.key || swap_state.token_b_account() != swap_b_acc.key {
+      return Err(ArbitrageError::PoolAccountMismatch.into());
+    }
+    let fees = swap_state.fees();
+    Ok(
+      ConstantProductPool {
+        reserve_a: TokenAccount::unpack(&swap_a_acc.try_borrow_data()?)?.amount,
+        reserve_b: TokenAccount::unpack(&swap_b_acc.try_borrow_data()?)?.amount,
+        fees: PoolFees {
+          trade_fee_numerator: fees.trade_fee_numerator,
+          trade_fee_denominator: fees.trade_fee_denominator,
+          owner_trade_fee_numerator: fees.owner_trade_fee_numerator,
+          owner_trade_fee_denominator: fees.owner_trade_fee_denominator,
+        },
+      }
+    )
+  }
+
+  /// `None` when the pool cannot fill the trade, as the swap program would fail it
+  pub fn quote(&self, amount_in: u64, a_to_b: bool) -> Option<SwapQuote> {
+    if a_to_b {
+      constant_product_quote(amount_in, self.reserve_a, self.reserve_b, &self.fees)
+    } else {
+      constant_product_quote(amount_in, self.reserve_b, self.reserve_a, &self.fees)
+    }
+  }
+}
+
+/// spl-token-swap charges at least one token once the fee numerator is set
+fn calculate_fee(amount: u128, fee_numerator: u128, fee_denominator: u128) -> Option<u128> {
+  if fee_numerator == 0 || amount == 0 {
+    return Some(0);
+  }
+  let fee = amount.checked_mul(fee_numerator)?.checked_div(fee_denominator)?;
+  if fee == 0 { Some(1) } else { Some(fee) }
+}
+
+/// spl-math's ceiling division, returns the rounded up quotient and the smallest
+/// divisor that still produces it
+fn checked_ceil_div(dividend: u128, divisor: u128) -> Option<(u128, u128)> {
+  let mut quotient = dividend.checked_div(divisor)?;
+  if quotient == 0 {
+    if dividend.checked_mul(2)? >= divisor {
+      return Some((1, 0));
+    }
+    return Some((0, 0));
+  }
+  let mut divisor = divisor;
+  if dividend.checked_rem(divisor)? > 0 {
+    quotient = quotient.checked_add(1)?;
+    divisor = dividend.checked_div(quotient)?;
+    if dividend.checked_rem(quotient)? > 0 {
+      divisor = divisor.checked_add(1)?;
+    }
+  }
+  Some((quotient, divisor))
+}
+
+/// `SwapCurve::swap` of spl-token-swap 2.1 for the constant product curve: fees come
+/// off the input, the output is rounded down through a ceiling division of the invariant.
+pub fn constant_product_quote(amount_in: u64, reserve_in: u64, reserve_out: u64, fees: &PoolFees) -> Option<SwapQuote> {
+  let amount_in = amount_in as u128;
+  let trade_fee = calculate_fee(amount_in, fees.trade_fee_numerator as u128, fees.trade_fee_denominator as u128)?;
+  let owner_fee = calculate_fee(amount_in, fees.owner_trade_fee_numerator as u128, fees.owner_trade_fee_denominator as u128)?;
+  let total_fee = trade_fee.checked_add(owner_fee)?;
+  let amount_in_less_fee = amount_in.checked_sub(total_fee)?;
+
+  let reserve_in = reserve_in as u128;
+  let reserve_out = reserve_out as u128;
+  let invariant = reserve_in.checked_mul(reserve_out)?;
+  let new_reserve_in = reserve_in.checked_add(amount_in_less_fee)?;
+  let (new_reserve_out, new_reserve_in) = checked_ceil_div(invariant, new_reserve_in)?;
+  let amount_in_swapped = new_reserve_in.checked_sub(reserve_in)?;
+  let amount_out = reserve_out.checked_sub(new_reserve_out)?;
+  if amount_out == 0 {
+    return None;
+  }
+
+  Some(
+    SwapQuote {
+      amount_in: u64::try_from(amount_in_swapped.checked_add(total_fee)?).ok()?,
+      amount_out: u64::try_from(amount_out).ok()?,
+      fee: u64::try_from(total_fee).ok()?,
+    }
+  )
+}
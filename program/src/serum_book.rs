@@ -0,0 +1,280 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use arrayref::{array_ref, array_refs};
+
+use std::convert::TryFrom;
+
+use crate::error::ArbitrageError;
+
+/// "serum" in front of every dex account
+const ACCOUNT_HEAD_PADDING: usize = 5;
+/// "padding" behind every dex account
+const ACCOUNT_TAIL_PADDING: usize = 7;
+/// `MarketState` up to and including `referrer_rebates_accrued`
+const MARKET_STATE_LEN: usize = 376;
+/// account flags in front of the bids and asks slabs
+const BOOK_HEADER_LEN: usize = 8;
+
+const FLAG_INITIALIZED: u64 = 1 << 0;
+const FLAG_MARKET: u64 = 1 << 1;
+const FLAG_BIDS: u64 = 1 << 5;
+const FLAG_ASKS: u64 = 1 << 6;
+
+const SLAB_HEADER_LEN: usize = 32;
+const SLAB_NODE_LEN: usize = 72;
+const NODE_TAG_INNER: u32 = 1;
+const NODE_TAG_LEAF: u32 = 2;
+
+/// one price level of the book, orders at the same price are merged
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrderBookLevel {
+  /// quote lots per base lot
+  pub price: u64,
+  /// base lots
+  pub quantity: u64,
+}
+
+/// native amounts executable against one side of the book
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BookDepth {
+  pub base_amount: u64,
+  pub quote_amount: u64,
+}
+
+/// Read-only view of a serum market's order book.
+///
+/// The market, bids and asks are decoded from their raw data without a mutable
+/// borrow, so the accounts may be passed readonly, and an empty side or an
+/// account that does not decode is an error instead of a panic.
+pub struct SerumBookReader<'a, 'b> {
+  pub coin_lot_size: u64,
+  pub pc_lot_size: u64,
+  bids_acc: &'a AccountInfo<'b>,
+  asks_acc: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> SerumBookReader<'a, 'b> {
+  /// checks that `bids_acc` and `asks_acc` are the books of `market_acc`
+  pub fn load(
+    program_id: &Pubkey,
+    market_acc: &'a AccountInfo<'b>,
+    bids_acc: &'a AccountInfo<'b>,
+    asks_acc: &'a AccountInfo<'b>,
+  ) -> Result<Self, ProgramError> {
+    if market_acc.owner != program_id {
+      return Err(ArbitrageError::InvalidOwner.into());
+    }
+    let data = market_acc.try_borrow_data()?;
+    if data.len() < ACCOUNT_HEAD_PADDING + MARKET_STATE_LEN + ACCOUNT_TAIL_PADDING {
+      return Err(ArbitrageError::InvalidOrderBook.into());
+    }
+    let market = array_ref![data, ACCOUNT_HEAD_PADDING, MARKET_STATE_LEN];
+    let (
+      account_flags,
+      own_address,
+      _vault_signer_nonce,
+      _coin_mint,
+      _pc_mint,
+      _coin_vault,
+      _coin_deposits_total,
+      _coin_fees_accrued,
+      _pc_vault,
+      _pc_deposits_total,
+      _pc_fees_accrued,
+      _pc_dust_threshold,
+      _req_q,
+      _event_q,
+      bids,
+      asks,
+      coin_lot_size,
+      pc_lot_size,
+      _fee_rate_bps,
+      _referrer_rebates_accrued,
+    ) = array_refs![market, 8, 32, 8, 32, 32, 32, 8, 8, 32, 8, 8, 8, 32, 32, 32, 32, 8, 8, 8, 8];
+
+    let account_flags = u64::from_le_bytes(*account_flags);
+    if account_flags & (FLAG_INITIALIZED | FLAG_MARKET) != FLAG_INITIALIZED | FLAG_MARKET {
+      return Err(ArbitrageError::InvalidOrderBook.into());
+    }
+    if own_address != &market_acc.key.to_bytes()
+      || bids != &bids_acc.key.to_bytes()
+      || asks != &asks_acc.key.to_bytes() {
+      return Err(ArbitrageError::InvalidOrderBook.into());
+    }
+    let coin_lot_size = u64::from_le_bytes(*coin_lot_size);
+    let pc_lot_size = u64::from_le_bytes(*pc_lot_size);
+    if coin_lot_size == 0 || pc_lot_size == 0 {
+      return Err(ArbitrageError::InvalidOrderBook.into());
+    }
+
+    Ok(
+      SerumBookReader {
+        coin_lot_size: coin_lot_size,
+        pc_lot_size: pc_lot_size,
+        bids_acc: bids_acc,
+        asks_acc: asks_acc,
+      }
+    )
+  }
+
+  /// up to `depth` bid levels, highest price first
+  pub fn bids(&self, depth: usize) -> Result<Vec<OrderBookLevel>, ProgramError> {
+    collect_levels(self.bids_acc, FLAG_BIDS, true, depth)
+  }
+
+  /// up to `depth` ask levels, lowest price first
+  pub fn asks(&self, depth: usize) -> Result<Vec<OrderBookLevel>, ProgramError> {
+    collect_levels(self.asks_acc, FLAG_ASKS, false, depth)
+  }
+
+  pub fn best_bid(&self) -> Result<OrderBookLevel, ProgramError> {
+    self.bids(1)?.pop().ok_or_else(|| ArbitrageError::EmptyOrderBook.into())
+  }
+
+  pub fn best_ask(&self) -> Result<OrderBookLevel, ProgramError> {
+    self.asks(1)?.pop().ok_or_else(|| ArbitrageError::EmptyOrderBook.into())
+  }
+
+  /// What a market sell fills against bids whose normalized price is at or above
+  /// `limit_price`, see `normalized_price` for `base_unit`.
+  pub fn bids_depth(&self, limit_price: u64, base_unit: u64) -> Result<BookDepth, ProgramError> {
+    self.depth(self.bids_acc, FLAG_BIDS, true, limit_price, base_unit)
+  }
+
+  /// What a market buy fills against asks whose normalized price is at or below
+  /// `limit_price`, see `normalized_price` for `base_unit`.
+  pub fn asks_depth(&self, limit_price: u64, base_unit: u64) -> Result<BookDepth, ProgramError> {
+    self.depth(self.asks_acc, FLAG_ASKS, false, limit_price, base_unit)
+  }
+
+  fn depth(&self, book_acc: &AccountInfo, side_flag: u64, descending: bool, limit_price: u64, base_unit: u64) -> Result<BookDepth, ProgramError> {
+    let mut base_lots: u128 = 0;
+    let mut quote_lots: u128 = 0;
+    walk_book(book_acc, side_flag, descending, |price, quantity| {
+      let price_normalized = self.normalized_price(price, base_unit)?;
+      let in_limit = if descending { price_normalized >= limit_price } else { price_normalized <= limit_price };
+      if in_limit {
+        base_lots = base_lots.checked_add(quantity as u128).ok_or(ArbitrageError::MathOverflow)?;
+        quote_lots = quote_lots.checked_add(price as u128 * quantity as u128).ok_or(ArbitrageError::MathOverflow)?;
+      }
+      Ok(in_limit)
+    })?;
+    Ok(
+      BookDepth {
+        base_amount: to_u64(base_lots.checked_mul(self.coin_lot_size as u128))?,
+        quote_amount: to_u64(quote_lots.checked_mul(self.pc_lot_size as u128))?,
+      }
+    )
+  }
+
+  /// native base units in `quantity` base lots
+  pub fn base_amount(&self, quantity: u64) -> Result<u64, ProgramError> {
+    to_u64((quantity as u128).checked_mul(self.coin_lot_size as u128))
+  }
+
+  /// native quote units paid for the whole level
+  pub fn quote_amount(&self, level: &OrderBookLevel) -> Result<u64, ProgramError> {
+    to_u64((level.price as u128 * level.quantity as u128).checked_mul(self.pc_lot_size as u128))
+  }
+
+  /// native quote units paid for `base_unit` native base units at `price`
+  pub fn normalized_price(&self, price: u64, base_unit: u64) -> Result<u64, ProgramError> {
+    to_u64(
+      (price as u128 * self.pc_lot_size as u128)
+        .checked_mul(base_unit as u128)
+        .map(|value| value / self.coin_lot_size as u128)
+    )
+  }
+}
+
+/// products of prices and lot sizes, `MathOverflow` when they leave u64
+fn to_u64(value: Option<u128>) -> Result<u64, ProgramError> {
+  value
+    .and_then(|value| u64::try_from(value).ok())
+    .ok_or_else(|| ArbitrageError::MathOverflow.into())
+}
+
+/// In order walk of the critbit slab, `descending` visits the higher child first.
+///
+/// `visit` gets every order as `(price, quantity)` in lots and returns false to stop.
+fn walk_book<F: FnMut(u64, u64) -> Result<bool, ProgramError>>(book_acc: &AccountInfo, side_flag: u64, descending: bool, mut visit: F) -> ProgramResult {
+  let data = book_acc.try_borrow_data()?;
+  if data.len() < ACCOUNT_HEAD_PADDING + BOOK_HEADER_LEN + SLAB_HEADER_LEN + ACCOUNT_TAIL_PADDING {
+    return Err(ArbitrageError::InvalidOrderBook.into());
+  }
+  let account_flags = u64::from_le_bytes(*array_ref![data, ACCOUNT_HEAD_PADDING, 8]);
+  if account_flags != FLAG_INITIALIZED | side_flag {
+    return Err(ArbitrageError::InvalidOrderBook.into());
+  }
+  let slab = &data[ACCOUNT_HEAD_PADDING + BOOK_HEADER_LEN..data.len() - ACCOUNT_TAIL_PADDING];
+  let header = array_ref![slab, 0, SLAB_HEADER_LEN];
+  let (bump_index, _free_list_len, _free_list_head, root_node, leaf_count) = array_refs![header, 8, 8, 4, 4, 8];
+  let bump_index = u64::from_le_bytes(*bump_index);
+  if bump_index > ((slab.len() - SLAB_HEADER_LEN) / SLAB_NODE_LEN) as u64 {
+    return Err(ArbitrageError::InvalidOrderBook.into());
+  }
+  if u64::from_le_bytes(*leaf_count) == 0 {
+    return Ok(());
+  }
+
+  let mut stack = vec![u32::from_le_bytes(*root_node)];
+  // a well formed tree visits every node at most once
+  let mut visited = 0u64;
+  while let Some(index) = stack.pop() {
+    visited += 1;
+    if index as u64 >= bump_index || visited > bump_index {
+      return Err(ArbitrageError::InvalidOrderBook.into());
+    }
+    let node = array_ref![slab, SLAB_HEADER_LEN + index as usize * SLAB_NODE_LEN, SLAB_NODE_LEN];
+    let (tag, _, key, children, _, quantity, _) = array_refs![node, 4, 4, 16, 8, 24, 8, 8];
+    match u32::from_le_bytes(*tag) {
+      NODE_TAG_INNER => {
+        let (low, high) = array_refs![children, 4, 4];
+        if descending {
+          stack.push(u32::from_le_bytes(*low));
+          stack.push(u32::from_le_bytes(*high));
+        } else {
+          stack.push(u32::from_le_bytes(*high));
+          stack.push(u32::from_le_bytes(*low));
+        }
+      },
+      NODE_TAG_LEAF => {
+        // the order key is the price in the upper 64 bits and a sequence number below
+        let price = (u128::from_le_bytes(*key) >> 64) as u64;
+        if !visit(price, u64::from_le_bytes(*quantity))? {
+          break;
+        }
+      },
+      _ => return Err(ArbitrageError::InvalidOrderBook.into()),
+    }
+  }
+  Ok(())
+}
+
+/// merges orders of equal price into at most `depth` levels
+fn collect_levels(book_acc: &AccountInfo, side_flag: u64, descending: bool, depth: usize) -> Result<Vec<OrderBookLevel>, ProgramError> {
+  let mut levels: Vec<OrderBookLevel> = Vec::with_capacity(depth);
+  if depth == 0 {
+    return Ok(levels);
+  }
+  walk_book(book_acc, side_flag, descending, |price, quantity| {
+    match levels.last_mut() {
+      Some(level) if level.price == price => {
+        level.quantity = level.quantity.checked_add(quantity).ok_or(ArbitrageError::InvalidOrderBook)?;
+      },
+      _ => {
+        if levels.len() == depth {
+          return Ok(false);
+        }
+        levels.push(OrderBookLevel { price: price, quantity: quantity });
+      },
+    }
+    Ok(true)
+  })?;
+  Ok(levels)
+}
@@ -0,0 +1,93 @@
+/// golden-section steps before the solver settles, each one quotes a round trip
+/// through both pools so this bounds the compute spent on sizing
+pub const SOLVER_MAX_ITERATIONS: u32 = 24;
+
+/// 1/phi as a fraction of 10^9
+const INV_PHI_NUMERATOR: u128 = 618_033_989;
+const INV_PHI_DENOMINATOR: u128 = 1_000_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OptimalTrade {
+  pub amount_in: u64,
+  /// quoted output of the second leg, in the token the cycle started with
+  pub amount_out: u64,
+  pub profit: u64,
+}
+
+/// `amount_in` through `first_leg` and its output through `second_leg`
+pub fn round_trip<F, G>(first_leg: F, second_leg: G, amount_in: u64) -> Option<u64>
+where
+  F: Fn(u64) -> Option<u64>,
+  G: Fn(u64) -> Option<u64>,
+{
+  first_leg(amount_in).and_then(second_leg)
+}
+
+/// Golden-section search over `[1, max_amount_in]` for the input that makes the
+/// most out of a two pool cycle. `first_leg` and `second_leg` quote each pool's
+/// output for an input, `None` when it cannot fill it.
+///
+/// The profit of a cycle through constant-product, stableswap or concentrated
+/// liquidity pools is concave in the input, so every quote narrows the range by
+/// 1/phi. The best profitable input seen is returned, `None` when every quoted
+/// input loses.
+pub fn optimal_input<F, G>(first_leg: F, second_leg: G, max_amount_in: u64, max_iterations: u32) -> Option<OptimalTrade>
+where
+  F: Fn(u64) -> Option<u64>,
+  G: Fn(u64) -> Option<u64>,
+{
+  if max_amount_in == 0 {
+    return None;
+  }
+  let mut best: Option<OptimalTrade> = None;
+  let mut profit_at = |amount_in: u64| -> i128 {
+    match round_trip(&first_leg, &second_leg, amount_in) {
+      Some(amount_out) => {
+        let profit = amount_out as i128 - amount_in as i128;
+        if profit > 0 && best.map_or(true, |trade| profit > trade.profit as i128) {
+          best = Some(
+            OptimalTrade {
+              amount_in: amount_in,
+              amount_out: amount_out,
+              profit: profit as u64,
+            }
+          );
+        }
+        profit
+      }
+      // more than the pools can fill ranks below any loss
+      None => i128::MIN,
+    }
+  };
+  //
+  let mut low = 1;
+  let mut high = max_amount_in;
+  let mut left = high - golden_step(low, high);
+  let mut right = low + golden_step(low, high);
+  let mut left_profit = profit_at(left);
+  let mut right_profit = profit_at(right);
+  for _ in 0..max_iterations {
+    if high - low < 3 {
+      break;
+    }
+    // the step is below the width, so either bound moves in every iteration
+    if left_profit < right_profit {
+      low = left;
+      left = right;
+      left_profit = right_profit;
+      right = low + golden_step(low, high);
+      right_profit = profit_at(right);
+    } else {
+      high = right;
+      right = left;
+      right_profit = left_profit;
+      left = high - golden_step(low, high);
+      left_profit = profit_at(left);
+    }
+  }
+  best
+}
+
+fn golden_step(low: u64, high: u64) -> u64 {
+  ((high - low) as u128 * INV_PHI_NUMERATOR / INV_PHI_DENOMINATOR) as u64
+}
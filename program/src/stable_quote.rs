@@ -0,0 +1,385 @@
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use arrayref::{array_ref, array_refs};
+use spl_token::state::Account as TokenAccount;
+use stable_swap_client::state::SwapInfo;
+
+use std::convert::TryFrom;
+
+use crate::{
+  error::ArbitrageError,
+  math::{to_u128, U256},
+};
+
+/// newton iterations before `compute_d` and `compute_y` give up converging
+const MAX_ITERATIONS: usize = 256;
+
+/// mercurial fees are numerators over 10^10
+pub const MERCURIAL_FEE_DENOMINATOR: u64 = 10_000_000_000;
+/// a mercurial pool holds at most four tokens
+pub const MERCURIAL_MAX_TOKENS: usize = 4;
+/// version, is_initialized, nonce, amplification_coefficient, fee_numerator,
+/// admin_fee_numerator, token_accounts_length, precision_factor,
+/// precision_multipliers[4], token_accounts[4], pool_mint, admin_token_mint
+const MERCURIAL_STATE_LEN: usize = 263;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StableQuote {
+  pub amount_out: u64,
+  /// trade fee, taken from the output before `amount_out`
+  pub fee: u64,
+}
+
+/// Saber's linear amplification ramp between `start_ramp_ts` and `stop_ramp_ts`
+pub fn compute_amp_factor(
+  initial_amp_factor: u64,
+  target_amp_factor: u64,
+  start_ramp_ts: i64,
+  stop_ramp_ts: i64,
+  now: i64,
+) -> Option<u64> {
+  if now >= stop_ramp_ts {
+    return Some(target_amp_factor);
+  }
+  if now <= start_ramp_ts {
+    return Some(initial_amp_factor);
+  }
+  let time_range = u128::try_from(stop_ramp_ts.checked_sub(start_ramp_ts)?).ok()?;
+  let time_delta = u128::try_from(now.checked_sub(start_ramp_ts)?).ok()?;
+  if target_amp_factor >= initial_amp_factor {
+    let amp_range = (target_amp_factor - initial_amp_factor) as u128;
+    let amp_delta = u64::try_from(amp_range.checked_mul(time_delta)?.checked_div(time_range)?).ok()?;
+    initial_amp_factor.checked_add(amp_delta)
+  } else {
+    let amp_range = (initial_amp_factor - target_amp_factor) as u128;
+    let amp_delta = u64::try_from(amp_range.checked_mul(time_delta)?.checked_div(time_range)?).ok()?;
+    initial_amp_factor.checked_sub(amp_delta)
+  }
+}
+
+/// The StableSwap invariant `D` of `balances`, by Newton's method as in curve's
+/// and saber's `get_D`. `amp` is the amplification coefficient `A`, the leverage
+/// is `A * n`.
+pub fn compute_d(amp: u64, balances: &[u128]) -> Option<U256> {
+  let n_coins = U256::from(balances.len());
+  let sum = balances.iter().try_fold(U256::zero(), |sum, balance| sum.checked_add(U256::from(*balance)))?;
+  if sum.is_zero() {
+    return Some(sum);
+  }
+  let ann = U256::from(amp).checked_mul(n_coins)?;
+  let mut d = sum;
+  for _ in 0..MAX_ITERATIONS {
+    let mut d_product = d;
+    for balance in balances {
+      d_product = d_product.checked_mul(d)?.checked_div(U256::from(*balance).checked_mul(n_coins)?)?;
+    }
+    let d_previous = d;
+    // d = (ann * sum + d_product * n) * d / ((ann - 1) * d + (n + 1) * d_product)
+    let numerator = ann.checked_mul(sum)?.checked_add(d_product.checked_mul(n_coins)?)?.checked_mul(d)?;
+    let denominator = ann.checked_sub(U256::one())?.checked_mul(d)?
+      .checked_add(n_coins.checked_add(U256::one())?.checked_mul(d_product)?)?;
+    d = numerator.checked_div(denominator)?;
+    if abs_diff(d, d_previous) <= U256::one() {
+      break;
+    }
+  }
+  Some(d)
+}
+
+/// Balance of token `j` that keeps `D` once token `i` holds `x`, curve's `get_y`.
+pub fn compute_y(amp: u64, balances: &[u128], i: usize, j: usize, x: u128) -> Option<U256> {
+  if i == j || i >= balances.len() || j >= balances.len() {
+    return None;
+  }
+  let n_coins = U256::from(balances.len());
+  let d = compute_d(amp, balances)?;
+  let ann = U256::from(amp).checked_mul(n_coins)?;
+
+  // c = D^(n + 1) / (n^n * prod(x_k) * ann * n), b = sum(x_k) + D / ann over k != j
+  let mut c = d;
+  let mut sum = U256::zero();
+  for (k, balance) in balances.iter().enumerate() {
+    if k == j {
+      continue;
+    }
+    let balance = U256::from(if k == i { x } else { *balance });
+    sum = sum.checked_add(balance)?;
+    c = c.checked_mul(d)?.checked_div(balance.checked_mul(n_coins)?)?;
+  }
+  c = c.checked_mul(d)?.checked_div(ann.checked_mul(n_coins)?)?;
+  let b = sum.checked_add(d.checked_div(ann)?)?;
+
+  // y^2 + (b - D) * y = c
+  let mut y = d;
+  for _ in 0..MAX_ITERATIONS {
+    let y_previous = y;
+    let numerator = y.checked_mul(y)?.checked_add(c)?;
+    let denominator = y.checked_mul(U256::from(2))?.checked_add(b)?.checked_sub(d)?;
+    y = numerator.checked_div(denominator)?;
+    if abs_diff(y, y_previous) <= U256::one() {
+      break;
+    }
+  }
+  Some(y)
+}
+
+/// Tokens `j` paid for `amount_in` tokens `i` before fees. One unit is held back
+/// against rounding as curve's `exchange` does.
+pub fn stable_swap_output(amp: u64, balances: &[u128], i: usize, j: usize, amount_in: u128) -> Option<u128> {
+  let x = balances.get(i)?.checked_add(amount_in)?;
+  let y = to_u128(compute_y(amp, balances, i, j, x)?)?;
+  balances.get(j)?.checked_sub(y)?.checked_sub(1)
+}
+
+fn abs_diff(a: U256, b: U256) -> U256 {
+  if a > b { a - b } else { b - a }
+}
+
+fn trade_fee(amount: u64, fee_numerator: u64, fee_denominator: u64) -> Option<u64> {
+  if fee_numerator == 0 {
+    return Some(0);
+  }
+  u64::try_from((amount as u128).checked_mul(fee_numerator as u128)?.checked_div(fee_denominator as u128)?).ok()
+}
+
+fn stable_quote(amount_out: u128, fee_numerator: u64, fee_denominator: u64) -> Option<StableQuote> {
+  let amount_out = u64::try_from(amount_out).ok()?;
+  let fee = trade_fee(amount_out, fee_numerator, fee_denominator)?;
+  let amount_out = amount_out.checked_sub(fee)?;
+  if amount_out == 0 {
+    return None;
+  }
+  Some(StableQuote { amount_out: amount_out, fee: fee })
+}
+
+/// A saber pool, read from its `SwapInfo` and two vaults with the amplification
+/// ramped to `now`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SaberPool {
+  pub amp_factor: u64,
+  pub is_paused: bool,
+  pub mint_a: Pubkey,
+  pub mint_b: Pubkey,
+  pub reserve_a: u64,
+  pub reserve_b: u64,
+  pub trade_fee_numerator: u64,
+  pub trade_fee_denominator: u64,
+}
+
+impl SaberPool {
+  pub fn load(
+    program_id: &Pubkey,
+    market_acc: &AccountInfo,
+    swap_a_acc: &AccountInfo,
+    swap_b_acc: &AccountInfo,
+    now: i64,
+  ) -> Result<Self, ProgramError> {
+    if market_acc.owner != program_id {
+      return Err(ArbitrageError::InvalidOwner.into());
+    }
+    let swap_info = SwapInfo::unpack(&market_acc.try_borrow_data()?)?;
+    if swap_info.token_a.reserves != *swap_a_acc.key || swap_info.token_b.reserves != *swap_b_acc.key {
+      return Err(ArbitrageError::PoolAccountMismatch.into());
+    }
+    let amp_factor = compute_amp_factor(
+      swap_info.initial_amp_factor,
+      swap_info.target_amp_factor,
+      swap_info.start_ramp_ts,
+      swap_info.stop_ramp_ts,
+      now,
+    ).ok_or(ArbitrageError::InvalidPoolState)?;
+    Ok(
+      SaberPool {
+        amp_factor: amp_factor,
+        is_paused: swap_info.is_paused,
+        mint_a: swap_info.token_a.mint,
+        mint_b: swap_info.token_b.mint,
+        reserve_a: TokenAccount::unpack(&swap_a_acc.try_borrow_data()?)?.amount,
+        reserve_b: TokenAccount::unpack(&swap_b_acc.try_borrow_data()?)?.amount,
+        trade_fee_numerator: swap_info.fees.trade_fee_numerator,
+        trade_fee_denominator: swap_info.fees.trade_fee_denominator,
+      }
+    )
+  }
+
+  /// `None` when the pool cannot fill the trade, as the swap program would fail it
+  pub fn quote(&self, amount_in: u64, a_to_b: bool) -> Option<StableQuote> {
+    if self.is_paused {
+      return None;
+    }
+    let (i, j) = if a_to_b { (0, 1) } else { (1, 0) };
+    let balances = [self.reserve_a as u128, self.reserve_b as u128];
+    let amount_out = stable_swap_output(self.amp_factor, &balances, i, j, amount_in as u128)?;
+    stable_quote(amount_out, self.trade_fee_numerator, self.trade_fee_denominator)
+  }
+}
+
+/// A mercurial n-pool, read from its swap state and vaults.
+///
+/// Balances are scaled by the pool's precision multipliers to a common
+/// decimal count before they enter the invariant.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MercurialPool {
+  pub amp: u64,
+  pub fee_numerator: u64,
+  pub precision_multipliers: Vec<u64>,
+  pub mints: Vec<Pubkey>,
+  pub reserves: Vec<u64>,
+}
+
+impl MercurialPool {
+  /// `swap_accs` are the pool's vaults in the order of its state
+  pub fn load(program_id: &Pubkey, market_acc: &AccountInfo, swap_accs: &[&AccountInfo]) -> Result<Self, ProgramError> {
+    if market_acc.owner != program_id {
+      return Err(ArbitrageError::InvalidOwner.into());
+    }
+    let data = market_acc.try_borrow_data()?;
+    if data.len() < MERCURIAL_STATE_LEN {
+      return Err(ArbitrageError::InvalidPoolState.into());
+    }
+    let state = array_ref![data, 0, MERCURIAL_STATE_LEN];
+    let (
+      _version,
+      is_initialized,
+      _nonce,
+      amp,
+      fee_numerator,
+      _admin_fee_numerator,
+      token_accounts_length,
+      _precision_factor,
+      precision_multipliers,
+      token_accounts,
+      _pool_mint,
+      _admin_token_mint,
+    ) = array_refs![state, 1, 1, 1, 8, 8, 8, 4, 8, 32, 128, 32, 32];
+
+    let token_count = u32::from_le_bytes(*token_accounts_length) as usize;
+    if is_initialized[0] != 1 || token_count < 2 || token_count > MERCURIAL_MAX_TOKENS {
+      return Err(ArbitrageError::InvalidPoolState.into());
+    }
+    if token_count != swap_accs.len() {
+      return Err(ArbitrageError::PoolAccountMismatch.into());
+    }
+
+    let mut multipliers = Vec::with_capacity(token_count);
+    let mut mints = Vec::with_capacity(token_count);
+    let mut reserves = Vec::with_capacity(token_count);
+    for (k, swap_acc) in swap_accs.iter().enumerate() {
+      if array_ref![token_accounts, k * 32, 32] != &swap_acc.key.to_bytes() {
+        return Err(ArbitrageError::PoolAccountMismatch.into());
+      }
+      let multiplier = u64::from_le_bytes(*array_ref![precision_multipliers, k * 8, 8]);
+      if multiplier == 0 {
+        return Err(ArbitrageError::InvalidPoolState.into());
+      }
+      let vault = TokenAccount::unpack(&swap_acc.try_borrow_data()?)?;
+      multipliers.push(multiplier);
+      mints.push(vault.mint);
+      reserves.push(vault.amount);
+    }
+
+    Ok(
+      MercurialPool {
+        amp: u64::from_le_bytes(*amp),
+        fee_numerator: u64::from_le_bytes(*fee_numerator),
+        precision_multipliers: multipliers,
+        mints: mints,
+        reserves: reserves,
+      }
+    )
+  }
+
+  /// index of the vault holding `mint`
+  pub fn index_of(&self, mint: &Pubkey) -> Option<usize> {
+    self.mints.iter().position(|pool_mint| pool_mint == mint)
+  }
+
+  /// `amount_in` of token `i` for token `j`, `None` when the pool cannot fill it
+  pub fn quote(&self, amount_in: u64, i: usize, j: usize) -> Option<StableQuote> {
+    let balances = self.reserves
+      .iter()
+      .zip(self.precision_multipliers.iter())
+      .map(|(reserve, multiplier)| (*reserve as u128).checked_mul(*multiplier as u128))
+      .collect::<Option<Vec<u128>>>()?;
+    let amount_in = (amount_in as u128).checked_mul(*self.precision_multipliers.get(i)? as u128)?;
+    let amount_out = stable_swap_output(self.amp, &balances, i, j, amount_in)?;
+    let amount_out = amount_out.checked_div(*self.precision_multipliers.get(j)? as u128)?;
+    stable_quote(amount_out, self.fee_numerator, MERCURIAL_FEE_DENOMINATOR)
+  }
+}
+
+/// A saber and a mercurial pool quoted as the two legs of a usdc -> ust -> usdc
+/// cycle, `saber_first` picks the leg order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SaberMercurialCycle {
+  pub saber: SaberPool,
+  pub mercurial: MercurialPool,
+  pub saber_first: bool,
+  usdc_is_a: bool,
+  usdc_index: usize,
+  ust_index: usize,
+}
+
+impl SaberMercurialCycle {
+  /// fails with `MintMismatch` when either pool does not trade `usdc_mint`
+  /// against `ust_mint`
+  pub fn new(
+    saber: SaberPool,
+    mercurial: MercurialPool,
+    usdc_mint: &Pubkey,
+    ust_mint: &Pubkey,
+    saber_first: bool,
+  ) -> Result<Self, ProgramError> {
+    let usdc_is_a = if saber.mint_a == *usdc_mint && saber.mint_b == *ust_mint {
+      true
+    } else if saber.mint_a == *ust_mint && saber.mint_b == *usdc_mint {
+      false
+    } else {
+      return Err(ArbitrageError::MintMismatch.into());
+    };
+    let (usdc_index, ust_index) = match (mercurial.index_of(usdc_mint), mercurial.index_of(ust_mint)) {
+      (Some(usdc_index), Some(ust_index)) => (usdc_index, ust_index),
+      _ => return Err(ArbitrageError::MintMismatch.into()),
+    };
+    Ok(
+      SaberMercurialCycle {
+        saber: saber,
+        mercurial: mercurial,
+        saber_first: saber_first,
+        usdc_is_a: usdc_is_a,
+        usdc_index: usdc_index,
+        ust_index: ust_index,
+      }
+    )
+  }
+
+  /// ust out of the first leg for `usdc_amount_in`
+  pub fn first_leg(&self, usdc_amount_in: u64) -> Option<u64> {
+    let quote = if self.saber_first {
+      self.saber.quote(usdc_amount_in, self.usdc_is_a)
+    } else {
+      self.mercurial.quote(usdc_amount_in, self.usdc_index, self.ust_index)
+    };
+    quote.map(|ust_quote| ust_quote.amount_out)
+  }
+
+  /// usdc out of the second leg for `ust_amount_in`
+  pub fn second_leg(&self, ust_amount_in: u64) -> Option<u64> {
+    let quote = if self.saber_first {
+      self.mercurial.quote(ust_amount_in, self.ust_index, self.usdc_index)
+    } else {
+      self.saber.quote(ust_amount_in, !self.usdc_is_a)
+    };
+    quote.map(|usdc_quote| usdc_quote.amount_out)
+  }
+
+  /// usdc back from both legs, `None` when a pool cannot fill its leg
+  pub fn quote(&self, usdc_amount_in: u64) -> Option<u64> {
+    self.first_leg(usdc_amount_in).and_then(|ust_amount_in| self.second_leg(ust_amount_in))
+  }
+}
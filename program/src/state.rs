@@ -1,21 +1,107 @@
 use solana_program::{
     program_error::ProgramError,
+    pubkey::Pubkey,
 };
 
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 
-#[derive(Clone, Debug, PartialEq, Copy)]
+use crate::{
+    event::{STRATEGY_ORCA_RAYDIUM_SERUM, STRATEGY_PATH},
+    math::checked_add,
+};
+
+/// account type tag of exchange state accounts
+pub const EXCHANGE_STATE_TAG: u8 = 0xE5;
+/// layout version of `ExchangeState`
+pub const EXCHANGE_STATE_VERSION: u8 = 3;
+/// size of the untagged exchange states written before the versioned layout,
+/// `MigrateExchangeState` copies them into an exchange state PDA
+pub const LEGACY_EXCHANGE_STATE_LEN: usize = 32;
+
+/// seed of the exchange state PDAs, followed by the owner and the strategy id
+pub const EXCHANGE_SEED: &[u8] = b"exchange";
+
+/// strategies that keep an `ExchangeState`: the stable indexes, orca/raydium/serum and paths
+pub fn is_exchange_strategy(strategy_id: u8) -> bool {
+    strategy_id < MAX_STRATEGIES || strategy_id == STRATEGY_ORCA_RAYDIUM_SERUM || strategy_id == STRATEGY_PATH
+}
+
+/// run state of one strategy for one owner, carried between its instructions
+#[derive(Clone, Debug, Default, PartialEq, Copy)]
 pub struct ExchangeState {
+    /// bump of the exchange PDA
+    pub bump_seed: u8,
+    pub strategy_id: u8,
+    /// the only signer the strategy runs this state for
+    pub owner: Pubkey,
     pub input_amount: u64,
     pub exchange_out: u64,
+    /// lifetime amounts, only `ResetExchangeState` clears them
     pub total_profit: u64,
     pub total_lost: u64,
+    /// amounts since the last `begin_run`, the flagged profit check compares these
+    pub run_profit: u64,
+    pub run_lost: u64,
+    /// runs of the strategy, traded or not
+    pub attempts: u64,
+    /// runs that ended with more usdc than they started with
+    pub successes: u64,
+    /// runs the threshold or the quote skipped
+    pub skips: u64,
+    pub last_slot: u64,
 }
 
 impl ExchangeState {
-    const LEN: usize = 32;
+    pub const LEN: usize = 1 + 1 + 1 + 1 + 32 + 8 * 10;
     pub fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() < ExchangeState::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
         let src = array_ref![src, 0, ExchangeState::LEN];
+        let (
+            tag_src,
+            version_src,
+            bump_seed_src,
+            strategy_id_src,
+            owner_src,
+            input_amount_src,
+            exchange_out_src,
+            total_profit_src,
+            total_lost_src,
+            run_profit_src,
+            run_lost_src,
+            attempts_src,
+            successes_src,
+            skips_src,
+            last_slot_src,
+        ) = array_refs![src, 1, 1, 1, 1, 32, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8];
+        if tag_src[0] != EXCHANGE_STATE_TAG || version_src[0] != EXCHANGE_STATE_VERSION {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(
+            ExchangeState{
+                bump_seed: bump_seed_src[0],
+                strategy_id: strategy_id_src[0],
+                owner: Pubkey::new_from_array(*owner_src),
+                input_amount: u64::from_le_bytes(*input_amount_src),
+                exchange_out: u64::from_le_bytes(*exchange_out_src),
+                total_profit: u64::from_le_bytes(*total_profit_src),
+                total_lost: u64::from_le_bytes(*total_lost_src),
+                run_profit: u64::from_le_bytes(*run_profit_src),
+                run_lost: u64::from_le_bytes(*run_lost_src),
+                attempts: u64::from_le_bytes(*attempts_src),
+                successes: u64::from_le_bytes(*successes_src),
+                skips: u64::from_le_bytes(*skips_src),
+                last_slot: u64::from_le_bytes(*last_slot_src),
+            }
+        )
+    }
+    /// amounts of a pre-versioned state, the rest of the state left empty
+    pub fn unpack_legacy(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != LEGACY_EXCHANGE_STATE_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let src = array_ref![src, 0, LEGACY_EXCHANGE_STATE_LEN];
         let (
             input_amount_src,
             exchange_out_src,
@@ -28,26 +114,221 @@ impl ExchangeState {
                 exchange_out: u64::from_le_bytes(*exchange_out_src),
                 total_profit: u64::from_le_bytes(*total_profit_src),
                 total_lost: u64::from_le_bytes(*total_lost_src),
+                ..ExchangeState::default()
             }
         )
     }
     pub fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, ExchangeState::LEN];
         let (
+            tag_dst,
+            version_dst,
+            bump_seed_dst,
+            strategy_id_dst,
+            owner_dst,
             input_amount_dst,
             exchange_out_dst,
             total_profit_dst,
-            total_lost_dst
-        ) = mut_array_refs![dst, 8, 8, 8, 8];
-        let ExchangeState {
-            input_amount,
-            exchange_out,
-            total_profit,
-            total_lost,
-        } = self;
-        *input_amount_dst = input_amount.to_le_bytes();
-        *exchange_out_dst = exchange_out.to_le_bytes();
-        *total_profit_dst = total_profit.to_le_bytes();
-        *total_lost_dst = total_lost.to_le_bytes();
-    }
-}
\ No newline at end of file
+            total_lost_dst,
+            run_profit_dst,
+            run_lost_dst,
+            attempts_dst,
+            successes_dst,
+            skips_dst,
+            last_slot_dst,
+        ) = mut_array_refs![dst, 1, 1, 1, 1, 32, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8];
+        tag_dst[0] = EXCHANGE_STATE_TAG;
+        version_dst[0] = EXCHANGE_STATE_VERSION;
+        bump_seed_dst[0] = self.bump_seed;
+        strategy_id_dst[0] = self.strategy_id;
+        owner_dst.copy_from_slice(self.owner.as_ref());
+        *input_amount_dst = self.input_amount.to_le_bytes();
+        *exchange_out_dst = self.exchange_out.to_le_bytes();
+        *total_profit_dst = self.total_profit.to_le_bytes();
+        *total_lost_dst = self.total_lost.to_le_bytes();
+        *run_profit_dst = self.run_profit.to_le_bytes();
+        *run_lost_dst = self.run_lost.to_le_bytes();
+        *attempts_dst = self.attempts.to_le_bytes();
+        *successes_dst = self.successes.to_le_bytes();
+        *skips_dst = self.skips.to_le_bytes();
+        *last_slot_dst = self.last_slot.to_le_bytes();
+    }
+    /// counts a run of the strategy at `slot`, the counters saturate
+    pub fn record_attempt(&mut self, slot: u64) {
+        self.attempts = self.attempts.saturating_add(1);
+        self.last_slot = slot;
+    }
+    pub fn record_success(&mut self) {
+        self.successes = self.successes.saturating_add(1);
+    }
+    pub fn record_skip(&mut self) {
+        self.skips = self.skips.saturating_add(1);
+    }
+    /// starts a run from `exchange_out`, the run's amounts go back to zero while
+    /// the lifetime totals and counters carry on
+    pub fn begin_run(&mut self, exchange_out: u64) {
+        self.exchange_out = exchange_out;
+        self.run_profit = 0;
+        self.run_lost = 0;
+    }
+    /// adds `profit` to the run and to the lifetime total
+    pub fn record_profit(&mut self, profit: u64) -> Result<(), ProgramError> {
+        self.run_profit = checked_add(self.run_profit, profit)?;
+        self.total_profit = checked_add(self.total_profit, profit)?;
+        Ok(())
+    }
+    /// adds `lost` to the run and to the lifetime total
+    pub fn record_loss(&mut self, lost: u64) -> Result<(), ProgramError> {
+        self.run_lost = checked_add(self.run_lost, lost)?;
+        self.total_lost = checked_add(self.total_lost, lost)?;
+        Ok(())
+    }
+    /// clears the amounts and the counters, the state stays bound to its owner and strategy
+    pub fn reset(&mut self) {
+        *self = ExchangeState {
+            bump_seed: self.bump_seed,
+            strategy_id: self.strategy_id,
+            owner: self.owner,
+            ..ExchangeState::default()
+        };
+    }
+}
+
+/// seed of the config PDA
+pub const CONFIG_SEED: &[u8] = b"config";
+/// maximum number of operators stored in the config
+pub const MAX_OPERATORS: usize = 8;
+
+/// program wide config, lists who may run the strategies
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    pub is_initialized: bool,
+    pub bump_seed: u8,
+    pub admin: Pubkey,
+    pub operators: Vec<Pubkey>,
+}
+
+impl Config {
+    pub const LEN: usize = 1 + 1 + 32 + 1 + 32 * MAX_OPERATORS;
+    pub fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() < Config::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let src = array_ref![src, 0, Config::LEN];
+        let (
+            is_initialized_src,
+            bump_seed_src,
+            admin_src,
+            operators_len_src,
+            operators_src,
+        ) = array_refs![src, 1, 1, 32, 1, 32 * MAX_OPERATORS];
+        let operators_len = operators_len_src[0] as usize;
+        if operators_len > MAX_OPERATORS {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(
+            Config {
+                is_initialized: is_initialized_src[0] != 0,
+                bump_seed: bump_seed_src[0],
+                admin: Pubkey::new_from_array(*admin_src),
+                operators: operators_src
+                    .chunks(32)
+                    .take(operators_len)
+                    .map(Pubkey::new)
+                    .collect(),
+            }
+        )
+    }
+    pub fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, Config::LEN];
+        let (
+            is_initialized_dst,
+            bump_seed_dst,
+            admin_dst,
+            operators_len_dst,
+            operators_dst,
+        ) = mut_array_refs![dst, 1, 1, 32, 1, 32 * MAX_OPERATORS];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        bump_seed_dst[0] = self.bump_seed;
+        admin_dst.copy_from_slice(self.admin.as_ref());
+        operators_len_dst[0] = self.operators.len() as u8;
+        for (i, chunk) in operators_dst.chunks_mut(32).enumerate() {
+            match self.operators.get(i) {
+                Some(operator) => chunk.copy_from_slice(operator.as_ref()),
+                None => chunk.copy_from_slice(&[0; 32]),
+            }
+        }
+    }
+    pub fn is_operator(&self, key: &Pubkey) -> bool {
+        self.admin == *key || self.operators.contains(key)
+    }
+}
+
+/// seed of the strategy params PDAs, followed by the strategy index
+pub const STRATEGY_SEED: &[u8] = b"strategy";
+/// strategy indexes the stable instructions dispatch to
+pub const MAX_STRATEGIES: u8 = 16;
+
+/// sizing of one strategy, the handlers of its index read it on every run
+#[derive(Clone, Debug, PartialEq, Copy)]
+pub struct StrategyParams {
+    pub is_initialized: bool,
+    pub bump_seed: u8,
+    pub index: u8,
+    pub enabled: bool,
+    /// usdc traded at the base size, the adaptive sizing scales it
+    pub input_amount: u64,
+    /// profit of the last run below which the next run skips, at the base size
+    pub threshold: u64,
+    /// profit the base size is expected to make
+    pub expected_profit: u64,
+}
+
+impl StrategyParams {
+    pub const LEN: usize = 1 + 1 + 1 + 1 + 8 + 8 + 8;
+    pub fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() < StrategyParams::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let src = array_ref![src, 0, StrategyParams::LEN];
+        let (
+            is_initialized_src,
+            bump_seed_src,
+            index_src,
+            enabled_src,
+            input_amount_src,
+            threshold_src,
+            expected_profit_src,
+        ) = array_refs![src, 1, 1, 1, 1, 8, 8, 8];
+        Ok(
+            StrategyParams {
+                is_initialized: is_initialized_src[0] != 0,
+                bump_seed: bump_seed_src[0],
+                index: index_src[0],
+                enabled: enabled_src[0] != 0,
+                input_amount: u64::from_le_bytes(*input_amount_src),
+                threshold: u64::from_le_bytes(*threshold_src),
+                expected_profit: u64::from_le_bytes(*expected_profit_src),
+            }
+        )
+    }
+    pub fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, StrategyParams::LEN];
+        let (
+            is_initialized_dst,
+            bump_seed_dst,
+            index_dst,
+            enabled_dst,
+            input_amount_dst,
+            threshold_dst,
+            expected_profit_dst,
+        ) = mut_array_refs![dst, 1, 1, 1, 1, 8, 8, 8];
+        is_initialized_dst[0] = self.is_initialized as u8;
+        bump_seed_dst[0] = self.bump_seed;
+        index_dst[0] = self.index;
+        enabled_dst[0] = self.enabled as u8;
+        *input_amount_dst = self.input_amount.to_le_bytes();
+        *threshold_dst = self.threshold.to_le_bytes();
+        *expected_profit_dst = self.expected_profit.to_le_bytes();
+    }
+}
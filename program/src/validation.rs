@@ -0,0 +1,116 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar,
+};
+
+use spl_token::state::Account as TokenAccount;
+
+use crate::error::ArbitrageError;
+
+/// orca token swap v2
+pub mod orca {
+  solana_program::declare_id!("9W959DqEETiGZocYWCQPaJ6sBmUzgfxXfqGeTEdp3aQP");
+}
+
+pub mod saber {
+  solana_program::declare_id!("SSwpkEEcbUqx4vtoEByFjSkhKdCT862DNVb52nZg1UZ");
+}
+
+pub mod mercurial {
+  solana_program::declare_id!("MERLuDFBMmsHnsBPZw2sDQZHvXFMwp8EdjudcU2HKky");
+}
+
+/// raydium amm v4
+pub mod raydium {
+  solana_program::declare_id!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+}
+
+/// serum dex v3
+pub mod serum {
+  solana_program::declare_id!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin");
+}
+
+/// orca whirlpools
+pub mod whirlpool {
+  solana_program::declare_id!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+}
+
+pub mod crema {
+  solana_program::declare_id!("6MLxLqiXaaSUpkgMnWDTuejNZEz3kE7k2woyHGVFw319");
+}
+
+/// `program_acc` is the program `program_id` names
+pub fn program(program_acc: &AccountInfo, program_id: &Pubkey) -> ProgramResult {
+  if program_acc.key != program_id {
+    return Err(ArbitrageError::InvalidProgramId.into());
+  }
+  Ok(())
+}
+
+pub fn token_program(program_acc: &AccountInfo) -> ProgramResult {
+  program(program_acc, &spl_token::id())
+}
+
+pub fn clock(sysvar_acc: &AccountInfo) -> ProgramResult {
+  if !sysvar::clock::check_id(sysvar_acc.key) {
+    return Err(ArbitrageError::InvalidSysvar.into());
+  }
+  Ok(())
+}
+
+pub fn rent(sysvar_acc: &AccountInfo) -> ProgramResult {
+  if !sysvar::rent::check_id(sysvar_acc.key) {
+    return Err(ArbitrageError::InvalidSysvar.into());
+  }
+  Ok(())
+}
+
+pub fn signer(signer_acc: &AccountInfo) -> ProgramResult {
+  if !signer_acc.is_signer {
+    return Err(ProgramError::MissingRequiredSignature);
+  }
+  Ok(())
+}
+
+/// account the program keeps its state in and the instruction writes to
+pub fn program_state(state_acc: &AccountInfo, program_id: &Pubkey) -> ProgramResult {
+  if state_acc.owner != program_id {
+    return Err(ArbitrageError::InvalidOwner.into());
+  }
+  if !state_acc.is_writable {
+    return Err(ArbitrageError::AccountNotWritable.into());
+  }
+  Ok(())
+}
+
+/// spl-token account held by `owner_acc`
+pub fn user_token_account(token_acc: &AccountInfo, owner_acc: &AccountInfo) -> Result<TokenAccount, ProgramError> {
+  let token = token_account(token_acc)?;
+  if token.owner != *owner_acc.key {
+    return Err(ArbitrageError::TokenOwnerMismatch.into());
+  }
+  Ok(token)
+}
+
+/// every mint of `mints` is held by one of the pool's `vault_accs`
+pub fn vault_mints(mints: &[Pubkey], vault_accs: &[&AccountInfo]) -> ProgramResult {
+  let mut vault_mints = Vec::with_capacity(vault_accs.len());
+  for vault_acc in vault_accs.iter() {
+    vault_mints.push(token_account(vault_acc)?.mint);
+  }
+  if mints.iter().any(|mint| !vault_mints.contains(mint)) {
+    return Err(ArbitrageError::MintMismatch.into());
+  }
+  Ok(())
+}
+
+fn token_account(token_acc: &AccountInfo) -> Result<TokenAccount, ProgramError> {
+  if *token_acc.owner != spl_token::id() {
+    return Err(ArbitrageError::InvalidTokenAccount.into());
+  }
+  TokenAccount::unpack(&token_acc.try_borrow_data()?).map_err(|_| ArbitrageError::InvalidTokenAccount.into())
+}
@@ -0,0 +1,502 @@
+use solana_program::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use arrayref::{array_ref, array_refs};
+
+use std::convert::TryFrom;
+
+use crate::{
+  error::ArbitrageError,
+  math::{to_u128, U256},
+};
+
+pub const MIN_TICK_INDEX: i32 = -443636;
+pub const MAX_TICK_INDEX: i32 = 443636;
+/// Q64.64 sqrt prices of `MIN_TICK_INDEX` and `MAX_TICK_INDEX`, the widest swap limits
+pub const MIN_SQRT_PRICE: u128 = 4295048016;
+pub const MAX_SQRT_PRICE: u128 = 79226673515401279992447579055;
+pub const TICK_ARRAY_SIZE: i32 = 88;
+/// whirlpool fee rates are in hundredths of a basis point
+const FEE_RATE_DENOMINATOR: u128 = 1_000_000;
+
+/// anchor discriminators, the first 8 bytes of sha256("account:<name>")
+const WHIRLPOOL_DISCRIMINATOR: [u8; 8] = [63, 149, 209, 12, 225, 128, 99, 9];
+const TICK_ARRAY_DISCRIMINATOR: [u8; 8] = [69, 97, 189, 190, 110, 7, 66, 187];
+/// `Whirlpool` up to and including `fee_growth_global_b`, the reward infos are not read
+const WHIRLPOOL_LEN: usize = 261;
+/// discriminator, start_tick_index, 88 ticks, whirlpool
+const TICK_ARRAY_LEN: usize = 9988;
+/// initialized, liquidity_net, liquidity_gross, fee and reward growths
+const TICK_LEN: usize = 113;
+
+/// sqrt(1.0001)^(2^k) in Q32.96 for the bits of a positive tick
+const POSITIVE_TICK_FACTORS: [u128; 18] = [
+  79236085330515764027303304731,
+  79244008939048815603706035061,
+  79259858533276714757314932305,
+  79291567232598584799939703904,
+  79355022692464371645785046466,
+  79482085999252804386437311141,
+  79736823300114093921829183326,
+  80248749790819932309965073892,
+  81282483887344747381513967011,
+  83390072131320151908154831281,
+  87770609709833776024991924138,
+  97234110755111693312479820773,
+  119332217159966728226237229890,
+  179736315981702064433883588727,
+  407748233172238350107850275304,
+  2098478828474011932436660412517,
+  55581415166113811149459800483533,
+  38992368544603139932233054999993551,
+];
+/// sqrt(1.0001)^(-2^k) in Q64.64 for the bits of a negative tick
+const NEGATIVE_TICK_FACTORS: [u128; 18] = [
+  18444899583751176498,
+  18443055278223354162,
+  18439367220385604838,
+  18431993317065449817,
+  18417254355718160513,
+  18387811781193591352,
+  18329067761203520168,
+  18212142134806087854,
+  17980523815641551639,
+  17526086738831147013,
+  16651378430235024244,
+  15030750278693429944,
+  12247334978882834399,
+  8131365268884726200,
+  3584323654723342297,
+  696457651847595233,
+  26294789957452057,
+  37481735321082,
+];
+
+/// Q64.64 sqrt price of `tick`, whirlpool's bit by bit product of precomputed factors
+pub fn sqrt_price_from_tick_index(tick: i32) -> Option<u128> {
+  if tick < MIN_TICK_INDEX || tick > MAX_TICK_INDEX {
+    return None;
+  }
+  let abs_tick = tick.unsigned_abs();
+  if tick >= 0 {
+    let mut ratio = U256::from(if abs_tick & 1 != 0 { 79232123823359799118286999567u128 } else { 1u128 << 96 });
+    for (bit, factor) in POSITIVE_TICK_FACTORS.iter().enumerate() {
+      if abs_tick & (2 << bit) != 0 {
+        ratio = (ratio * U256::from(*factor)) >> 96;
+      }
+    }
+    to_u128(ratio >> 32)
+  } else {
+    let mut ratio = if abs_tick & 1 != 0 { 18445821805675392311u128 } else { 1u128 << 64 };
+    for (bit, factor) in NEGATIVE_TICK_FACTORS.iter().enumerate() {
+      if abs_tick & (2 << bit) != 0 {
+        ratio = ratio.checked_mul(*factor)? >> 64;
+      }
+    }
+    Some(ratio)
+  }
+}
+
+/// highest tick whose sqrt price is at or below `sqrt_price`
+pub fn tick_index_from_sqrt_price(sqrt_price: u128) -> Option<i32> {
+  if sqrt_price < MIN_SQRT_PRICE || sqrt_price > MAX_SQRT_PRICE {
+    return None;
+  }
+  let (mut low, mut high) = (MIN_TICK_INDEX, MAX_TICK_INDEX);
+  while low < high {
+    let middle = low + (high - low + 1) / 2;
+    if sqrt_price_from_tick_index(middle)? <= sqrt_price {
+      low = middle;
+    } else {
+      high = middle - 1;
+    }
+  }
+  Some(low)
+}
+
+fn price_order(sqrt_price_0: u128, sqrt_price_1: u128) -> (u128, u128) {
+  if sqrt_price_0 > sqrt_price_1 { (sqrt_price_1, sqrt_price_0) } else { (sqrt_price_0, sqrt_price_1) }
+}
+
+/// token a between two sqrt prices at `liquidity`: L * (upper - lower) / (upper * lower)
+pub fn get_amount_delta_a(sqrt_price_0: u128, sqrt_price_1: u128, liquidity: u128, round_up: bool) -> Option<u64> {
+  let (lower, upper) = price_order(sqrt_price_0, sqrt_price_1);
+  let product = U256::from(liquidity).checked_mul(U256::from(upper - lower))?;
+  if product.bits() > 192 {
+    return None;
+  }
+  let numerator = product << 64;
+  let denominator = U256::from(upper).checked_mul(U256::from(lower))?;
+  let (mut quotient, remainder) = numerator.div_mod(denominator);
+  if round_up && !remainder.is_zero() {
+    quotient = quotient + U256::one();
+  }
+  u64::try_from(to_u128(quotient)?).ok()
+}
+
+/// token b between two sqrt prices at `liquidity`: L * (upper - lower)
+pub fn get_amount_delta_b(sqrt_price_0: u128, sqrt_price_1: u128, liquidity: u128, round_up: bool) -> Option<u64> {
+  let (lower, upper) = price_order(sqrt_price_0, sqrt_price_1);
+  let product = U256::from(liquidity).checked_mul(U256::from(upper - lower))?;
+  let mut amount = product >> 64;
+  if round_up && !(product & U256::from(u64::MAX)).is_zero() {
+    amount = amount + U256::one();
+  }
+  u64::try_from(to_u128(amount)?).ok()
+}
+
+/// sqrt price after `amount` input, rounded against the trader
+fn get_next_sqrt_price(sqrt_price: u128, liquidity: u128, amount: u64, a_to_b: bool) -> Option<u128> {
+  if amount == 0 {
+    return Some(sqrt_price);
+  }
+  let next_sqrt_price = if a_to_b {
+    // L * p / (L + amount * p), rounded up
+    let product = U256::from(sqrt_price).checked_mul(U256::from(amount))?;
+    let numerator = U256::from(liquidity).checked_mul(U256::from(sqrt_price))?;
+    if numerator.bits() > 192 {
+      return None;
+    }
+    let denominator = (U256::from(liquidity) << 64).checked_add(product)?;
+    let (quotient, remainder) = (numerator << 64).div_mod(denominator);
+    to_u128(if remainder.is_zero() { quotient } else { quotient + U256::one() })?
+  } else {
+    // p + amount / L, rounded down
+    sqrt_price.checked_add(((amount as u128) << 64).checked_div(liquidity)?)?
+  };
+  if next_sqrt_price < MIN_SQRT_PRICE || next_sqrt_price > MAX_SQRT_PRICE {
+    return None;
+  }
+  Some(next_sqrt_price)
+}
+
+/// one exact input step of a swap within a single liquidity range
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SwapStep {
+  pub amount_in: u64,
+  pub amount_out: u64,
+  pub fee: u64,
+  pub next_sqrt_price: u128,
+}
+
+/// Whirlpool's `compute_swap` for an exact input: moves the price towards
+/// `sqrt_price_target` with what is left of the input after the fee.
+pub fn compute_swap_step(
+  amount_remaining: u64,
+  fee_rate: u16,
+  liquidity: u128,
+  sqrt_price: u128,
+  sqrt_price_target: u128,
+  a_to_b: bool,
+) -> Option<SwapStep> {
+  let fee_rate = fee_rate as u128;
+  let amount_less_fee = u64::try_from(
+    (amount_remaining as u128).checked_mul(FEE_RATE_DENOMINATOR.checked_sub(fee_rate)?)? / FEE_RATE_DENOMINATOR
+  ).ok()?;
+  let amount_in_to_target = if a_to_b {
+    get_amount_delta_a(sqrt_price, sqrt_price_target, liquidity, true)
+  } else {
+    get_amount_delta_b(sqrt_price, sqrt_price_target, liquidity, true)
+  };
+  // an input to the target that does not fit a u64 is never reached
+  let next_sqrt_price = match amount_in_to_target {
+    Some(amount_in_to_target) if amount_less_fee >= amount_in_to_target => sqrt_price_target,
+    _ => get_next_sqrt_price(sqrt_price, liquidity, amount_less_fee, a_to_b)?,
+  };
+  let is_max_swap = next_sqrt_price == sqrt_price_target;
+
+  let (amount_in, amount_out) = if a_to_b {
+    (
+      get_amount_delta_a(sqrt_price, next_sqrt_price, liquidity, true)?,
+      get_amount_delta_b(sqrt_price, next_sqrt_price, liquidity, false)?,
+    )
+  } else {
+    (
+      get_amount_delta_b(sqrt_price, next_sqrt_price, liquidity, true)?,
+      get_amount_delta_a(sqrt_price, next_sqrt_price, liquidity, false)?,
+    )
+  };
+  let fee = if is_max_swap {
+    // fee on the part of the input that reached the target, rounded up
+    let numerator = (amount_in as u128).checked_mul(fee_rate)?;
+    let denominator = FEE_RATE_DENOMINATOR - fee_rate;
+    u64::try_from((numerator + denominator - 1) / denominator).ok()?
+  } else {
+    amount_remaining.checked_sub(amount_in)?
+  };
+
+  Some(
+    SwapStep {
+      amount_in: amount_in,
+      amount_out: amount_out,
+      fee: fee,
+      next_sqrt_price: next_sqrt_price,
+    }
+  )
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Tick {
+  pub initialized: bool,
+  pub liquidity_net: i128,
+}
+
+/// the ticks of one tick array account, spaced by the pool's tick spacing
+#[derive(Clone, Debug, PartialEq)]
+pub struct TickArray {
+  pub key: Pubkey,
+  pub start_tick_index: i32,
+  pub ticks: Vec<Tick>,
+}
+
+impl TickArray {
+  /// `whirlpool` is the pool the array must belong to
+  pub fn unpack(key: &Pubkey, data: &[u8], whirlpool: &Pubkey) -> Result<Self, ProgramError> {
+    if data.len() < TICK_ARRAY_LEN {
+      return Err(ArbitrageError::InvalidPoolState.into());
+    }
+    let data = array_ref![data, 0, TICK_ARRAY_LEN];
+    let (discriminator, start_tick_index, ticks, owner_pool) = array_refs![data, 8, 4, 9944, 32];
+    if discriminator != &TICK_ARRAY_DISCRIMINATOR || owner_pool != &whirlpool.to_bytes() {
+      return Err(ArbitrageError::InvalidPoolState.into());
+    }
+    let ticks = ticks
+      .chunks(TICK_LEN)
+      .map(|tick| Tick {
+        initialized: tick[0] != 0,
+        liquidity_net: i128::from_le_bytes(*array_ref![tick, 1, 16]),
+      })
+      .collect();
+    Ok(
+      TickArray {
+        key: *key,
+        start_tick_index: i32::from_le_bytes(*start_tick_index),
+        ticks: ticks,
+      }
+    )
+  }
+
+  /// Whirlpool's search range, shifted down by one spacing for b to a searches
+  fn in_search_range(&self, tick: i32, tick_spacing: i32, a_to_b: bool) -> bool {
+    let shift = if a_to_b { 0 } else { tick_spacing };
+    let lower = self.start_tick_index - shift;
+    let upper = self.start_tick_index + TICK_ARRAY_SIZE * tick_spacing - shift;
+    tick >= lower && tick < upper
+  }
+
+  /// Next initialized tick from `tick` within this array, inclusive of the
+  /// current offset when moving left. `None` when `tick` is out of range.
+  fn next_initialized_tick(&self, tick: i32, tick_spacing: i32, a_to_b: bool) -> Option<Option<(i32, Tick)>> {
+    if !self.in_search_range(tick, tick_spacing, a_to_b) {
+      return None;
+    }
+    let mut offset = (tick - self.start_tick_index).div_euclid(tick_spacing);
+    if !a_to_b {
+      offset += 1;
+    }
+    while offset >= 0 && offset < TICK_ARRAY_SIZE {
+      let array_tick = self.ticks[offset as usize];
+      if array_tick.initialized {
+        return Some(Some((self.start_tick_index + offset * tick_spacing, array_tick)));
+      }
+      offset += if a_to_b { -1 } else { 1 };
+    }
+    Some(None)
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WhirlQuote {
+  /// below the requested input when the swap stops at the price limit
+  pub amount_in: u64,
+  pub amount_out: u64,
+  /// part of `amount_in`
+  pub fee: u64,
+  pub end_sqrt_price: u128,
+  pub end_tick_index: i32,
+  /// the tick arrays the swap moves through, in the order the instruction takes them
+  pub tick_arrays: Vec<Pubkey>,
+}
+
+/// A whirlpool and the tick arrays passed with it, in swap order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Whirlpool {
+  pub tick_spacing: u16,
+  pub fee_rate: u16,
+  pub liquidity: u128,
+  pub sqrt_price: u128,
+  pub tick_current_index: i32,
+  pub token_mint_a: Pubkey,
+  pub token_vault_a: Pubkey,
+  pub token_mint_b: Pubkey,
+  pub token_vault_b: Pubkey,
+  pub tick_arrays: Vec<TickArray>,
+}
+
+impl Whirlpool {
+  pub fn load(program_id: &Pubkey, market_acc: &AccountInfo, tick_array_accs: &[&AccountInfo]) -> Result<Self, ProgramError> {
+    if market_acc.owner != program_id {
+      return Err(ArbitrageError::InvalidOwner.into());
+    }
+    let mut tick_arrays = Vec::with_capacity(tick_array_accs.len());
+    for tick_array_acc in tick_array_accs {
+      if tick_array_acc.owner != program_id {
+        return Err(ArbitrageError::InvalidOwner.into());
+      }
+      tick_arrays.push(TickArray::unpack(tick_array_acc.key, &tick_array_acc.try_borrow_data()?, market_acc.key)?);
+    }
+    Self::unpack(&market_acc.try_borrow_data()?, tick_arrays)
+  }
+
+  pub fn unpack(data: &[u8], tick_arrays: Vec<TickArray>) -> Result<Self, ProgramError> {
+    if data.len() < WHIRLPOOL_LEN {
+      return Err(ArbitrageError::InvalidPoolState.into());
+    }
+    let data = array_ref![data, 0, WHIRLPOOL_LEN];
+    let (
+      discriminator,
+      _whirlpools_config,
+      _whirlpool_bump,
+      tick_spacing,
+      _tick_spacing_seed,
+      fee_rate,
+      _protocol_fee_rate,
+      liquidity,
+      sqrt_price,
+      tick_current_index,
+      _protocol_fee_owed_a,
+      _protocol_fee_owed_b,
+      token_mint_a,
+      token_vault_a,
+      _fee_growth_global_a,
+      token_mint_b,
+      token_vault_b,
+      _fee_growth_global_b,
+    ) = array_refs![data, 8, 32, 1, 2, 2, 2, 2, 16, 16, 4, 8, 8, 32, 32, 16, 32, 32, 16];
+    let tick_spacing = u16::from_le_bytes(*tick_spacing);
+    if discriminator != &WHIRLPOOL_DISCRIMINATOR || tick_spacing == 0 {
+      return Err(ArbitrageError::InvalidPoolState.into());
+    }
+    Ok(
+      Whirlpool {
+        tick_spacing: tick_spacing,
+        fee_rate: u16::from_le_bytes(*fee_rate),
+        liquidity: u128::from_le_bytes(*liquidity),
+        sqrt_price: u128::from_le_bytes(*sqrt_price),
+        tick_current_index: i32::from_le_bytes(*tick_current_index),
+        token_mint_a: Pubkey::new_from_array(*token_mint_a),
+        token_vault_a: Pubkey::new_from_array(*token_vault_a),
+        token_mint_b: Pubkey::new_from_array(*token_mint_b),
+        token_vault_b: Pubkey::new_from_array(*token_vault_b),
+        tick_arrays: tick_arrays,
+      }
+    )
+  }
+
+  /// Next initialized tick at or after `array_index`, whirlpool's tick array
+  /// sequence search. Without one the swap stops at the edge of the last array.
+  fn next_initialized_tick(&self, array_index: usize, tick: i32, a_to_b: bool) -> Option<(usize, i32, Tick)> {
+    let tick_spacing = self.tick_spacing as i32;
+    let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing;
+    let mut search_tick = tick;
+    let mut array_index = array_index;
+    loop {
+      let tick_array = self.tick_arrays.get(array_index)?;
+      if let Some((next_tick, array_tick)) = tick_array.next_initialized_tick(search_tick, tick_spacing, a_to_b)? {
+        return Some((array_index, next_tick, array_tick));
+      }
+      let edge = if a_to_b {
+        if tick_array.start_tick_index <= MIN_TICK_INDEX {
+          return Some((array_index, MIN_TICK_INDEX, Tick::default()));
+        }
+        tick_array.start_tick_index
+      } else {
+        if tick_array.start_tick_index + ticks_in_array > MAX_TICK_INDEX {
+          return Some((array_index, MAX_TICK_INDEX, Tick::default()));
+        }
+        tick_array.start_tick_index + ticks_in_array - 1
+      };
+      if array_index + 1 == self.tick_arrays.len() {
+        return Some((array_index, edge, Tick::default()));
+      }
+      search_tick = if a_to_b { edge - 1 } else { edge };
+      array_index += 1;
+    }
+  }
+
+  /// Simulates an exact input swap across the initialized ticks of the loaded
+  /// tick arrays. `None` when the swap would need more tick arrays or fails as
+  /// the whirlpool program would fail it.
+  pub fn quote(&self, amount_in: u64, a_to_b: bool, sqrt_price_limit: u128) -> Option<WhirlQuote> {
+    if sqrt_price_limit < MIN_SQRT_PRICE || sqrt_price_limit > MAX_SQRT_PRICE {
+      return None;
+    }
+    if (a_to_b && sqrt_price_limit >= self.sqrt_price) || (!a_to_b && sqrt_price_limit <= self.sqrt_price) {
+      return None;
+    }
+    if self.tick_arrays.is_empty() {
+      return None;
+    }
+    let tick_spacing = self.tick_spacing as i32;
+
+    let mut amount_remaining = amount_in;
+    let mut amount_out: u64 = 0;
+    let mut fee: u64 = 0;
+    let mut sqrt_price = self.sqrt_price;
+    let mut tick = self.tick_current_index;
+    let mut liquidity = self.liquidity;
+    let mut array_index = 0;
+    let mut last_array_index = 0;
+    while amount_remaining > 0 && sqrt_price != sqrt_price_limit {
+      let (next_array_index, next_tick, next_tick_state) = self.next_initialized_tick(array_index, tick, a_to_b)?;
+      last_array_index = next_array_index;
+      let next_tick_sqrt_price = sqrt_price_from_tick_index(next_tick)?;
+      let sqrt_price_target = if a_to_b {
+        next_tick_sqrt_price.max(sqrt_price_limit)
+      } else {
+        next_tick_sqrt_price.min(sqrt_price_limit)
+      };
+
+      let step = compute_swap_step(amount_remaining, self.fee_rate, liquidity, sqrt_price, sqrt_price_target, a_to_b)?;
+      amount_remaining = amount_remaining.checked_sub(step.amount_in.checked_add(step.fee)?)?;
+      amount_out = amount_out.checked_add(step.amount_out)?;
+      fee = fee.checked_add(step.fee)?;
+
+      if step.next_sqrt_price == next_tick_sqrt_price {
+        if next_tick_state.initialized {
+          // liquidity_net is added moving right and taken away moving left
+          let liquidity_net = if a_to_b { next_tick_state.liquidity_net.checked_neg()? } else { next_tick_state.liquidity_net };
+          liquidity = if liquidity_net < 0 {
+            liquidity.checked_sub(liquidity_net.unsigned_abs())?
+          } else {
+            liquidity.checked_add(liquidity_net as u128)?
+          };
+        }
+        let offset = (next_tick - self.tick_arrays[next_array_index].start_tick_index).div_euclid(tick_spacing);
+        array_index = if (a_to_b && offset == 0) || (!a_to_b && offset == TICK_ARRAY_SIZE - 1) {
+          next_array_index + 1
+        } else {
+          next_array_index
+        };
+        tick = if a_to_b { next_tick - 1 } else { next_tick };
+      } else if step.next_sqrt_price != sqrt_price {
+        tick = tick_index_from_sqrt_price(step.next_sqrt_price)?;
+      }
+      sqrt_price = step.next_sqrt_price;
+    }
+
+    Some(
+      WhirlQuote {
+        amount_in: amount_in - amount_remaining,
+        amount_out: amount_out,
+        fee: fee,
+        end_sqrt_price: sqrt_price,
+        end_tick_index: tick,
+        tick_arrays: self.tick_arrays[..=last_array_index].iter().map(|tick_array| tick_array.key).collect(),
+      }
+    )
+  }
+}
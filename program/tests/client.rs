@@ -0,0 +1,477 @@
+//! Runs every client builder through `Processor::process` to check that its
+//! accounts line up with the processor's `next_account_info` order.
+//!
+//! The config lists no operator, so a builder whose accounts parse completely,
+//! with the config first and the signer where the strategy expects its owner,
+//! stops at `UnauthorizedOperator`.
+#![cfg(feature = "no-entrypoint")]
+
+use solana_arbitrage::{
+    client::*,
+    error::ArbitrageError,
+    instruction::{ArbitrageInstruction, Market, INSTRUCTION_VERSION},
+    processor::Processor,
+    state::Config,
+};
+use solana_program::{
+    account_info::AccountInfo,
+    instruction::Instruction,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+fn run(program_id: &Pubkey, instruction: &Instruction) -> Result<(), ProgramError> {
+    let (config_key, bump_seed) = config_address(program_id);
+    let config = Config {
+        is_initialized: true,
+        bump_seed: bump_seed,
+        admin: Pubkey::new_unique(),
+        operators: vec![],
+    };
+    let mut config_data = vec![0; Config::LEN];
+    config.pack_into_slice(&mut config_data);
+
+    let foreign_owner = Pubkey::new_unique();
+    let mut lamports = vec![0u64; instruction.accounts.len()];
+    let mut data: Vec<Vec<u8>> = instruction
+        .accounts
+        .iter()
+        .map(|meta| if meta.pubkey == config_key { config_data.clone() } else { vec![] })
+        .collect();
+    let owners: Vec<Pubkey> = instruction
+        .accounts
+        .iter()
+        .map(|meta| if meta.pubkey == config_key { *program_id } else { foreign_owner })
+        .collect();
+    let accounts: Vec<AccountInfo> = instruction
+        .accounts
+        .iter()
+        .zip(lamports.iter_mut())
+        .zip(data.iter_mut())
+        .zip(owners.iter())
+        .map(|(((meta, lamports), data), owner)| {
+            AccountInfo::new(&meta.pubkey, meta.is_signer, meta.is_writable, lamports, data, owner, false, 0)
+        })
+        .collect();
+    Processor::process(program_id, &accounts, &instruction.data)
+}
+
+fn assert_account_order(program_id: &Pubkey, mut instruction: Instruction) {
+    assert_eq!(instruction.accounts[0].pubkey, config_address(program_id).0);
+    assert_eq!(
+        run(program_id, &instruction),
+        Err(ArbitrageError::UnauthorizedOperator.into())
+    );
+    instruction.accounts.pop();
+    assert_eq!(run(program_id, &instruction), Err(ProgramError::NotEnoughAccountKeys));
+}
+
+fn orca() -> OrcaAccounts {
+    OrcaAccounts {
+        program: Pubkey::new_unique(),
+        market: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        swap_a: Pubkey::new_unique(),
+        swap_b: Pubkey::new_unique(),
+        pool_mint: Pubkey::new_unique(),
+        fee: Pubkey::new_unique(),
+    }
+}
+
+fn saber() -> SaberAccounts {
+    SaberAccounts {
+        program: Pubkey::new_unique(),
+        market: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        swap_a: Pubkey::new_unique(),
+        swap_b: Pubkey::new_unique(),
+        admin_fee: Pubkey::new_unique(),
+    }
+}
+
+fn serum() -> SerumAccounts {
+    SerumAccounts {
+        program: Pubkey::new_unique(),
+        market: Pubkey::new_unique(),
+        open_orders: Pubkey::new_unique(),
+        request_queue: Pubkey::new_unique(),
+        event_queue: Pubkey::new_unique(),
+        bids: Pubkey::new_unique(),
+        asks: Pubkey::new_unique(),
+        base_vault: Pubkey::new_unique(),
+        quote_vault: Pubkey::new_unique(),
+        vault_signer: Pubkey::new_unique(),
+    }
+}
+
+fn raydium() -> RaydiumAccounts {
+    RaydiumAccounts {
+        program: Pubkey::new_unique(),
+        market: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        open_orders: Pubkey::new_unique(),
+        target_orders: Pubkey::new_unique(),
+        coin_vault: Pubkey::new_unique(),
+        pc_vault: Pubkey::new_unique(),
+        serum_program: Pubkey::new_unique(),
+        serum_market: Pubkey::new_unique(),
+        serum_request_queue: Pubkey::new_unique(),
+        serum_event_queue: Pubkey::new_unique(),
+        serum_bids: Pubkey::new_unique(),
+        serum_asks: Pubkey::new_unique(),
+        serum_base_vault: Pubkey::new_unique(),
+        serum_quote_vault: Pubkey::new_unique(),
+        serum_vault_signer: Pubkey::new_unique(),
+    }
+}
+
+fn mercurial(n: usize) -> MercurialAccounts {
+    MercurialAccounts {
+        program: Pubkey::new_unique(),
+        market: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        swap_accounts: (0..n).map(|_| Pubkey::new_unique()).collect(),
+    }
+}
+
+fn whirl() -> WhirlAccounts {
+    WhirlAccounts {
+        program: Pubkey::new_unique(),
+        market: Pubkey::new_unique(),
+        vault_a: Pubkey::new_unique(),
+        vault_b: Pubkey::new_unique(),
+        tick_array: Pubkey::new_unique(),
+        oracle: Pubkey::new_unique(),
+    }
+}
+
+fn crema() -> CremaAccounts {
+    CremaAccounts {
+        program: Pubkey::new_unique(),
+        market: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        swap_a: Pubkey::new_unique(),
+        swap_b: Pubkey::new_unique(),
+        ticks: Pubkey::new_unique(),
+    }
+}
+
+fn user() -> StableUserAccounts {
+    StableUserAccounts {
+        owner: Pubkey::new_unique(),
+        user_usdc: Pubkey::new_unique(),
+        user_ust: Pubkey::new_unique(),
+    }
+}
+
+#[test]
+fn orca_raydium_serum_order() {
+    let program_id = Pubkey::new_unique();
+    let builder = OrcaRaydiumSerumExchange {
+        exchange: Pubkey::new_unique(),
+        raydium: raydium(),
+        orca: orca(),
+        owner: Pubkey::new_unique(),
+        user_usdc: Pubkey::new_unique(),
+        user_other: Pubkey::new_unique(),
+        other_mint: Pubkey::new_unique(),
+        flag: 0,
+        begin_run: false,
+        min_amounts_out: [0, 0],
+    };
+    assert_account_order(&program_id, builder.instruction(&program_id));
+}
+
+#[test]
+fn serum_select_order() {
+    let program_id = Pubkey::new_unique();
+    for v2 in [false, true] {
+        let builder = SerumSelectExchange {
+            serum: serum(),
+            saber: saber(),
+            saber_admin_fee_b: Pubkey::new_unique(),
+            orca: orca(),
+            owner: Pubkey::new_unique(),
+            user_usdc: Pubkey::new_unique(),
+            user_usdt: Pubkey::new_unique(),
+            user_sol: Pubkey::new_unique(),
+            sol_mint: Pubkey::new_unique(),
+            v2: v2,
+            bid_min_amounts_out: [0; 3],
+            ask_min_amounts_out: [0; 3],
+        };
+        assert_account_order(&program_id, builder.instruction(&program_id));
+    }
+}
+
+#[test]
+fn serum_whirl_order() {
+    let program_id = Pubkey::new_unique();
+    for reversed in [false, true] {
+        let builder = SerumWhirlExchange {
+            serum: serum(),
+            saber: saber(),
+            saber_admin_fee_b: Pubkey::new_unique(),
+            whirl: whirl(),
+            owner: Pubkey::new_unique(),
+            user_usdc: Pubkey::new_unique(),
+            user_stsol: Pubkey::new_unique(),
+            user_sol: Pubkey::new_unique(),
+            sol_mint: Pubkey::new_unique(),
+            reversed: reversed,
+            min_amounts_out: [0; 3],
+        };
+        assert_account_order(&program_id, builder.instruction(&program_id));
+    }
+}
+
+#[test]
+fn saber_mercurial_order() {
+    let program_id = Pubkey::new_unique();
+    for (n, slot) in [(3, 0), (3, 1), (2, 2), (2, 3), (4, 4), (4, 5)] {
+        for reversed in [false, true] {
+            let builder = SaberMercurialExchange {
+                exchange: Pubkey::new_unique(),
+                saber: saber(),
+                mercurial: mercurial(n),
+                user: user(),
+                slot: slot,
+                reversed: reversed,
+                flag: 0,
+                begin_run: true,
+                max_amount_in: 7,
+                min_amounts_out: [8, 9],
+            };
+            let instruction = builder.instruction(&program_id).unwrap();
+            let mut data = vec![INSTRUCTION_VERSION, 6 + 2 * slot + reversed as u8, 0, 1];
+            for amount in [7u64, 8, 9] {
+                data.extend_from_slice(&amount.to_le_bytes());
+            }
+            assert_eq!(instruction.data, data);
+            assert_account_order(&program_id, instruction);
+        }
+    }
+}
+
+#[test]
+fn saber_mercurial_rejects_slot_of_other_pool_size() {
+    let program_id = Pubkey::new_unique();
+    let builder = SaberMercurialExchange {
+        exchange: Pubkey::new_unique(),
+        saber: saber(),
+        mercurial: mercurial(2),
+        user: user(),
+        slot: 0,
+        reversed: false,
+        flag: 0,
+        begin_run: false,
+        max_amount_in: 0,
+        min_amounts_out: [0, 0],
+    };
+    assert_eq!(
+        builder.instruction(&program_id),
+        Err(ArbitrageError::InvalidInstruction.into())
+    );
+}
+
+#[test]
+fn saber_whirl_order() {
+    let program_id = Pubkey::new_unique();
+    for slot in [6, 7] {
+        for reversed in [false, true] {
+            let builder = SaberWhirlExchange {
+                exchange: Pubkey::new_unique(),
+                saber: saber(),
+                whirl: whirl(),
+                user: user(),
+                slot: slot,
+                reversed: reversed,
+                flag: 0,
+                begin_run: false,
+                min_amounts_out: [0, 0],
+            };
+            assert_account_order(&program_id, builder.instruction(&program_id).unwrap());
+        }
+    }
+}
+
+#[test]
+fn saber_crema_order() {
+    let program_id = Pubkey::new_unique();
+    for slot in [8, 9] {
+        for reversed in [false, true] {
+            let builder = SaberCremaExchange {
+                exchange: Pubkey::new_unique(),
+                saber: saber(),
+                crema: crema(),
+                user: user(),
+                slot: slot,
+                reversed: reversed,
+                flag: 0,
+                begin_run: false,
+                min_amounts_out: [0, 0],
+            };
+            assert_account_order(&program_id, builder.instruction(&program_id).unwrap());
+        }
+    }
+}
+
+#[test]
+fn orca_serum_order() {
+    let program_id = Pubkey::new_unique();
+    for slot in [10, 11] {
+        for reversed in [false, true] {
+            let builder = OrcaSerumExchange {
+                exchange: Pubkey::new_unique(),
+                orca: orca(),
+                serum: serum(),
+                user: user(),
+                slot: slot,
+                reversed: reversed,
+                flag: 0,
+                begin_run: false,
+                min_amounts_out: [0, 0],
+            };
+            assert_account_order(&program_id, builder.instruction(&program_id).unwrap());
+        }
+    }
+}
+
+#[test]
+fn orca_raydium_order() {
+    let program_id = Pubkey::new_unique();
+    for slot in [12, 13] {
+        for reversed in [false, true] {
+            let builder = OrcaRaydiumExchange {
+                exchange: Pubkey::new_unique(),
+                orca: orca(),
+                raydium: raydium(),
+                user: user(),
+                slot: slot,
+                reversed: reversed,
+                flag: 0,
+                begin_run: false,
+                min_amounts_out: [0, 0],
+            };
+            assert_account_order(&program_id, builder.instruction(&program_id).unwrap());
+        }
+    }
+}
+
+#[test]
+fn orca_whirl_order() {
+    let program_id = Pubkey::new_unique();
+    for slot in [14, 15] {
+        for reversed in [false, true] {
+            let builder = OrcaWhirlExchange {
+                exchange: Pubkey::new_unique(),
+                orca: orca(),
+                whirl: whirl(),
+                user: user(),
+                slot: slot,
+                reversed: reversed,
+                flag: 0,
+                begin_run: false,
+                min_amounts_out: [0, 0],
+            };
+            assert_account_order(&program_id, builder.instruction(&program_id).unwrap());
+        }
+    }
+}
+
+#[test]
+fn path_and_route_put_operator_second() {
+    let program_id = Pubkey::new_unique();
+    let operator = Pubkey::new_unique();
+    let orca = orca();
+    let hop_accounts = orca.hop_accounts(&operator, &Pubkey::new_unique(), &Pubkey::new_unique(), true);
+
+    let path = PathExchange {
+        operator: operator,
+        exchange: Pubkey::new_unique(),
+        flag: 0,
+        amount: 1,
+        market: Market::Orca,
+        side: 0,
+        minimum_amount_out: 0,
+        hop_accounts: hop_accounts.clone(),
+    }
+    .instruction(&program_id);
+    assert_eq!(path.accounts[1].pubkey, operator);
+    assert_eq!(run(&program_id, &path), Err(ArbitrageError::UnauthorizedOperator.into()));
+
+    let route = ExecuteRoute {
+        operator: operator,
+        amount_in: 1,
+        hops: vec![
+            RouteHopAccounts { market: Market::Orca, side: 0, minimum_amount_out: 0, accounts: hop_accounts.clone() },
+            RouteHopAccounts { market: Market::Orca, side: 0, minimum_amount_out: 0, accounts: hop_accounts },
+        ],
+    }
+    .instruction(&program_id)
+    .unwrap();
+    assert_eq!(route.accounts.len(), 2 + 2 * 11);
+    match ArbitrageInstruction::unpack(&route.data).unwrap() {
+        ArbitrageInstruction::ExecuteRoute(data) => {
+            assert_eq!(data.hops.len(), 2);
+            assert_eq!(data.hops[0].accounts_len, 11);
+        }
+        _ => panic!("not a route"),
+    }
+    assert_eq!(run(&program_id, &route), Err(ArbitrageError::UnauthorizedOperator.into()));
+}
+
+#[test]
+fn unknown_discriminants_are_rejected() {
+    let invalid = Some(ProgramError::from(ArbitrageError::InvalidInstruction));
+    assert_eq!(ArbitrageInstruction::unpack(&[INSTRUCTION_VERSION, 47]).err(), invalid);
+    assert_eq!(ArbitrageInstruction::unpack(&[INSTRUCTION_VERSION]).err(), invalid);
+    // routes need the versioned layout, their old tag decodes as nothing
+    assert_eq!(ArbitrageInstruction::unpack(&[171, 1, 0, 0, 0, 0, 0, 0, 0, 1]).err(), invalid);
+}
+
+#[test]
+fn path_minimum_is_required() {
+    let mut data = vec![INSTRUCTION_VERSION, 5, 0];
+    data.extend_from_slice(&1_000u64.to_le_bytes());
+    data.extend_from_slice(&[Market::Orca as u8, 0]);
+    assert_eq!(
+        ArbitrageInstruction::unpack(&data).err(),
+        Some(ProgramError::from(ArbitrageError::InvalidInstruction))
+    );
+    data.extend_from_slice(&990u64.to_le_bytes());
+    match ArbitrageInstruction::unpack(&data).unwrap() {
+        ArbitrageInstruction::Exchange_WithPath(path) => assert_eq!(path.minimum_amount_out, 990),
+        _ => panic!("not a path"),
+    }
+}
+
+#[test]
+fn leg_minimums_are_required() {
+    // flag, begin_run and max_amount_in without the leg minimums
+    let mut data = vec![INSTRUCTION_VERSION, 6, 0, 0];
+    data.extend_from_slice(&0u64.to_le_bytes());
+    assert_eq!(
+        ArbitrageInstruction::unpack(&data).err(),
+        Some(ProgramError::from(ArbitrageError::InvalidInstruction))
+    );
+    data.extend_from_slice(&990u64.to_le_bytes());
+    data.extend_from_slice(&980u64.to_le_bytes());
+    match ArbitrageInstruction::unpack(&data).unwrap() {
+        ArbitrageInstruction::Exchange_Stable1(stable) => assert_eq!(stable.min_amounts_out, [990, 980]),
+        _ => panic!("not a stable strategy"),
+    }
+
+    let mut data = vec![INSTRUCTION_VERSION, 3];
+    assert_eq!(
+        ArbitrageInstruction::unpack(&data).err(),
+        Some(ProgramError::from(ArbitrageError::InvalidInstruction))
+    );
+    for amount in [1u64, 2, 3] {
+        data.extend_from_slice(&amount.to_le_bytes());
+    }
+    match ArbitrageInstruction::unpack(&data).unwrap() {
+        ArbitrageInstruction::Exchange_NonStable_Serum3(serum) => assert_eq!(serum.min_amounts_out, [1, 2, 3]),
+        _ => panic!("not a serum strategy"),
+    }
+}
@@ -0,0 +1,630 @@
+//! Shared harness for the integration tests.
+//!
+//! The venues are replaced by one local constant product program registered
+//! under each dex's mainnet id, which the strategies validate. It accepts the instruction layouts the
+//! processor's CPI wrappers emit, finds the user's token accounts (owned by the
+//! signer) and the pool vaults (owned by the mock's pda) among the accounts and
+//! moves the tokens with spl-token, so the processor sees real balance changes.
+#![allow(dead_code)]
+
+use arrayref::{array_ref, array_refs};
+use solana_arbitrage::{
+    client::*,
+    error::ArbitrageError,
+    processor::Processor,
+    stable_quote::MERCURIAL_FEE_DENOMINATOR,
+    state::{Config, ExchangeState, StrategyParams, MAX_STRATEGIES},
+    validation,
+};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{Instruction, InstructionError},
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+use stable_swap_client::{
+    fees::Fees as SaberFees,
+    state::{SwapInfo, SwapTokenInfo},
+};
+
+pub const MOCK_AUTHORITY_SEED: &[u8] = b"authority";
+pub const MOCK_FEE_NUMERATOR: u64 = 30;
+pub const MOCK_FEE_DENOMINATOR: u64 = 10_000;
+/// amplification of the saber and mercurial pool states, low enough to price an
+/// imbalanced pool like the mock's constant product
+pub const MOCK_AMP_FACTOR: u64 = 1;
+/// `(input_amount, threshold, expected_profit)` seeded for every strategy, the
+/// saber/whirl and saber/crema slots 6-9 size like slot 0
+pub const STRATEGY_PARAMS: [(u64, u64, u64); MAX_STRATEGIES as usize] = [
+    (2_500_000_000, 20_000_000, 80_000_000),
+    (100_000_000, 20_000, 100_000),
+    (2_500_000_000, 5_000, 100_000),
+    (2_500_000_000, 5_000, 100_000),
+    (2_500_000_000, 500_000, 2_500_000),
+    (2_500_000_000, 500_000, 500_000),
+    (2_500_000_000, 20_000_000, 80_000_000),
+    (2_500_000_000, 20_000_000, 80_000_000),
+    (2_500_000_000, 20_000_000, 80_000_000),
+    (2_500_000_000, 20_000_000, 80_000_000),
+    (2_500_000_000, 5_000, 100_000),
+    (2_500_000_000, 5_000, 100_000),
+    (2_500_000_000, 500_000, 2_500_000),
+    (2_500_000_000, 500_000, 500_000),
+    (2_500_000_000, 2_500_000, 25_000_000),
+    (2_500_000_000, 2_500_000, 25_000_000),
+];
+/// custom error returned by a mock when the output is below the caller's minimum
+pub const MOCK_SLIPPAGE: u32 = 1;
+
+/// error of the first instruction of a transaction failing with `err`
+pub fn instruction_error(err: ArbitrageError) -> TransactionError {
+    match ProgramError::from(err) {
+        ProgramError::Custom(code) => TransactionError::InstructionError(0, InstructionError::Custom(code)),
+        err => panic!("not a custom error: {:?}", err),
+    }
+}
+
+/// vault authority of every pool of a mock program
+pub fn mock_authority(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MOCK_AUTHORITY_SEED], program_id)
+}
+
+/// output of a mock swap of `amount_in` against the given reserves
+pub fn mock_amount_out(reserve_in: u64, reserve_out: u64, amount_in: u64) -> u64 {
+    let amount_in = amount_in as u128 * (MOCK_FEE_DENOMINATOR - MOCK_FEE_NUMERATOR) as u128
+        / MOCK_FEE_DENOMINATOR as u128;
+    (reserve_out as u128 * amount_in / (reserve_in as u128 + amount_in)) as u64
+}
+
+/// saber state of a mock pool, with the mock's fee and a low amplification
+pub fn mock_saber_state(reserves: &[(Pubkey, u64)], vaults: &[Pubkey]) -> Vec<u8> {
+    let token = |index: usize| SwapTokenInfo {
+        reserves: vaults[index],
+        mint: reserves[index].0,
+        admin_fees: Pubkey::new_unique(),
+        index: index as u8,
+    };
+    let swap_info = SwapInfo {
+        is_initialized: true,
+        is_paused: false,
+        nonce: 0,
+        initial_amp_factor: MOCK_AMP_FACTOR,
+        target_amp_factor: MOCK_AMP_FACTOR,
+        start_ramp_ts: 0,
+        stop_ramp_ts: 0,
+        future_admin_deadline: 0,
+        future_admin_key: Pubkey::default(),
+        admin_key: Pubkey::default(),
+        token_a: token(0),
+        token_b: token(1),
+        pool_mint: Pubkey::new_unique(),
+        fees: SaberFees {
+            admin_trade_fee_numerator: 0,
+            admin_trade_fee_denominator: 1,
+            admin_withdraw_fee_numerator: 0,
+            admin_withdraw_fee_denominator: 1,
+            trade_fee_numerator: MOCK_FEE_NUMERATOR,
+            trade_fee_denominator: MOCK_FEE_DENOMINATOR,
+            withdraw_fee_numerator: 0,
+            withdraw_fee_denominator: 1,
+        },
+    };
+    let mut data = vec![0; SwapInfo::LEN];
+    swap_info.pack_into_slice(&mut data);
+    data
+}
+
+/// mercurial state of a mock pool over `vaults`, see `mock_saber_state`
+pub fn mock_mercurial_state(vaults: &[Pubkey]) -> Vec<u8> {
+    let mut data = vec![0u8; 263];
+    data[1] = 1;
+    data[3..11].copy_from_slice(&MOCK_AMP_FACTOR.to_le_bytes());
+    let fee_numerator = MOCK_FEE_NUMERATOR * MERCURIAL_FEE_DENOMINATOR / MOCK_FEE_DENOMINATOR;
+    data[11..19].copy_from_slice(&fee_numerator.to_le_bytes());
+    data[27..31].copy_from_slice(&(vaults.len() as u32).to_le_bytes());
+    data[31..39].copy_from_slice(&6u64.to_le_bytes());
+    for (k, vault) in vaults.iter().enumerate() {
+        data[39 + k * 8..47 + k * 8].copy_from_slice(&1u64.to_le_bytes());
+        data[71 + k * 32..103 + k * 32].copy_from_slice(vault.as_ref());
+    }
+    data
+}
+
+/// spl-token-swap, saber, mercurial, raydium and crema all end their swap data with
+/// `amount_in, minimum_amount_out` and list the user source before the destination
+fn process_amounts_at_end(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    if data.len() < 16 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let amounts = array_ref![data, data.len() - 16, 16];
+    let (amount_in, minimum_amount_out) = array_refs![amounts, 8, 8];
+    mock_swap(
+        program_id,
+        accounts,
+        u64::from_le_bytes(*amount_in),
+        u64::from_le_bytes(*minimum_amount_out),
+        true,
+    )
+}
+
+/// whirlpool swap: `discriminator, amount, other_amount_threshold, sqrt_price_limit, exact_input, a_to_b`
+fn process_whirlpool(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    if data.len() != 42 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let data = array_ref![data, 0, 42];
+    let (_discriminator, amount, other_amount_threshold, _sqrt_price_limit, _exact_input, a_to_b) =
+        array_refs![data, 8, 8, 8, 16, 1, 1];
+    mock_swap(
+        program_id,
+        accounts,
+        u64::from_le_bytes(*amount),
+        u64::from_le_bytes(*other_amount_threshold),
+        a_to_b[0] == 1,
+    )
+}
+
+fn mock_swap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    minimum_amount_out: u64,
+    first_user_account_is_source: bool,
+) -> ProgramResult {
+    let (authority, bump_seed) = mock_authority(program_id);
+    let owner = accounts
+        .iter()
+        .find(|acc| acc.is_signer)
+        .ok_or(ProgramError::MissingRequiredSignature)?;
+    let authority_acc = accounts
+        .iter()
+        .find(|acc| *acc.key == authority)
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let token_program = accounts
+        .iter()
+        .find(|acc| *acc.key == spl_token::id())
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    let mut user_accs = vec![];
+    let mut vaults = vec![];
+    for acc in accounts.iter() {
+        if acc.owner != &spl_token::id() || acc.data_len() != TokenAccount::LEN {
+            continue;
+        }
+        let token = TokenAccount::unpack(&acc.try_borrow_data()?)?;
+        if token.owner == *owner.key {
+            user_accs.push((acc, token));
+        } else if token.owner == authority {
+            vaults.push((acc, token));
+        }
+    }
+    if user_accs.len() != 2 {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let (source, destination) = if first_user_account_is_source {
+        (&user_accs[0], &user_accs[1])
+    } else {
+        (&user_accs[1], &user_accs[0])
+    };
+    let vault_for = |mint: &Pubkey| {
+        vaults
+            .iter()
+            .find(|(_, vault)| vault.mint == *mint)
+            .ok_or(ProgramError::InvalidArgument)
+    };
+    let source_vault = vault_for(&source.1.mint)?;
+    let destination_vault = vault_for(&destination.1.mint)?;
+
+    let amount_out = mock_amount_out(source_vault.1.amount, destination_vault.1.amount, amount_in);
+    msg!("mock swap, amount in: {}, amount out: {}", amount_in, amount_out);
+    if amount_out < minimum_amount_out {
+        return Err(ProgramError::Custom(MOCK_SLIPPAGE));
+    }
+
+    invoke(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            source.0.key,
+            source_vault.0.key,
+            owner.key,
+            &[],
+            amount_in,
+        )?,
+        &[source.0.clone(), source_vault.0.clone(), owner.clone(), token_program.clone()],
+    )?;
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            destination_vault.0.key,
+            destination.0.key,
+            &authority,
+            &[],
+            amount_out,
+        )?,
+        &[
+            destination_vault.0.clone(),
+            destination.0.clone(),
+            authority_acc.clone(),
+            token_program.clone(),
+        ],
+        &[&[MOCK_AUTHORITY_SEED, &[bump_seed]]],
+    )
+}
+
+/// program ids the mock is registered under
+pub struct MockPrograms {
+    pub token_swap: Pubkey,
+    pub saber: Pubkey,
+    pub mercurial: Pubkey,
+    pub raydium: Pubkey,
+    pub whirl: Pubkey,
+    pub crema: Pubkey,
+}
+
+/// a seeded mock pool, `vaults` follow the order of the reserves it was created with
+pub struct MockPool {
+    pub program: Pubkey,
+    pub market: Pubkey,
+    pub authority: Pubkey,
+    pub vaults: Vec<Pubkey>,
+}
+
+impl MockPool {
+    pub fn orca(&self) -> OrcaAccounts {
+        OrcaAccounts {
+            program: self.program,
+            market: self.market,
+            authority: self.authority,
+            swap_a: self.vaults[0],
+            swap_b: self.vaults[1],
+            pool_mint: Pubkey::new_unique(),
+            fee: Pubkey::new_unique(),
+        }
+    }
+
+    pub fn saber(&self) -> SaberAccounts {
+        SaberAccounts {
+            program: self.program,
+            market: self.market,
+            authority: self.authority,
+            swap_a: self.vaults[0],
+            swap_b: self.vaults[1],
+            admin_fee: Pubkey::new_unique(),
+        }
+    }
+
+    pub fn mercurial(&self) -> MercurialAccounts {
+        MercurialAccounts {
+            program: self.program,
+            market: self.market,
+            authority: self.authority,
+            swap_accounts: self.vaults.clone(),
+        }
+    }
+
+    pub fn raydium(&self) -> RaydiumAccounts {
+        RaydiumAccounts {
+            program: self.program,
+            market: self.market,
+            authority: self.authority,
+            open_orders: Pubkey::new_unique(),
+            target_orders: Pubkey::new_unique(),
+            coin_vault: self.vaults[0],
+            pc_vault: self.vaults[1],
+            serum_program: validation::serum::id(),
+            serum_market: Pubkey::new_unique(),
+            serum_request_queue: Pubkey::new_unique(),
+            serum_event_queue: Pubkey::new_unique(),
+            serum_bids: Pubkey::new_unique(),
+            serum_asks: Pubkey::new_unique(),
+            serum_base_vault: Pubkey::new_unique(),
+            serum_quote_vault: Pubkey::new_unique(),
+            serum_vault_signer: Pubkey::new_unique(),
+        }
+    }
+
+    /// token a of the whirlpool is the first reserve
+    pub fn whirl(&self) -> WhirlAccounts {
+        WhirlAccounts {
+            program: self.program,
+            market: self.market,
+            vault_a: self.vaults[0],
+            vault_b: self.vaults[1],
+            tick_array: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+        }
+    }
+
+    pub fn crema(&self) -> CremaAccounts {
+        CremaAccounts {
+            program: self.program,
+            market: self.market,
+            authority: self.authority,
+            swap_a: self.vaults[0],
+            swap_b: self.vaults[1],
+            ticks: Pubkey::new_unique(),
+        }
+    }
+}
+
+/// accounts are seeded on the `ProgramTest` before the bank starts
+pub struct Harness {
+    pub program_test: ProgramTest,
+    pub program_id: Pubkey,
+    pub mocks: MockPrograms,
+    pub operator: Keypair,
+    pub exchange: Pubkey,
+    /// written to `exchange` when the bank starts
+    pub exchange_state: ExchangeState,
+}
+
+impl Harness {
+    /// arbitrage program and mocks, without a config account
+    pub fn bare() -> Self {
+        let program_id = Pubkey::new_unique();
+        let mut program_test = ProgramTest::new("solana_arbitrage", program_id, processor!(Processor::process));
+        let mocks = MockPrograms {
+            token_swap: validation::orca::id(),
+            saber: validation::saber::id(),
+            mercurial: validation::mercurial::id(),
+            raydium: validation::raydium::id(),
+            whirl: validation::whirlpool::id(),
+            crema: validation::crema::id(),
+        };
+        program_test.add_builtin_program("mock_token_swap", mocks.token_swap, processor!(process_amounts_at_end));
+        program_test.add_builtin_program("mock_saber", mocks.saber, processor!(process_amounts_at_end));
+        program_test.add_builtin_program("mock_mercurial", mocks.mercurial, processor!(process_amounts_at_end));
+        program_test.add_builtin_program("mock_raydium", mocks.raydium, processor!(process_amounts_at_end));
+        program_test.add_builtin_program("mock_whirl", mocks.whirl, processor!(process_whirlpool));
+        program_test.add_builtin_program("mock_crema", mocks.crema, processor!(process_amounts_at_end));
+
+        let operator = Keypair::new();
+        let exchange_state = ExchangeState {
+            owner: operator.pubkey(),
+            ..ExchangeState::default()
+        };
+        Harness {
+            program_test: program_test,
+            program_id: program_id,
+            mocks: mocks,
+            operator: operator,
+            exchange: Pubkey::new_unique(),
+            exchange_state: exchange_state,
+        }
+    }
+
+    /// `bare` plus a config listing the operator
+    pub fn new() -> Self {
+        let mut harness = Self::bare();
+        let (config_key, bump_seed) = config_address(&harness.program_id);
+        let config = Config {
+            is_initialized: true,
+            bump_seed: bump_seed,
+            admin: harness.operator.pubkey(),
+            operators: vec![harness.operator.pubkey()],
+        };
+        let mut data = vec![0; Config::LEN];
+        config.pack_into_slice(&mut data);
+        harness.program_test.add_account(
+            config_key,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::LEN),
+                data: data,
+                owner: harness.program_id,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        for index in 0..MAX_STRATEGIES {
+            let (input_amount, threshold, expected_profit) = STRATEGY_PARAMS[index as usize];
+            harness.set_strategy(index, true, input_amount, threshold, expected_profit);
+        }
+        harness
+    }
+
+    /// seeds the params pda of strategy `index`
+    pub fn set_strategy(&mut self, index: u8, enabled: bool, input_amount: u64, threshold: u64, expected_profit: u64) {
+        let (strategy_key, bump_seed) = strategy_address(&self.program_id, index);
+        let strategy = StrategyParams {
+            is_initialized: true,
+            bump_seed: bump_seed,
+            index: index,
+            enabled: enabled,
+            input_amount: input_amount,
+            threshold: threshold,
+            expected_profit: expected_profit,
+        };
+        let mut data = vec![0; StrategyParams::LEN];
+        strategy.pack_into_slice(&mut data);
+        self.program_test.add_account(
+            strategy_key,
+            Account {
+                lamports: Rent::default().minimum_balance(StrategyParams::LEN),
+                data: data,
+                owner: self.program_id,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    }
+
+    pub fn add_mint(&mut self) -> Pubkey {
+        let mint = Pubkey::new_unique();
+        self.program_test.add_packable_account(
+            mint,
+            Rent::default().minimum_balance(Mint::LEN),
+            &Mint {
+                mint_authority: COption::None,
+                supply: u64::MAX / 2,
+                decimals: 6,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            },
+            &spl_token::id(),
+        );
+        mint
+    }
+
+    pub fn add_token_account(&mut self, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+        let account = Pubkey::new_unique();
+        self.program_test.add_packable_account(
+            account,
+            Rent::default().minimum_balance(TokenAccount::LEN),
+            &TokenAccount {
+                mint: *mint,
+                owner: *owner,
+                amount: amount,
+                state: AccountState::Initialized,
+                ..TokenAccount::default()
+            },
+            &spl_token::id(),
+        );
+        account
+    }
+
+    /// token account of the operator
+    pub fn add_user_account(&mut self, mint: &Pubkey, amount: u64) -> Pubkey {
+        let owner = self.operator.pubkey();
+        self.add_token_account(mint, &owner, amount)
+    }
+
+    /// pool of the mock registered as `program` holding `reserves`.
+    ///
+    /// A whirlpool owns its vaults, so its market is the mock authority and a
+    /// test can seed one whirlpool only.
+    pub fn add_pool(&mut self, program: &Pubkey, reserves: &[(Pubkey, u64)]) -> MockPool {
+        let (authority, _) = mock_authority(program);
+        let market = if *program == self.mocks.whirl { authority } else { Pubkey::new_unique() };
+        let vaults: Vec<Pubkey> = reserves
+            .iter()
+            .map(|(mint, amount)| self.add_token_account(mint, &authority, *amount))
+            .collect();
+        let data = if *program == self.mocks.saber {
+            mock_saber_state(reserves, &vaults)
+        } else if *program == self.mocks.mercurial {
+            mock_mercurial_state(&vaults)
+        } else {
+            vec![0; 8]
+        };
+        self.program_test.add_account(
+            market,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data: data,
+                owner: *program,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        MockPool {
+            program: *program,
+            market: market,
+            authority: authority,
+            vaults: vaults,
+        }
+    }
+
+    /// the exchange account, with its state bound to `strategy_id`
+    pub fn exchange_for(&mut self, strategy_id: u8) -> Pubkey {
+        self.exchange_state.strategy_id = strategy_id;
+        self.exchange
+    }
+
+    pub async fn start(mut self) -> TestContext {
+        let mut data = vec![0; ExchangeState::LEN];
+        self.exchange_state.pack_into_slice(&mut data);
+        self.program_test.add_account(
+            self.exchange,
+            Account {
+                lamports: Rent::default().minimum_balance(ExchangeState::LEN),
+                data: data,
+                owner: self.program_id,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        let (banks_client, payer, recent_blockhash) = self.program_test.start().await;
+        TestContext {
+            banks_client: banks_client,
+            payer: payer,
+            recent_blockhash: recent_blockhash,
+            program_id: self.program_id,
+            operator: self.operator,
+            exchange: self.exchange,
+        }
+    }
+}
+
+pub struct TestContext {
+    pub banks_client: BanksClient,
+    pub payer: Keypair,
+    pub recent_blockhash: Hash,
+    pub program_id: Pubkey,
+    pub operator: Keypair,
+    pub exchange: Pubkey,
+}
+
+impl TestContext {
+    /// sends `instruction` signed by the payer and `signers`
+    pub async fn process_with_signers(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        let mut keypairs = vec![&self.payer];
+        keypairs.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.payer.pubkey()),
+            &keypairs,
+            self.recent_blockhash,
+        );
+        self.banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|err| err.unwrap())
+    }
+
+    /// sends `instruction` signed by the payer and the operator
+    pub async fn process(&mut self, instruction: Instruction) -> Result<(), TransactionError> {
+        let operator = Keypair::from_bytes(&self.operator.to_bytes()).unwrap();
+        self.process_with_signers(instruction, &[&operator]).await
+    }
+
+    pub async fn token_balance(&mut self, account: &Pubkey) -> u64 {
+        let account = self.banks_client.get_account(*account).await.unwrap().unwrap();
+        TokenAccount::unpack(&account.data).unwrap().amount
+    }
+
+    pub async fn exchange_state(&mut self) -> ExchangeState {
+        let exchange = self.exchange;
+        let account = self.banks_client.get_account(exchange).await.unwrap().unwrap();
+        ExchangeState::unpack_from_slice(&account.data).unwrap()
+    }
+
+    pub async fn strategy(&mut self, index: u8) -> StrategyParams {
+        let (strategy_key, _) = strategy_address(&self.program_id, index);
+        let account = self.banks_client.get_account(strategy_key).await.unwrap().unwrap();
+        StrategyParams::unpack_from_slice(&account.data).unwrap()
+    }
+
+    pub async fn config(&mut self) -> Config {
+        let (config_key, _) = config_address(&self.program_id);
+        let account = self.banks_client.get_account(config_key).await.unwrap().unwrap();
+        Config::unpack_from_slice(&account.data).unwrap()
+    }
+}
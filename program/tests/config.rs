@@ -0,0 +1,159 @@
+#![cfg(feature = "no-entrypoint")]
+
+mod common;
+
+use common::{instruction_error, Harness};
+use solana_arbitrage::{client::*, error::ArbitrageError};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn initialize_and_update_config() {
+    let harness = Harness::bare();
+    let mut context = harness.start().await;
+    let program_id = context.program_id;
+    let admin = Keypair::new();
+    let operator = Pubkey::new_unique();
+
+    let initialize = InitializeConfig {
+        payer: context.payer.pubkey(),
+        admin: admin.pubkey(),
+        operators: vec![operator],
+    }
+    .instruction(&program_id);
+    context.process_with_signers(initialize, &[]).await.unwrap();
+    let config = context.config().await;
+    assert!(config.is_initialized);
+    assert_eq!(config.bump_seed, config_address(&program_id).1);
+    assert_eq!(config.admin, admin.pubkey());
+    assert_eq!(config.operators, vec![operator]);
+
+    let new_admin = Pubkey::new_unique();
+    let update = UpdateConfig {
+        admin: admin.pubkey(),
+        new_admin: new_admin,
+        operators: vec![],
+    }
+    .instruction(&program_id);
+    context.process_with_signers(update, &[&admin]).await.unwrap();
+    let config = context.config().await;
+    assert_eq!(config.admin, new_admin);
+    assert!(config.operators.is_empty());
+}
+
+#[tokio::test]
+async fn update_config_requires_admin() {
+    let harness = Harness::new();
+    let mut context = harness.start().await;
+    let program_id = context.program_id;
+    let intruder = Keypair::new();
+
+    let update = UpdateConfig {
+        admin: intruder.pubkey(),
+        new_admin: intruder.pubkey(),
+        operators: vec![intruder.pubkey()],
+    }
+    .instruction(&program_id);
+    assert_eq!(
+        context.process_with_signers(update, &[&intruder]).await,
+        Err(instruction_error(ArbitrageError::UnauthorizedOperator))
+    );
+}
+
+#[tokio::test]
+async fn initialize_and_update_strategy() {
+    let harness = Harness::bare();
+    let mut context = harness.start().await;
+    let program_id = context.program_id;
+    let admin = context.payer.pubkey();
+
+    let initialize = InitializeConfig {
+        payer: admin,
+        admin: admin,
+        operators: vec![],
+    }
+    .instruction(&program_id);
+    context.process_with_signers(initialize, &[]).await.unwrap();
+
+    let initialize = InitializeStrategy {
+        admin: admin,
+        index: 3,
+        enabled: true,
+        input_amount: 2_500_000_000,
+        threshold: 5_000,
+        expected_profit: 100_000,
+    }
+    .instruction(&program_id);
+    context.process_with_signers(initialize, &[]).await.unwrap();
+    let strategy = context.strategy(3).await;
+    assert!(strategy.is_initialized);
+    assert!(strategy.enabled);
+    assert_eq!(strategy.bump_seed, strategy_address(&program_id, 3).1);
+    assert_eq!(strategy.index, 3);
+    assert_eq!(strategy.input_amount, 2_500_000_000);
+    assert_eq!(strategy.threshold, 5_000);
+    assert_eq!(strategy.expected_profit, 100_000);
+
+    let update = UpdateStrategy {
+        admin: admin,
+        index: 3,
+        enabled: false,
+        input_amount: 1_000_000_000,
+        threshold: 10_000,
+        expected_profit: 50_000,
+    }
+    .instruction(&program_id);
+    context.process_with_signers(update, &[]).await.unwrap();
+    let strategy = context.strategy(3).await;
+    assert!(!strategy.enabled);
+    assert_eq!(strategy.input_amount, 1_000_000_000);
+    assert_eq!(strategy.threshold, 10_000);
+    assert_eq!(strategy.expected_profit, 50_000);
+}
+
+#[tokio::test]
+async fn update_strategy_requires_admin() {
+    let harness = Harness::new();
+    let mut context = harness.start().await;
+    let program_id = context.program_id;
+    let intruder = Keypair::new();
+
+    let update = UpdateStrategy {
+        admin: intruder.pubkey(),
+        index: 0,
+        enabled: true,
+        input_amount: 1,
+        threshold: 0,
+        expected_profit: 1,
+    }
+    .instruction(&program_id);
+    assert_eq!(
+        context.process_with_signers(update, &[&intruder]).await,
+        Err(instruction_error(ArbitrageError::UnauthorizedOperator))
+    );
+}
+
+#[tokio::test]
+async fn update_strategy_rejects_zero_sizes() {
+    let harness = Harness::new();
+    let mut context = harness.start().await;
+    let program_id = context.program_id;
+    let admin = context.operator.pubkey();
+
+    // the handlers divide by the input amount and the expected profit
+    for (input_amount, expected_profit) in [(0, 100_000), (2_500_000_000, 0)] {
+        let update = UpdateStrategy {
+            admin: admin,
+            index: 0,
+            enabled: true,
+            input_amount: input_amount,
+            threshold: 0,
+            expected_profit: expected_profit,
+        }
+        .instruction(&program_id);
+        assert_eq!(
+            context.process(update).await,
+            Err(instruction_error(ArbitrageError::InvalidInstruction))
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use solana_arbitrage::{decimals::MintDecimals, error::ArbitrageError};
+use solana_program::{
+    account_info::AccountInfo, program_error::ProgramError, program_option::COption, program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+fn mint_data(decimals: u8) -> Vec<u8> {
+    let mut data = vec![0u8; Mint::LEN];
+    Mint::pack(
+        Mint {
+            mint_authority: COption::None,
+            supply: 0,
+            decimals: decimals,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        },
+        &mut data,
+    )
+    .unwrap();
+    data
+}
+
+fn token_account_data(mint: &Pubkey) -> Vec<u8> {
+    let mut data = vec![0u8; TokenAccount::LEN];
+    TokenAccount::pack(
+        TokenAccount {
+            mint: *mint,
+            owner: Pubkey::new_unique(),
+            state: AccountState::Initialized,
+            ..TokenAccount::default()
+        },
+        &mut data,
+    )
+    .unwrap();
+    data
+}
+
+#[test]
+fn unit_of_token_account_mint() {
+    let token_program = spl_token::id();
+    let (mint_key, token_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mut mint_bytes, mut token_bytes) = (mint_data(9), token_account_data(&mint_key));
+    let (mut mint_lamports, mut token_lamports) = (0, 0);
+    let mint_acc = AccountInfo::new(&mint_key, false, false, &mut mint_lamports, &mut mint_bytes, &token_program, false, 0);
+    let token_acc = AccountInfo::new(&token_key, false, false, &mut token_lamports, &mut token_bytes, &token_program, false, 0);
+
+    let mut mint_decimals = MintDecimals::new();
+    assert_eq!(mint_decimals.decimals(&mint_acc), Ok(9));
+    assert_eq!(mint_decimals.unit_of(&token_acc, &mint_acc), Ok(1_000_000_000));
+
+    // the decimals are read once per instruction
+    mint_acc.try_borrow_mut_data().unwrap().copy_from_slice(&[0u8; Mint::LEN]);
+    assert_eq!(mint_decimals.unit(&mint_acc), Ok(1_000_000_000));
+    assert_eq!(MintDecimals::new().unit(&mint_acc), Err(ProgramError::UninitializedAccount));
+}
+
+#[test]
+fn rejects_other_mints() {
+    let token_program = spl_token::id();
+    let (mint_key, token_key) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mut mint_bytes, mut token_bytes) = (mint_data(6), token_account_data(&Pubkey::new_unique()));
+    let (mut mint_lamports, mut token_lamports) = (0, 0);
+    let mint_acc = AccountInfo::new(&mint_key, false, false, &mut mint_lamports, &mut mint_bytes, &token_program, false, 0);
+    let token_acc = AccountInfo::new(&token_key, false, false, &mut token_lamports, &mut token_bytes, &token_program, false, 0);
+    assert_eq!(
+        MintDecimals::new().unit_of(&token_acc, &mint_acc),
+        Err(ArbitrageError::MintMismatch.into())
+    );
+
+    // a mint layout under another program
+    let foreign_owner = Pubkey::new_unique();
+    let (mut data, mut lamports) = (mint_data(6), 0);
+    let foreign_acc = AccountInfo::new(&mint_key, false, false, &mut lamports, &mut data, &foreign_owner, false, 0);
+    assert_eq!(
+        MintDecimals::new().decimals(&foreign_acc),
+        Err(ProgramError::from(ArbitrageError::InvalidOwner))
+    );
+}
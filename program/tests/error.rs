@@ -0,0 +1,81 @@
+#![cfg(feature = "no-entrypoint")]
+
+use num_traits::FromPrimitive;
+use solana_arbitrage::{
+    client::PathExchange,
+    error::ArbitrageError,
+    instruction::{ArbitrageInstruction, Market},
+};
+use solana_program::{decode_error::DecodeError, program_error::ProgramError, pubkey::Pubkey};
+
+const FIRST_CODE: u32 = 10000;
+const LAST_CODE: u32 = 10025;
+
+#[test]
+fn codes_roundtrip() {
+    for code in FIRST_CODE..=LAST_CODE {
+        let error = ArbitrageError::from_u32(code).unwrap();
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(code));
+    }
+    assert!(ArbitrageError::from_u32(FIRST_CODE - 1).is_none());
+    assert!(ArbitrageError::from_u32(LAST_CODE + 1).is_none());
+    assert!(ArbitrageError::from_i64(-1).is_none());
+}
+
+#[test]
+fn codes_are_stable() {
+    // clients match on these numbers, they never move
+    for (error, code) in [
+        (ArbitrageError::InvalidInstruction, 10000),
+        (ArbitrageError::UnauthorizedOperator, 10004),
+        (ArbitrageError::EmptyOrderBook, 10005),
+        (ArbitrageError::InvalidProgramId, 10012),
+        (ArbitrageError::MintMismatch, 10016),
+        (ArbitrageError::SlippageExceeded, 10017),
+        (ArbitrageError::ProfitBelowMinimum, 10018),
+        (ArbitrageError::StaleState, 10019),
+        (ArbitrageError::UnsupportedMarket, 10020),
+        (ArbitrageError::PoolAccountMismatch, 10021),
+        (ArbitrageError::ExchangeOwnerMismatch, 10022),
+        (ArbitrageError::StrategyMismatch, 10023),
+        (ArbitrageError::AccountNotWritable, 10024),
+        (ArbitrageError::AmountBelowLotSize, 10025),
+    ] {
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(code));
+    }
+}
+
+#[test]
+fn decodes_custom_errors() {
+    assert_eq!(<ArbitrageError as DecodeError<ArbitrageError>>::type_of(), "ArbitrageError");
+    let error: Option<ArbitrageError> = ArbitrageError::decode_custom_error_to_enum(10019);
+    assert_eq!(error.unwrap().to_string(), "Stale State");
+    let error: Option<ArbitrageError> = ArbitrageError::decode_custom_error_to_enum(0);
+    assert!(error.is_none());
+}
+
+#[test]
+fn unknown_market_tag() {
+    let path = |market: Market| {
+        PathExchange {
+            operator: Pubkey::new_unique(),
+            exchange: Pubkey::new_unique(),
+            flag: 0,
+            amount: 1,
+            market: market,
+            side: 0,
+            minimum_amount_out: 0,
+            hop_accounts: vec![],
+        }
+        .instruction(&Pubkey::new_unique())
+        .data
+    };
+    let (orca, saber) = (path(Market::Orca), path(Market::Saber));
+    let market_offset = orca.iter().zip(saber.iter()).position(|(a, b)| a != b).unwrap();
+    let mut data = orca;
+    data[market_offset] = 0xFF;
+    assert_eq!(
+        ArbitrageInstruction::unpack(&data).err(),
+        Some(ArbitrageError::UnsupportedMarket.into())
+    );
+}
@@ -0,0 +1,151 @@
+use solana_arbitrage::{
+    adapter::venue_error,
+    event::{decode_log, decode_logs, ArbitrageEvent, SkipReason, EVENT_VERSION, STRATEGY_ROUTE, STRATEGY_SERUM_SELECT},
+    instruction::Market,
+};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+fn events() -> Vec<ArbitrageEvent> {
+    vec![
+        ArbitrageEvent::HopExecuted {
+            strategy: STRATEGY_ROUTE,
+            hop: 2,
+            amount_in: 1_000_000,
+            amount_out: u64::MAX,
+        },
+        ArbitrageEvent::CycleCompleted {
+            strategy: 6,
+            balance_before: 100_000_000_000,
+            balance_after: 100_000_450_000,
+            total_profit: 450_000,
+            total_lost: 0,
+        },
+        ArbitrageEvent::CycleSkipped {
+            strategy: 0,
+            reason: SkipReason::QuoteBelowInput,
+            expected: 5_000_000_000,
+            actual: 4_999_000_000,
+        },
+        ArbitrageEvent::ProfitCheckFailed {
+            strategy: 11,
+            total_profit: 1,
+            total_lost: 2,
+        },
+        ArbitrageEvent::VenueFailed {
+            strategy: 3,
+            hop: 1,
+            venue: Market::Saber as u8,
+            error: u64::from(ProgramError::Custom(6)),
+        },
+        ArbitrageEvent::CycleSkipped {
+            strategy: STRATEGY_SERUM_SELECT,
+            reason: SkipReason::BelowLotSize,
+            expected: 100_000_000,
+            actual: 99_999_999,
+        },
+    ]
+}
+
+fn data_log(data: &[u8]) -> String {
+    format!("Program data: {}", base64::encode(data))
+}
+
+#[test]
+fn pack_unpack_roundtrip() {
+    for event in events() {
+        let packed = event.pack();
+        assert_eq!(packed[0], EVENT_VERSION);
+        assert_eq!(ArbitrageEvent::unpack(&packed), Some(event));
+    }
+}
+
+#[test]
+fn stable_layout() {
+    let event = ArbitrageEvent::CycleSkipped {
+        strategy: 7,
+        reason: SkipReason::BelowThreshold,
+        expected: 0x0102,
+        actual: 3,
+    };
+    assert_eq!(
+        event.pack(),
+        vec![EVENT_VERSION, 2, 7, 0, 2, 1, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0]
+    );
+}
+
+#[test]
+fn unpack_rejects_other_layouts() {
+    let packed = events()[1].pack();
+    assert_eq!(ArbitrageEvent::unpack(&packed[..packed.len() - 1]), None);
+    let mut longer = packed.clone();
+    longer.push(0);
+    assert_eq!(ArbitrageEvent::unpack(&longer), None);
+
+    let mut other_version = packed.clone();
+    other_version[0] = EVENT_VERSION + 1;
+    assert_eq!(ArbitrageEvent::unpack(&other_version), None);
+
+    let mut unknown_tag = packed;
+    unknown_tag[1] = 5;
+    assert_eq!(ArbitrageEvent::unpack(&unknown_tag), None);
+
+    let mut unknown_reason = events()[2].pack();
+    unknown_reason[3] = 3;
+    assert_eq!(ArbitrageEvent::unpack(&unknown_reason), None);
+    assert_eq!(ArbitrageEvent::unpack(&[]), None);
+}
+
+#[test]
+fn decode_single_log() {
+    let event = events()[0].clone();
+    assert_eq!(decode_log(&data_log(&event.pack())), Some(event.clone()));
+    assert_eq!(decode_log("Program log: usdc balance before: 100"), None);
+    assert_eq!(decode_log("Program data: not-base64!"), None);
+    // two fields are never one of our events
+    let two_fields = format!("{} {}", data_log(&event.pack()), base64::encode(&event.pack()));
+    assert_eq!(decode_log(&two_fields), None);
+}
+
+#[test]
+fn decode_transaction_logs() {
+    let program_id = Pubkey::new_unique();
+    let venue_id = Pubkey::new_unique();
+    let events = events();
+    // a venue logging data that happens to use our layout must not be picked up
+    let logs = vec![
+        format!("Program {} invoke [1]", program_id),
+        "Program log: Instruction: saber/whirl Exchange v1".to_string(),
+        format!("Program {} invoke [2]", venue_id),
+        "Program log: Program fake invoke [3]".to_string(),
+        data_log(&events[3].pack()),
+        format!("Program {} consumed 2000 of 190000 compute units", venue_id),
+        format!("Program {} success", venue_id),
+        data_log(&events[0].pack()),
+        format!("Program {} invoke [2]", venue_id),
+        format!("Program {} failed: custom program error: 0x1", venue_id),
+        data_log(&events[1].pack()),
+        format!("Program return: {} AQ==", program_id),
+        format!("Program {} consumed 50000 of 200000 compute units", program_id),
+        format!("Program {} success", program_id),
+        data_log(&events[2].pack()),
+    ];
+    assert_eq!(decode_logs(&program_id, &logs), vec![events[0].clone(), events[1].clone()]);
+    assert_eq!(decode_logs(&venue_id, &logs), vec![events[3].clone()]);
+}
+
+#[test]
+fn venue_errors_keep_their_code() {
+    assert_eq!(
+        venue_error(STRATEGY_ROUTE, 1, Market::Serum, ProgramError::Custom(42)),
+        ProgramError::Custom(42)
+    );
+    assert_eq!(
+        venue_error(0, 0, Market::Saber, ProgramError::InvalidAccountData),
+        ProgramError::InvalidAccountData
+    );
+    let error = match events()[4] {
+        ArbitrageEvent::VenueFailed { error, .. } => error,
+        _ => unreachable!(),
+    };
+    assert_eq!(ProgramError::from(error), ProgramError::Custom(6));
+}
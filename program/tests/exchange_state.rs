@@ -0,0 +1,405 @@
+#![cfg(feature = "no-entrypoint")]
+
+mod common;
+
+use common::{instruction_error, Harness};
+use solana_arbitrage::{
+    client::*,
+    error::ArbitrageError,
+    event::{STRATEGY_ORCA_RAYDIUM_SERUM, STRATEGY_PATH, STRATEGY_ROUTE, STRATEGY_SERUM_SELECT},
+    instruction::ArbitrageInstruction,
+    state::{ExchangeState, EXCHANGE_STATE_TAG, EXCHANGE_STATE_VERSION, LEGACY_EXCHANGE_STATE_LEN},
+};
+use solana_program::{
+    instruction::InstructionError, program_error::ProgramError, pubkey::Pubkey, rent::Rent, system_program,
+};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::TransactionError,
+};
+
+fn legacy_data(input_amount: u64, exchange_out: u64, total_profit: u64, total_lost: u64) -> Vec<u8> {
+    [input_amount, exchange_out, total_profit, total_lost]
+        .iter()
+        .flat_map(|amount| amount.to_le_bytes())
+        .collect()
+}
+
+#[test]
+fn pack_unpack_roundtrip() {
+    let state = ExchangeState {
+        bump_seed: 254,
+        strategy_id: 6,
+        owner: Pubkey::new_unique(),
+        input_amount: 1,
+        exchange_out: 2,
+        total_profit: 3,
+        total_lost: 4,
+        run_profit: 8,
+        run_lost: 9,
+        attempts: 5,
+        successes: 6,
+        skips: 7,
+        last_slot: u64::MAX,
+    };
+    let mut data = vec![0; ExchangeState::LEN];
+    state.pack_into_slice(&mut data);
+    assert_eq!(data[..2], [EXCHANGE_STATE_TAG, EXCHANGE_STATE_VERSION]);
+    assert_eq!(ExchangeState::unpack_from_slice(&data), Ok(state));
+}
+
+#[test]
+fn unpack_rejects_other_layouts() {
+    let mut data = vec![0; ExchangeState::LEN];
+    ExchangeState::default().pack_into_slice(&mut data);
+    assert_eq!(
+        ExchangeState::unpack_from_slice(&data[..ExchangeState::LEN - 1]),
+        Err(ProgramError::InvalidAccountData)
+    );
+    let mut other_version = data.clone();
+    other_version[1] = EXCHANGE_STATE_VERSION + 1;
+    assert_eq!(ExchangeState::unpack_from_slice(&other_version), Err(ProgramError::InvalidAccountData));
+    let mut other_tag = data;
+    other_tag[0] = 0;
+    assert_eq!(ExchangeState::unpack_from_slice(&other_tag), Err(ProgramError::InvalidAccountData));
+
+    let legacy = legacy_data(1, 2, 3, 4);
+    assert_eq!(ExchangeState::unpack_from_slice(&legacy), Err(ProgramError::InvalidAccountData));
+    assert_eq!(
+        ExchangeState::unpack_legacy(&legacy),
+        Ok(ExchangeState {
+            input_amount: 1,
+            exchange_out: 2,
+            total_profit: 3,
+            total_lost: 4,
+            ..ExchangeState::default()
+        })
+    );
+    assert_eq!(
+        ExchangeState::unpack_legacy(&legacy[..LEGACY_EXCHANGE_STATE_LEN - 1]),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn counters_saturate() {
+    let mut state = ExchangeState {
+        attempts: u64::MAX,
+        successes: u64::MAX,
+        skips: u64::MAX,
+        ..ExchangeState::default()
+    };
+    state.record_attempt(42);
+    state.record_success();
+    state.record_skip();
+    assert_eq!((state.attempts, state.successes, state.skips), (u64::MAX, u64::MAX, u64::MAX));
+    assert_eq!(state.last_slot, 42);
+}
+
+#[test]
+fn begin_run_keeps_the_totals() {
+    let mut state = ExchangeState {
+        input_amount: 1_000,
+        exchange_out: 0,
+        total_profit: 5,
+        total_lost: 7,
+        run_profit: 5,
+        run_lost: 7,
+        attempts: 2,
+        ..ExchangeState::default()
+    };
+    state.begin_run(450);
+    assert_eq!((state.exchange_out, state.run_profit, state.run_lost), (450, 0, 0));
+    assert_eq!((state.input_amount, state.total_profit, state.total_lost, state.attempts), (1_000, 5, 7, 2));
+
+    state.record_profit(3).unwrap();
+    state.record_loss(1).unwrap();
+    assert_eq!((state.run_profit, state.run_lost), (3, 1));
+    assert_eq!((state.total_profit, state.total_lost), (8, 8));
+}
+
+/// the operator pays for the states it creates
+fn funded_harness() -> Harness {
+    let mut harness = Harness::new();
+    let operator = harness.operator.pubkey();
+    harness.program_test.add_account(
+        operator,
+        Account {
+            lamports: 1_000_000_000,
+            data: vec![],
+            owner: system_program::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    harness
+}
+
+/// a 32 byte exchange state next to the harness'
+fn migration_harness() -> (Harness, Pubkey) {
+    let mut harness = funded_harness();
+    let legacy = Pubkey::new_unique();
+    let program_id = harness.program_id;
+    harness.program_test.add_account(
+        legacy,
+        Account {
+            lamports: Rent::default().minimum_balance(LEGACY_EXCHANGE_STATE_LEN),
+            data: legacy_data(1_000, 1_100, 100, 0),
+            owner: program_id,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    (harness, legacy)
+}
+
+#[tokio::test]
+async fn migrate_legacy_state() {
+    let (harness, legacy) = migration_harness();
+    let mut context = harness.start().await;
+    let program_id = context.program_id;
+    // the harness' operator is also the config admin
+    let admin = context.operator.pubkey();
+    let migrate = MigrateExchangeState {
+        admin: admin,
+        legacy: legacy,
+        owner: admin,
+        strategy_id: 6,
+    };
+    let admin_lamports = context.banks_client.get_account(admin).await.unwrap().unwrap().lamports;
+    context.process(migrate.instruction(&program_id)).await.unwrap();
+
+    let (exchange, bump_seed) = exchange_address(&program_id, &admin, 6);
+    let account = context.banks_client.get_account(exchange).await.unwrap().unwrap();
+    assert_eq!(account.owner, program_id);
+    assert_eq!(
+        ExchangeState::unpack_from_slice(&account.data),
+        Ok(ExchangeState {
+            bump_seed: bump_seed,
+            strategy_id: 6,
+            owner: admin,
+            input_amount: 1_000,
+            exchange_out: 1_100,
+            total_profit: 100,
+            ..ExchangeState::default()
+        })
+    );
+    // the old account is closed, its rent offsets the new one
+    assert_eq!(context.banks_client.get_account(legacy).await.unwrap(), None);
+    let admin_account = context.banks_client.get_account(admin).await.unwrap().unwrap();
+    assert_eq!(
+        admin_account.lamports,
+        admin_lamports + Rent::default().minimum_balance(LEGACY_EXCHANGE_STATE_LEN)
+            - Rent::default().minimum_balance(ExchangeState::LEN)
+    );
+
+    // a versioned state has nothing to migrate
+    let migrate = MigrateExchangeState {
+        legacy: context.exchange,
+        strategy_id: 7,
+        ..migrate
+    };
+    assert_eq!(
+        context.process(migrate.instruction(&program_id)).await,
+        Err(TransactionError::InstructionError(0, InstructionError::AccountAlreadyInitialized))
+    );
+}
+
+#[tokio::test]
+async fn migrate_requires_admin_and_operator_owner() {
+    let (harness, legacy) = migration_harness();
+    let mut context = harness.start().await;
+    let program_id = context.program_id;
+    let admin = context.operator.pubkey();
+    let intruder = Keypair::new();
+    let migrate = MigrateExchangeState {
+        admin: intruder.pubkey(),
+        legacy: legacy,
+        owner: intruder.pubkey(),
+        strategy_id: 6,
+    };
+    assert_eq!(
+        context.process_with_signers(migrate.instruction(&program_id), &[&intruder]).await,
+        Err(instruction_error(ArbitrageError::UnauthorizedOperator))
+    );
+
+    // the admin cannot hand the state to someone outside the config
+    let migrate = MigrateExchangeState {
+        admin: admin,
+        legacy: legacy,
+        owner: intruder.pubkey(),
+        strategy_id: 6,
+    };
+    assert_eq!(
+        context.process(migrate.instruction(&program_id)).await,
+        Err(instruction_error(ArbitrageError::UnauthorizedOperator))
+    );
+    assert!(context.banks_client.get_account(legacy).await.unwrap().is_some());
+}
+
+#[test]
+fn exchange_strategies_only() {
+    let migrate = |strategy_id| {
+        MigrateExchangeState {
+            admin: Pubkey::new_unique(),
+            legacy: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            strategy_id: strategy_id,
+        }
+        .instruction(&Pubkey::new_unique())
+    };
+    for strategy_id in [0, 15, STRATEGY_ORCA_RAYDIUM_SERUM, STRATEGY_PATH] {
+        assert!(ArbitrageInstruction::unpack(&migrate(strategy_id).data).is_ok());
+    }
+    for strategy_id in [16, STRATEGY_SERUM_SELECT, STRATEGY_ROUTE, u8::MAX] {
+        assert_eq!(
+            ArbitrageInstruction::unpack(&migrate(strategy_id).data).err(),
+            Some(ProgramError::from(ArbitrageError::InvalidInstruction))
+        );
+    }
+}
+
+#[tokio::test]
+async fn initialize_exchange_state() {
+    // the harness' state sits at the pda of strategy 6
+    let mut harness = funded_harness();
+    let operator = harness.operator.pubkey();
+    let program_id = harness.program_id;
+    harness.exchange = exchange_address(&program_id, &operator, 6).0;
+    harness.exchange_state.strategy_id = 6;
+    let mut context = harness.start().await;
+
+    let initialize = InitializeExchangeState {
+        operator: operator,
+        strategy_id: STRATEGY_PATH,
+    };
+    context.process(initialize.instruction(&program_id)).await.unwrap();
+    let (exchange, bump_seed) = exchange_address(&program_id, &operator, STRATEGY_PATH);
+    let account = context.banks_client.get_account(exchange).await.unwrap().unwrap();
+    assert_eq!(account.owner, program_id);
+    assert_eq!(
+        ExchangeState::unpack_from_slice(&account.data),
+        Ok(ExchangeState {
+            bump_seed: bump_seed,
+            strategy_id: STRATEGY_PATH,
+            owner: operator,
+            ..ExchangeState::default()
+        })
+    );
+
+    let initialize = InitializeExchangeState {
+        operator: operator,
+        strategy_id: 6,
+    };
+    assert_eq!(
+        context.process(initialize.instruction(&program_id)).await,
+        Err(TransactionError::InstructionError(0, InstructionError::AccountAlreadyInitialized))
+    );
+
+    // the address must be the one of the signer and the strategy
+    let mut instruction = InitializeExchangeState {
+        operator: operator,
+        strategy_id: 1,
+    }
+    .instruction(&program_id);
+    instruction.accounts[2].pubkey = exchange_address(&program_id, &operator, 2).0;
+    assert_eq!(
+        context.process(instruction).await,
+        Err(TransactionError::InstructionError(0, InstructionError::InvalidSeeds))
+    );
+}
+
+#[tokio::test]
+async fn reset_and_close_exchange_state() {
+    let mut harness = Harness::new();
+    harness.exchange_state = ExchangeState {
+        strategy_id: 6,
+        input_amount: 1_000,
+        exchange_out: 1_100,
+        total_profit: 100,
+        total_lost: 1,
+        attempts: 3,
+        successes: 1,
+        skips: 1,
+        last_slot: 9,
+        ..harness.exchange_state
+    };
+    let mut context = harness.start().await;
+    let program_id = context.program_id;
+    let operator = context.operator.pubkey();
+    let exchange = context.exchange;
+
+    let reset = ResetExchangeState {
+        owner: operator,
+        exchange: exchange,
+        strategy_id: 6,
+    };
+    context.process(reset.instruction(&program_id)).await.unwrap();
+    assert_eq!(
+        context.exchange_state().await,
+        ExchangeState {
+            strategy_id: 6,
+            owner: operator,
+            ..ExchangeState::default()
+        }
+    );
+
+    let destination = Pubkey::new_unique();
+    let lamports = context.banks_client.get_account(exchange).await.unwrap().unwrap().lamports;
+    let close = CloseExchangeState {
+        owner: operator,
+        exchange: exchange,
+        destination: destination,
+        strategy_id: 6,
+    };
+    context.process(close.instruction(&program_id)).await.unwrap();
+    assert_eq!(context.banks_client.get_account(exchange).await.unwrap(), None);
+    let destination = context.banks_client.get_account(destination).await.unwrap().unwrap();
+    assert_eq!(destination.lamports, lamports);
+}
+
+#[tokio::test]
+async fn reset_and_close_require_the_owner() {
+    let mut harness = Harness::new();
+    harness.exchange_state.strategy_id = 6;
+    let mut context = harness.start().await;
+    let program_id = context.program_id;
+    let exchange = context.exchange;
+    let intruder = Keypair::new();
+
+    let reset = ResetExchangeState {
+        owner: intruder.pubkey(),
+        exchange: exchange,
+        strategy_id: 6,
+    };
+    assert_eq!(
+        context.process_with_signers(reset.instruction(&program_id), &[&intruder]).await,
+        Err(instruction_error(ArbitrageError::ExchangeOwnerMismatch))
+    );
+
+    let close = CloseExchangeState {
+        owner: intruder.pubkey(),
+        exchange: exchange,
+        destination: intruder.pubkey(),
+        strategy_id: 6,
+    };
+    assert_eq!(
+        context.process_with_signers(close.instruction(&program_id), &[&intruder]).await,
+        Err(instruction_error(ArbitrageError::ExchangeOwnerMismatch))
+    );
+
+    // the owner closing the state of another strategy
+    let operator = context.operator.pubkey();
+    let close = CloseExchangeState {
+        owner: operator,
+        exchange: exchange,
+        destination: operator,
+        strategy_id: 7,
+    };
+    assert_eq!(
+        context.process(close.instruction(&program_id)).await,
+        Err(instruction_error(ArbitrageError::StrategyMismatch))
+    );
+    assert!(context.banks_client.get_account(exchange).await.unwrap().is_some());
+}
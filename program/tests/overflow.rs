@@ -0,0 +1,200 @@
+//! Strategies driven with extreme reserves and accumulators, every overflow
+//! surfaces as an `ArbitrageError` instead of a panic or a wrapped amount.
+#![cfg(feature = "no-entrypoint")]
+
+mod common;
+
+use common::{instruction_error, Harness, MockPool, STRATEGY_PARAMS};
+use solana_arbitrage::{
+    client::*,
+    error::ArbitrageError,
+    math::{balance_decrease, balance_increase, checked_add, checked_div, checked_mul, mul_div},
+    state::ExchangeState,
+};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use solana_sdk::signature::Signer;
+
+const USER_USDC: u64 = 100_000_000_000;
+/// a quarter of the mock mints' supply, with the cheap pool's 10% on top
+const EXTREME_RESERVE: u64 = u64::MAX / 4;
+const EXTREME_CHEAP_UST_RESERVE: u64 = EXTREME_RESERVE / 10 * 11;
+
+struct OverflowCase {
+    harness: Harness,
+    user: StableUserAccounts,
+    saber: MockPool,
+    other: MockPool,
+}
+
+/// saber prices ust 10% cheaper than the pool of `other_program`
+fn overflow_case(other_program: fn(&Harness) -> Pubkey, reserve: u64, cheap_ust_reserve: u64, extra_tokens: usize) -> OverflowCase {
+    let mut harness = Harness::new();
+    let usdc = harness.add_mint();
+    let ust = harness.add_mint();
+    let user = StableUserAccounts {
+        owner: harness.operator.pubkey(),
+        user_usdc: harness.add_user_account(&usdc, USER_USDC),
+        user_ust: harness.add_user_account(&ust, 0),
+    };
+    let program = harness.mocks.saber;
+    let saber = harness.add_pool(&program, &[(usdc, reserve), (ust, cheap_ust_reserve)]);
+    let program = other_program(&harness);
+    let mut reserves = if program == harness.mocks.whirl {
+        vec![(ust, reserve), (usdc, reserve)]
+    } else {
+        vec![(usdc, reserve), (ust, reserve)]
+    };
+    for _ in 0..extra_tokens {
+        let mint = harness.add_mint();
+        reserves.push((mint, reserve));
+    }
+    let other = harness.add_pool(&program, &reserves);
+    OverflowCase {
+        harness: harness,
+        user: user,
+        saber: saber,
+        other: other,
+    }
+}
+
+fn saber_whirl(case: &OverflowCase, reversed: bool, flag: u8) -> SaberWhirlExchange {
+    SaberWhirlExchange {
+        exchange: case.harness.exchange,
+        saber: case.saber.saber(),
+        whirl: case.other.whirl(),
+        user: case.user.clone(),
+        slot: 6,
+        reversed: reversed,
+        flag: flag,
+        begin_run: false,
+        min_amounts_out: [0, 0],
+    }
+}
+
+/// a running saber/whirl state, past the reset and above slot 6's threshold
+fn running_state(case: &OverflowCase, total_profit: u64, total_lost: u64) -> ExchangeState {
+    let (input_amount, _, expected_profit) = STRATEGY_PARAMS[6];
+    ExchangeState {
+        strategy_id: 6,
+        input_amount: input_amount,
+        exchange_out: expected_profit,
+        total_profit: total_profit,
+        total_lost: total_lost,
+        ..case.harness.exchange_state
+    }
+}
+
+#[test]
+fn checked_helpers() {
+    let overflow = Err(ProgramError::from(ArbitrageError::MathOverflow));
+    assert_eq!(checked_add(u64::MAX, 1), overflow);
+    assert_eq!(checked_mul(u64::MAX / 2, 3), overflow);
+    assert_eq!(checked_div(1, 0), overflow);
+    assert_eq!(mul_div(u64::MAX, 10, 10), Ok(u64::MAX));
+    assert_eq!(mul_div(u64::MAX, 11, 10), overflow);
+    assert_eq!(mul_div(1, 1, 0), overflow);
+    assert_eq!(balance_increase(5, 7), Ok(2));
+    assert_eq!(balance_increase(7, 5), Err(ArbitrageError::BalanceDecreased.into()));
+    assert_eq!(balance_decrease(7, 5), Ok(2));
+    assert_eq!(balance_decrease(5, 7), overflow);
+}
+
+#[tokio::test]
+async fn profit_accumulator_overflow() {
+    let mut case = overflow_case(|harness| harness.mocks.whirl, 1_000_000_000_000, 1_100_000_000_000, 0);
+    case.harness.exchange_state = running_state(&case, u64::MAX, 0);
+    let instruction = saber_whirl(&case, false, 1).instruction(&case.harness.program_id).unwrap();
+    let mut context = case.harness.start().await;
+    assert_eq!(
+        context.process(instruction).await,
+        Err(instruction_error(ArbitrageError::MathOverflow))
+    );
+}
+
+#[tokio::test]
+async fn loss_accumulator_overflow() {
+    let mut case = overflow_case(|harness| harness.mocks.whirl, 1_000_000_000_000, 1_100_000_000_000, 0);
+    case.harness.exchange_state = running_state(&case, 0, u64::MAX);
+    let instruction = saber_whirl(&case, true, 1).instruction(&case.harness.program_id).unwrap();
+    let mut context = case.harness.start().await;
+    assert_eq!(
+        context.process(instruction).await,
+        Err(instruction_error(ArbitrageError::MathOverflow))
+    );
+}
+
+#[tokio::test]
+async fn extreme_thresholds_overflow() {
+    // more than twice the normal input puts the threshold four times the params'
+    let mut case = overflow_case(|harness| harness.mocks.whirl, 1_000_000_000_000, 1_100_000_000_000, 0);
+    let (input_amount, _, expected_profit) = STRATEGY_PARAMS[6];
+    case.harness.set_strategy(6, true, input_amount, u64::MAX / 2, expected_profit);
+    case.harness.exchange_state = ExchangeState {
+        input_amount: input_amount * 3,
+        ..running_state(&case, 0, 0)
+    };
+    let instruction = saber_whirl(&case, false, 1).instruction(&case.harness.program_id).unwrap();
+    let mut context = case.harness.start().await;
+    assert_eq!(
+        context.process(instruction).await,
+        Err(instruction_error(ArbitrageError::MathOverflow))
+    );
+}
+
+#[tokio::test]
+async fn extreme_reserves() {
+    for max_amount_in in [0, USER_USDC] {
+        let case = overflow_case(|harness| harness.mocks.mercurial, EXTREME_RESERVE, EXTREME_CHEAP_UST_RESERVE, 1);
+        let builder = SaberMercurialExchange {
+            exchange: case.harness.exchange,
+            saber: case.saber.saber(),
+            mercurial: case.other.mercurial(),
+            user: case.user.clone(),
+            slot: 0,
+            reversed: false,
+            flag: 0,
+            begin_run: false,
+            max_amount_in: max_amount_in,
+            min_amounts_out: [0, 0],
+        };
+        let instruction = builder.instruction(&case.harness.program_id).unwrap();
+        let user = case.user.clone();
+        let mut context = case.harness.start().await;
+        context.process(instruction).await.unwrap();
+
+        let usdc_after = context.token_balance(&user.user_usdc).await;
+        let state = context.exchange_state().await;
+        assert!(usdc_after > USER_USDC);
+        assert_eq!(state.total_profit, usdc_after - USER_USDC);
+        assert_eq!(state.total_lost, 0);
+    }
+}
+
+#[tokio::test]
+async fn near_empty_pools() {
+    // neither pool can fill the slot's input, the quote skips the swap
+    for max_amount_in in [0, USER_USDC] {
+        let case = overflow_case(|harness| harness.mocks.mercurial, 10, 11, 1);
+        let builder = SaberMercurialExchange {
+            exchange: case.harness.exchange,
+            saber: case.saber.saber(),
+            mercurial: case.other.mercurial(),
+            user: case.user.clone(),
+            slot: 0,
+            reversed: false,
+            flag: 0,
+            begin_run: false,
+            max_amount_in: max_amount_in,
+            min_amounts_out: [0, 0],
+        };
+        let instruction = builder.instruction(&case.harness.program_id).unwrap();
+        let user = case.user.clone();
+        let mut context = case.harness.start().await;
+        context.process(instruction).await.unwrap();
+
+        assert_eq!(context.token_balance(&user.user_usdc).await, USER_USDC);
+        let state = context.exchange_state().await;
+        assert_eq!(state.total_profit, 0);
+        assert_eq!(state.total_lost, 0);
+    }
+}
@@ -0,0 +1,229 @@
+#![cfg(feature = "no-entrypoint")]
+
+mod common;
+
+use common::{instruction_error, mock_amount_out, Harness, MOCK_SLIPPAGE};
+use solana_arbitrage::{client::*, error::ArbitrageError, event::STRATEGY_PATH, instruction::Market, state::ExchangeState};
+use solana_program::{instruction::{AccountMeta, InstructionError}, pubkey::Pubkey};
+use solana_sdk::{signature::Signer, transaction::TransactionError};
+
+const RESERVE_IN: u64 = 1_000_000_000_000;
+const RESERVE_OUT: u64 = 1_100_000_000_000;
+const AMOUNT_IN: u64 = 1_000_000_000;
+
+#[derive(Clone, Copy, Debug)]
+enum Venue {
+    Orca,
+    Saber,
+    Mercurial(usize),
+    Raydium,
+    Whirl,
+    Crema,
+}
+
+struct PathCase {
+    harness: Harness,
+    user_source: Pubkey,
+    user_destination: Pubkey,
+    market: Market,
+    side: u8,
+    hop_accounts: Vec<AccountMeta>,
+}
+
+/// a pool of `venue` quoting RESERVE_IN source tokens against RESERVE_OUT destination tokens
+fn path_case(venue: Venue) -> PathCase {
+    let mut harness = Harness::new();
+    let source_mint = harness.add_mint();
+    let destination_mint = harness.add_mint();
+    let user_source = harness.add_user_account(&source_mint, AMOUNT_IN * 10);
+    let user_destination = harness.add_user_account(&destination_mint, 0);
+    let owner = harness.operator.pubkey();
+    let reserves = vec![(source_mint, RESERVE_IN), (destination_mint, RESERVE_OUT)];
+
+    let (market, side, hop_accounts) = match venue {
+        Venue::Orca => {
+            let program = harness.mocks.token_swap;
+            let pool = harness.add_pool(&program, &reserves);
+            (Market::Orca, 0, pool.orca().hop_accounts(&owner, &user_source, &user_destination, true))
+        }
+        Venue::Saber => {
+            let program = harness.mocks.saber;
+            let pool = harness.add_pool(&program, &reserves);
+            (Market::Saber, 0, pool.saber().hop_accounts(&owner, &user_source, &user_destination, true))
+        }
+        Venue::Mercurial(n) => {
+            let mut reserves = reserves;
+            for _ in 2..n {
+                let mint = harness.add_mint();
+                reserves.push((mint, RESERVE_IN));
+            }
+            let program = harness.mocks.mercurial;
+            let pool = harness.add_pool(&program, &reserves);
+            let mercurial = pool.mercurial();
+            (
+                mercurial.market().unwrap(),
+                0,
+                mercurial.hop_accounts(&owner, &user_source, &user_destination),
+            )
+        }
+        Venue::Raydium => {
+            let program = harness.mocks.raydium;
+            let pool = harness.add_pool(&program, &reserves);
+            (Market::Raydium, 0, pool.raydium().hop_accounts(&owner, &user_source, &user_destination))
+        }
+        Venue::Whirl => {
+            let program = harness.mocks.whirl;
+            let pool = harness.add_pool(&program, &reserves);
+            (Market::Whirl, 1, pool.whirl().hop_accounts(&owner, &user_source, &user_destination))
+        }
+        Venue::Crema => {
+            let program = harness.mocks.crema;
+            let pool = harness.add_pool(&program, &reserves);
+            (Market::Crema, 0, pool.crema().hop_accounts(&owner, &user_source, &user_destination, true))
+        }
+    };
+    harness.exchange_state.strategy_id = STRATEGY_PATH;
+    PathCase {
+        harness: harness,
+        user_source: user_source,
+        user_destination: user_destination,
+        market: market,
+        side: side,
+        hop_accounts: hop_accounts,
+    }
+}
+
+fn path_instruction(case: &PathCase, flag: u8, minimum_amount_out: u64) -> PathExchange {
+    PathExchange {
+        operator: case.harness.operator.pubkey(),
+        exchange: case.harness.exchange,
+        flag: flag,
+        amount: AMOUNT_IN,
+        market: case.market,
+        side: case.side,
+        minimum_amount_out: minimum_amount_out,
+        hop_accounts: case.hop_accounts.clone(),
+    }
+}
+
+#[tokio::test]
+async fn path_exchange_on_every_venue() {
+    let venues = [
+        Venue::Orca,
+        Venue::Saber,
+        Venue::Mercurial(2),
+        Venue::Mercurial(3),
+        Venue::Mercurial(4),
+        Venue::Raydium,
+        Venue::Whirl,
+        Venue::Crema,
+    ];
+    for venue in venues {
+        let case = path_case(venue);
+        let instruction = path_instruction(&case, 0, 0);
+        let (user_source, user_destination) = (case.user_source, case.user_destination);
+        let mut context = case.harness.start().await;
+        let program_id = context.program_id;
+        context.process(instruction.instruction(&program_id)).await.unwrap();
+
+        let expected_out = mock_amount_out(RESERVE_IN, RESERVE_OUT, AMOUNT_IN);
+        let state = context.exchange_state().await;
+        assert_eq!(state.input_amount, AMOUNT_IN, "{:?}", venue);
+        assert_eq!(state.exchange_out, expected_out, "{:?}", venue);
+        assert_eq!(context.token_balance(&user_source).await, AMOUNT_IN * 9, "{:?}", venue);
+        assert_eq!(context.token_balance(&user_destination).await, expected_out, "{:?}", venue);
+    }
+}
+
+#[tokio::test]
+async fn path_exchange_forwards_minimum_amount_out() {
+    let case = path_case(Venue::Saber);
+    let expected_out = mock_amount_out(RESERVE_IN, RESERVE_OUT, AMOUNT_IN);
+    let instruction = path_instruction(&case, 0, expected_out + 1);
+    let user_source = case.user_source;
+    let mut context = case.harness.start().await;
+    let program_id = context.program_id;
+    assert_eq!(
+        context.process(instruction.instruction(&program_id)).await,
+        Err(TransactionError::InstructionError(0, InstructionError::Custom(MOCK_SLIPPAGE)))
+    );
+    assert_eq!(context.token_balance(&user_source).await, AMOUNT_IN * 10);
+}
+
+#[tokio::test]
+async fn path_exchange_final_hop_rejects_loss() {
+    // flag 2 swaps the previous hop's output and compares the result with the
+    // input the first hop stored, twice the hop input here
+    let case = path_case(Venue::Orca);
+    let instruction = path_instruction(&case, 2, 0);
+    let mut harness = case.harness;
+    harness.exchange_state = ExchangeState {
+        input_amount: AMOUNT_IN * 2,
+        exchange_out: AMOUNT_IN,
+        ..harness.exchange_state
+    };
+    let mut context = harness.start().await;
+    let program_id = context.program_id;
+    assert_eq!(
+        context.process(instruction.instruction(&program_id)).await,
+        Err(instruction_error(ArbitrageError::ProfitBelowMinimum))
+    );
+}
+
+#[tokio::test]
+async fn path_exchange_rejects_unstarted_state() {
+    // a later hop with no flag 0 hop before it
+    let case = path_case(Venue::Orca);
+    let instruction = path_instruction(&case, 1, 0);
+    let mut context = case.harness.start().await;
+    let program_id = context.program_id;
+    assert_eq!(
+        context.process(instruction.instruction(&program_id)).await,
+        Err(instruction_error(ArbitrageError::StaleState))
+    );
+}
+
+#[tokio::test]
+async fn path_exchange_checks_state_binding() {
+    // the state belongs to another operator
+    let mut case = path_case(Venue::Orca);
+    case.harness.exchange_state.owner = Pubkey::new_unique();
+    let instruction = path_instruction(&case, 0, 0);
+    let mut context = case.harness.start().await;
+    let program_id = context.program_id;
+    assert_eq!(
+        context.process(instruction.instruction(&program_id)).await,
+        Err(instruction_error(ArbitrageError::ExchangeOwnerMismatch))
+    );
+
+    // the state was set up for a stable strategy
+    let mut case = path_case(Venue::Orca);
+    case.harness.exchange_state.strategy_id = 6;
+    let instruction = path_instruction(&case, 0, 0);
+    let mut context = case.harness.start().await;
+    let program_id = context.program_id;
+    assert_eq!(
+        context.process(instruction.instruction(&program_id)).await,
+        Err(instruction_error(ArbitrageError::StrategyMismatch))
+    );
+}
+
+#[tokio::test]
+async fn path_exchange_only_invokes_the_venue_program() {
+    // an orca hop pointed at another program and a pool state that program owns
+    let mut case = path_case(Venue::Orca);
+    let program = case.harness.mocks.saber;
+    let (mint, other_mint) = (case.harness.add_mint(), case.harness.add_mint());
+    let pool = case.harness.add_pool(&program, &[(mint, RESERVE_IN), (other_mint, RESERVE_OUT)]);
+    case.hop_accounts[0].pubkey = pool.program;
+    case.hop_accounts[1].pubkey = pool.market;
+    let instruction = path_instruction(&case, 0, 0);
+    let user_source = case.user_source;
+    let mut context = case.harness.start().await;
+    let program_id = context.program_id;
+    assert_eq!(
+        context.process(instruction.instruction(&program_id)).await,
+        Err(instruction_error(ArbitrageError::InvalidProgramId))
+    );
+    assert_eq!(context.token_balance(&user_source).await, AMOUNT_IN * 10);
+}
@@ -0,0 +1,179 @@
+use solana_arbitrage::{
+    error::ArbitrageError,
+    quote::{constant_product_quote, ConstantProductPool, PoolFees},
+};
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+use spl_token::state::{Account as TokenAccount, AccountState};
+use spl_token_swap::{
+    curve::{
+        base::{CurveType, SwapCurve},
+        calculator::TradeDirection,
+        fees::Fees,
+    },
+    state::{SwapV1, SwapVersion},
+};
+
+/// orca's 0.25% trade fee and 0.05% owner fee
+const ORCA_FEES: PoolFees = PoolFees {
+    trade_fee_numerator: 25,
+    trade_fee_denominator: 10000,
+    owner_trade_fee_numerator: 5,
+    owner_trade_fee_denominator: 10000,
+};
+
+fn swap_fees(fees: &PoolFees) -> Fees {
+    Fees {
+        trade_fee_numerator: fees.trade_fee_numerator,
+        trade_fee_denominator: fees.trade_fee_denominator,
+        owner_trade_fee_numerator: fees.owner_trade_fee_numerator,
+        owner_trade_fee_denominator: fees.owner_trade_fee_denominator,
+        owner_withdraw_fee_numerator: 0,
+        owner_withdraw_fee_denominator: 0,
+        host_fee_numerator: 0,
+        host_fee_denominator: 0,
+    }
+}
+
+fn curve(curve_type: CurveType) -> SwapCurve {
+    let mut data = [0u8; SwapCurve::LEN];
+    data[0] = curve_type as u8;
+    SwapCurve::unpack_from_slice(&data).unwrap()
+}
+
+#[test]
+fn matches_spl_token_swap_curve() {
+    let swap_curve = curve(CurveType::ConstantProduct);
+    let no_fees = PoolFees {
+        trade_fee_numerator: 0,
+        trade_fee_denominator: 1,
+        owner_trade_fee_numerator: 0,
+        owner_trade_fee_denominator: 1,
+    };
+    let reserves = [3u64, 1_000_000, 1_000_000_000, 123_456_789_012, u64::MAX / 2];
+    let amounts = [1u64, 7, 999, 1_000_000, 1_000_000_000_000];
+    for fees in [ORCA_FEES, no_fees] {
+        for reserve_in in reserves {
+            for reserve_out in reserves {
+                for amount_in in amounts {
+                    let expected = swap_curve.swap(
+                        amount_in as u128,
+                        reserve_in as u128,
+                        reserve_out as u128,
+                        TradeDirection::AtoB,
+                        &swap_fees(&fees),
+                    );
+                    let quote = constant_product_quote(amount_in, reserve_in, reserve_out, &fees);
+                    let case = (amount_in, reserve_in, reserve_out, fees);
+                    match expected {
+                        None => assert_eq!(quote, None, "{:?}", case),
+                        Some(expected) => {
+                            let quote = quote.unwrap_or_else(|| panic!("{:?}", case));
+                            assert_eq!(quote.amount_out as u128, expected.destination_amount_swapped, "{:?}", case);
+                            assert_eq!(quote.amount_in as u128, expected.source_amount_swapped, "{:?}", case);
+                            assert_eq!(quote.fee as u128, expected.trade_fee + expected.owner_fee, "{:?}", case);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn fee_is_at_least_one_token() {
+    let quote = constant_product_quote(10, 1_000_000, 1_000_000, &ORCA_FEES).unwrap();
+    assert_eq!(quote.fee, 2);
+    assert!(quote.amount_out < 10);
+}
+
+struct PoolAccounts {
+    program_id: Pubkey,
+    keys: [Pubkey; 3],
+    data: [Vec<u8>; 3],
+    lamports: [u64; 3],
+    owners: [Pubkey; 3],
+}
+
+impl PoolAccounts {
+    fn new(curve_type: CurveType, reserve_a: u64, reserve_b: u64) -> Self {
+        let program_id = Pubkey::new_unique();
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let swap = SwapVersion::SwapV1(SwapV1 {
+            is_initialized: true,
+            bump_seed: 255,
+            token_program_id: spl_token::id(),
+            token_a: keys[1],
+            token_b: keys[2],
+            pool_mint: Pubkey::new_unique(),
+            token_a_mint: Pubkey::new_unique(),
+            token_b_mint: Pubkey::new_unique(),
+            pool_fee_account: Pubkey::new_unique(),
+            fees: swap_fees(&ORCA_FEES),
+            swap_curve: curve(curve_type),
+        });
+        let mut swap_data = vec![0u8; SwapVersion::LATEST_LEN];
+        SwapVersion::pack(swap, &mut swap_data).unwrap();
+        let vault = |amount: u64| {
+            let mut data = vec![0u8; TokenAccount::LEN];
+            TokenAccount::pack(
+                TokenAccount {
+                    mint: Pubkey::new_unique(),
+                    owner: Pubkey::new_unique(),
+                    amount: amount,
+                    state: AccountState::Initialized,
+                    ..TokenAccount::default()
+                },
+                &mut data,
+            )
+            .unwrap();
+            data
+        };
+        PoolAccounts {
+            program_id: program_id,
+            keys: keys,
+            data: [swap_data, vault(reserve_a), vault(reserve_b)],
+            lamports: [0; 3],
+            owners: [program_id, spl_token::id(), spl_token::id()],
+        }
+    }
+
+    fn accounts(&mut self) -> Vec<AccountInfo> {
+        self.keys
+            .iter()
+            .zip(self.data.iter_mut())
+            .zip(self.lamports.iter_mut())
+            .zip(self.owners.iter())
+            .map(|(((key, data), lamports), owner)| AccountInfo::new(key, false, false, lamports, data, owner, false, 0))
+            .collect()
+    }
+}
+
+#[test]
+fn load_reads_fees_and_reserves() {
+    let mut pool_accounts = PoolAccounts::new(CurveType::ConstantProduct, 2_000_000, 5_000_000);
+    let program_id = pool_accounts.program_id;
+    let accounts = pool_accounts.accounts();
+    let pool = ConstantProductPool::load(&program_id, &accounts[0], &accounts[1], &accounts[2]).unwrap();
+    assert_eq!(pool.reserve_a, 2_000_000);
+    assert_eq!(pool.reserve_b, 5_000_000);
+    assert_eq!(pool.fees, ORCA_FEES);
+    assert_eq!(pool.quote(1_000, true), constant_product_quote(1_000, 2_000_000, 5_000_000, &ORCA_FEES));
+    assert_eq!(pool.quote(1_000, false), constant_product_quote(1_000, 5_000_000, 2_000_000, &ORCA_FEES));
+
+    // vaults swapped
+    assert_eq!(
+        ConstantProductPool::load(&program_id, &accounts[0], &accounts[2], &accounts[1]),
+        Err(ArbitrageError::PoolAccountMismatch.into())
+    );
+}
+
+#[test]
+fn load_rejects_other_curves() {
+    let mut pool_accounts = PoolAccounts::new(CurveType::Stable, 2_000_000, 5_000_000);
+    let program_id = pool_accounts.program_id;
+    let accounts = pool_accounts.accounts();
+    assert_eq!(
+        ConstantProductPool::load(&program_id, &accounts[0], &accounts[1], &accounts[2]),
+        Err(ProgramError::from(ArbitrageError::UnsupportedCurve))
+    );
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "no-entrypoint")]
+
+mod common;
+
+use common::{instruction_error, mock_amount_out, Harness};
+use solana_arbitrage::{client::*, error::ArbitrageError, instruction::Market};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+const RESERVE: u64 = 1_000_000_000_000;
+const AMOUNT_IN: u64 = 1_000_000_000;
+
+struct RouteCase {
+    harness: Harness,
+    user_usdc: Pubkey,
+    user_ust: Pubkey,
+    route: ExecuteRoute,
+}
+
+/// usdc -> ust on saber, ust -> usdc on orca; `saber_ust` sets saber's ust reserve
+fn route_case(saber_ust: u64) -> RouteCase {
+    let mut harness = Harness::new();
+    let usdc = harness.add_mint();
+    let ust = harness.add_mint();
+    let user_usdc = harness.add_user_account(&usdc, AMOUNT_IN * 10);
+    let user_ust = harness.add_user_account(&ust, 0);
+    let owner = harness.operator.pubkey();
+    let saber_program = harness.mocks.saber;
+    let saber = harness.add_pool(&saber_program, &[(usdc, RESERVE), (ust, saber_ust)]);
+    let orca_program = harness.mocks.token_swap;
+    let orca = harness.add_pool(&orca_program, &[(ust, RESERVE), (usdc, RESERVE)]);
+
+    let route = ExecuteRoute {
+        operator: owner,
+        amount_in: AMOUNT_IN,
+        hops: vec![
+            RouteHopAccounts {
+                market: Market::Saber,
+                side: 0,
+                minimum_amount_out: 0,
+                accounts: saber.saber().hop_accounts(&owner, &user_usdc, &user_ust, true),
+            },
+            RouteHopAccounts {
+                market: Market::Orca,
+                side: 0,
+                minimum_amount_out: 0,
+                accounts: orca.orca().hop_accounts(&owner, &user_ust, &user_usdc, true),
+            },
+        ],
+    };
+    RouteCase {
+        harness: harness,
+        user_usdc: user_usdc,
+        user_ust: user_ust,
+        route: route,
+    }
+}
+
+#[tokio::test]
+async fn route_feeds_each_hop_the_previous_output() {
+    let saber_ust = RESERVE * 11 / 10;
+    let case = route_case(saber_ust);
+    let (user_usdc, user_ust) = (case.user_usdc, case.user_ust);
+    let mut context = case.harness.start().await;
+    let program_id = context.program_id;
+    context.process(case.route.instruction(&program_id).unwrap()).await.unwrap();
+
+    let ust_out = mock_amount_out(RESERVE, saber_ust, AMOUNT_IN);
+    let usdc_out = mock_amount_out(RESERVE, RESERVE, ust_out);
+    assert!(usdc_out > AMOUNT_IN);
+    assert_eq!(context.token_balance(&user_usdc).await, AMOUNT_IN * 9 + usdc_out);
+    assert_eq!(context.token_balance(&user_ust).await, 0);
+}
+
+#[tokio::test]
+async fn route_rejects_unprofitable_cycle() {
+    let case = route_case(RESERVE);
+    let user_usdc = case.user_usdc;
+    let mut context = case.harness.start().await;
+    let program_id = context.program_id;
+    assert_eq!(
+        context.process(case.route.instruction(&program_id).unwrap()).await,
+        Err(instruction_error(ArbitrageError::ProfitBelowMinimum))
+    );
+    assert_eq!(context.token_balance(&user_usdc).await, AMOUNT_IN * 10);
+}
+
+#[tokio::test]
+async fn route_rejects_hop_with_wrong_account_count() {
+    let mut case = route_case(RESERVE * 11 / 10);
+    case.route.hops[0].accounts.push(case.route.hops[1].accounts[0].clone());
+    let mut context = case.harness.start().await;
+    let program_id = context.program_id;
+    assert_eq!(
+        context.process(case.route.instruction(&program_id).unwrap()).await,
+        Err(instruction_error(ArbitrageError::InvalidInstruction))
+    );
+}
+
+#[tokio::test]
+async fn route_rejects_cycle_ending_in_another_mint() {
+    let mut case = route_case(RESERVE * 11 / 10);
+    case.route.hops.pop();
+    let user_usdc = case.user_usdc;
+    let mut context = case.harness.start().await;
+    let program_id = context.program_id;
+    assert_eq!(
+        context.process(case.route.instruction(&program_id).unwrap()).await,
+        Err(instruction_error(ArbitrageError::MintMismatch))
+    );
+    assert_eq!(context.token_balance(&user_usdc).await, AMOUNT_IN * 10);
+}
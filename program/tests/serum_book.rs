@@ -0,0 +1,223 @@
+use solana_arbitrage::{
+    error::ArbitrageError,
+    serum_book::{BookDepth, OrderBookLevel, SerumBookReader},
+};
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+const COIN_LOT_SIZE: u64 = 100_000;
+const PC_LOT_SIZE: u64 = 10;
+
+const FLAG_INITIALIZED: u64 = 1 << 0;
+const FLAG_MARKET: u64 = 1 << 1;
+const FLAG_BIDS: u64 = 1 << 5;
+const FLAG_ASKS: u64 = 1 << 6;
+
+fn dex_account(body: Vec<u8>) -> Vec<u8> {
+    let mut data = b"serum".to_vec();
+    data.extend(body);
+    data.extend(b"padding");
+    data
+}
+
+fn market_data(market: &Pubkey, bids: &Pubkey, asks: &Pubkey) -> Vec<u8> {
+    let mut state = vec![0u8; 376];
+    state[0..8].copy_from_slice(&(FLAG_INITIALIZED | FLAG_MARKET).to_le_bytes());
+    state[8..40].copy_from_slice(market.as_ref());
+    state[280..312].copy_from_slice(bids.as_ref());
+    state[312..344].copy_from_slice(asks.as_ref());
+    state[344..352].copy_from_slice(&COIN_LOT_SIZE.to_le_bytes());
+    state[352..360].copy_from_slice(&PC_LOT_SIZE.to_le_bytes());
+    dex_account(state)
+}
+
+fn leaf(price: u64, sequence: u64, quantity: u64) -> Vec<u8> {
+    let mut node = vec![0u8; 72];
+    node[0..4].copy_from_slice(&2u32.to_le_bytes());
+    let key = (price as u128) << 64 | sequence as u128;
+    node[8..24].copy_from_slice(&key.to_le_bytes());
+    node[56..64].copy_from_slice(&quantity.to_le_bytes());
+    node
+}
+
+fn inner(low: u32, high: u32) -> Vec<u8> {
+    let mut node = vec![0u8; 72];
+    node[0..4].copy_from_slice(&1u32.to_le_bytes());
+    node[24..28].copy_from_slice(&low.to_le_bytes());
+    node[28..32].copy_from_slice(&high.to_le_bytes());
+    node
+}
+
+/// a chain of inner nodes, each holding the next lowest order on its low side
+fn book_data(side_flag: u64, orders: &[(u64, u64)]) -> Vec<u8> {
+    let mut keyed: Vec<(u64, u64, u64)> = orders
+        .iter()
+        .enumerate()
+        .map(|(sequence, (price, quantity))| (*price, sequence as u64, *quantity))
+        .collect();
+    keyed.sort();
+
+    // the root is node 0: the single leaf, or inner nodes 0..n-1 followed by the leaves
+    let mut nodes: Vec<Vec<u8>> = vec![];
+    let n = keyed.len();
+    if n > 1 {
+        let leaf_index = |i: usize| (n - 1 + i) as u32;
+        for i in 0..n - 1 {
+            let high = if i == n - 2 { leaf_index(n - 1) } else { (i + 1) as u32 };
+            nodes.push(inner(leaf_index(i), high));
+        }
+    }
+    for (price, sequence, quantity) in keyed.iter() {
+        nodes.push(leaf(*price, *sequence, *quantity));
+    }
+
+    let mut body = (FLAG_INITIALIZED | side_flag).to_le_bytes().to_vec();
+    body.extend((nodes.len() as u64).to_le_bytes());
+    body.extend(0u64.to_le_bytes());
+    body.extend(0u32.to_le_bytes());
+    body.extend(0u32.to_le_bytes());
+    body.extend((orders.len() as u64).to_le_bytes());
+    for node in nodes {
+        body.extend(node);
+    }
+    dex_account(body)
+}
+
+struct Book {
+    program_id: Pubkey,
+    keys: [Pubkey; 3],
+    data: [Vec<u8>; 3],
+    lamports: [u64; 3],
+}
+
+impl Book {
+    fn new(bids: &[(u64, u64)], asks: &[(u64, u64)]) -> Self {
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        Book {
+            program_id: Pubkey::new_unique(),
+            data: [
+                market_data(&keys[0], &keys[1], &keys[2]),
+                book_data(FLAG_BIDS, bids),
+                book_data(FLAG_ASKS, asks),
+            ],
+            keys: keys,
+            lamports: [0; 3],
+        }
+    }
+
+    fn accounts(&mut self) -> Vec<AccountInfo> {
+        let program_id = &self.program_id;
+        self.keys
+            .iter()
+            .zip(self.data.iter_mut())
+            .zip(self.lamports.iter_mut())
+            .map(|((key, data), lamports)| AccountInfo::new(key, false, false, lamports, data, program_id, false, 0))
+            .collect()
+    }
+}
+
+#[test]
+fn best_levels_and_depth() {
+    let mut book = Book::new(
+        &[(98, 5), (99, 3), (97, 1), (99, 2)],
+        &[(102, 4), (101, 6), (103, 1)],
+    );
+    let program_id = book.program_id;
+    let accounts = book.accounts();
+    let reader = SerumBookReader::load(&program_id, &accounts[0], &accounts[1], &accounts[2]).unwrap();
+    assert_eq!(reader.coin_lot_size, COIN_LOT_SIZE);
+    assert_eq!(reader.pc_lot_size, PC_LOT_SIZE);
+
+    // both orders at 99 make up the best bid
+    assert_eq!(reader.best_bid().unwrap(), OrderBookLevel { price: 99, quantity: 5 });
+    assert_eq!(reader.best_ask().unwrap(), OrderBookLevel { price: 101, quantity: 6 });
+    assert_eq!(
+        reader.bids(2).unwrap(),
+        vec![OrderBookLevel { price: 99, quantity: 5 }, OrderBookLevel { price: 98, quantity: 5 }]
+    );
+    assert_eq!(reader.asks(10).unwrap().len(), 3);
+
+    assert_eq!(reader.base_amount(5).unwrap(), 5 * COIN_LOT_SIZE);
+    assert_eq!(reader.quote_amount(&reader.best_ask().unwrap()).unwrap(), 101 * 6 * PC_LOT_SIZE);
+    assert_eq!(reader.normalized_price(101, 1_000_000).unwrap(), 101 * PC_LOT_SIZE * 1_000_000 / COIN_LOT_SIZE);
+}
+
+#[test]
+fn depth_up_to_price_limit() {
+    let mut book = Book::new(
+        &[(98, 5), (99, 3), (97, 1), (99, 2)],
+        &[(102, 4), (101, 6), (103, 1)],
+    );
+    let program_id = book.program_id;
+    let accounts = book.accounts();
+    let reader = SerumBookReader::load(&program_id, &accounts[0], &accounts[1], &accounts[2]).unwrap();
+    // a price of p lots normalizes to p * 100 with a base unit of 1_000_000
+    assert_eq!(
+        reader.bids_depth(9_800, 1_000_000).unwrap(),
+        BookDepth {
+            base_amount: 10 * COIN_LOT_SIZE,
+            quote_amount: (99 * 5 + 98 * 5) * PC_LOT_SIZE,
+        }
+    );
+    assert_eq!(
+        reader.asks_depth(10_200, 1_000_000).unwrap(),
+        BookDepth {
+            base_amount: 10 * COIN_LOT_SIZE,
+            quote_amount: (101 * 6 + 102 * 4) * PC_LOT_SIZE,
+        }
+    );
+    // limits inside the spread fill nothing
+    assert_eq!(reader.bids_depth(10_000, 1_000_000).unwrap(), BookDepth::default());
+    assert_eq!(reader.asks_depth(10_000, 1_000_000).unwrap(), BookDepth::default());
+}
+
+#[test]
+fn empty_side_is_an_error() {
+    let mut book = Book::new(&[(99, 1)], &[]);
+    let program_id = book.program_id;
+    let accounts = book.accounts();
+    let reader = SerumBookReader::load(&program_id, &accounts[0], &accounts[1], &accounts[2]).unwrap();
+    assert_eq!(reader.best_bid().unwrap(), OrderBookLevel { price: 99, quantity: 1 });
+    assert_eq!(reader.best_ask(), Err(ArbitrageError::EmptyOrderBook.into()));
+    assert!(reader.asks(3).unwrap().is_empty());
+}
+
+#[test]
+fn rejects_books_of_another_market() {
+    let mut book = Book::new(&[(99, 1)], &[(101, 1)]);
+    let program_id = book.program_id;
+    let accounts = book.accounts();
+    assert_eq!(
+        SerumBookReader::load(&program_id, &accounts[0], &accounts[2], &accounts[1]).err(),
+        Some(ArbitrageError::InvalidOrderBook.into())
+    );
+    assert_eq!(
+        SerumBookReader::load(&Pubkey::new_unique(), &accounts[0], &accounts[1], &accounts[2]).err(),
+        Some(ProgramError::from(ArbitrageError::InvalidOwner))
+    );
+}
+
+#[test]
+fn rejects_out_of_range_nodes() {
+    let mut book = Book::new(&[(99, 1), (98, 1)], &[(101, 1)]);
+    // point the root past the allocated nodes
+    let root_offset = 5 + 8 + 20;
+    book.data[1][root_offset..root_offset + 4].copy_from_slice(&7u32.to_le_bytes());
+    let program_id = book.program_id;
+    let accounts = book.accounts();
+    let reader = SerumBookReader::load(&program_id, &accounts[0], &accounts[1], &accounts[2]).unwrap();
+    assert_eq!(reader.best_bid(), Err(ArbitrageError::InvalidOrderBook.into()));
+}
+
+#[test]
+fn extreme_prices_report_overflow() {
+    let mut book = Book::new(&[(u64::MAX, 1)], &[(u64::MAX, u64::MAX)]);
+    let program_id = book.program_id;
+    let accounts = book.accounts();
+    let reader = SerumBookReader::load(&program_id, &accounts[0], &accounts[1], &accounts[2]).unwrap();
+    let best_ask = reader.best_ask().unwrap();
+    assert_eq!(reader.quote_amount(&best_ask), Err(ArbitrageError::MathOverflow.into()));
+    assert_eq!(reader.normalized_price(best_ask.price, 1_000_000_000), Err(ArbitrageError::MathOverflow.into()));
+    assert_eq!(reader.asks_depth(u64::MAX, 1_000_000_000), Err(ArbitrageError::MathOverflow.into()));
+    // a single lot at the top price still fits
+    assert_eq!(reader.normalized_price(u64::MAX, COIN_LOT_SIZE / PC_LOT_SIZE), Ok(u64::MAX));
+}
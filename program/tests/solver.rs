@@ -0,0 +1,87 @@
+use solana_arbitrage::{
+    quote::{constant_product_quote, PoolFees},
+    solver::{optimal_input, round_trip, SOLVER_MAX_ITERATIONS},
+    stable_quote::stable_swap_output,
+};
+
+const ORCA_FEES: PoolFees = PoolFees {
+    trade_fee_numerator: 25,
+    trade_fee_denominator: 10000,
+    owner_trade_fee_numerator: 5,
+    owner_trade_fee_denominator: 10000,
+};
+
+fn constant_product(reserve_in: u64, reserve_out: u64) -> impl Fn(u64) -> Option<u64> {
+    move |amount_in| constant_product_quote(amount_in, reserve_in, reserve_out, &ORCA_FEES).map(|quote| quote.amount_out)
+}
+
+fn stable_swap(amp: u64, reserve_in: u64, reserve_out: u64) -> impl Fn(u64) -> Option<u64> {
+    let balances = [reserve_in as u128, reserve_out as u128];
+    move |amount_in| {
+        let amount_out = stable_swap_output(amp, &balances, 0, 1, amount_in as u128)? as u64;
+        // saber's 0.3% from the output
+        Some(amount_out - amount_out * 30 / 10000).filter(|amount_out| *amount_out > 0)
+    }
+}
+
+/// best profit of every input in `amounts`
+fn brute_force<F, G>(first_leg: F, second_leg: G, amounts: impl Iterator<Item = u64>) -> (u64, u64)
+where
+    F: Fn(u64) -> Option<u64>,
+    G: Fn(u64) -> Option<u64>,
+{
+    amounts
+        .filter_map(|amount_in| {
+            let amount_out = round_trip(&first_leg, &second_leg, amount_in)?;
+            amount_out.checked_sub(amount_in).map(|profit| (profit, amount_in))
+        })
+        .max()
+        .unwrap()
+}
+
+#[test]
+fn constant_product_cycle_matches_brute_force() {
+    // ust is 10% cheaper in the first pool
+    let first_leg = constant_product(1_000_000, 1_100_000);
+    let second_leg = constant_product(1_000_000, 1_000_000);
+    let trade = optimal_input(&first_leg, &second_leg, 200_000, SOLVER_MAX_ITERATIONS).unwrap();
+    let (best_profit, best_amount_in) = brute_force(&first_leg, &second_leg, 1..=200_000);
+
+    assert_eq!(round_trip(&first_leg, &second_leg, trade.amount_in), Some(trade.amount_out));
+    assert_eq!(trade.profit, trade.amount_out - trade.amount_in);
+    assert!(trade.profit * 100 >= best_profit * 99, "{:?} vs {}", trade, best_profit);
+    assert!((trade.amount_in as i64 - best_amount_in as i64).abs() * 10 < best_amount_in as i64);
+}
+
+#[test]
+fn stable_swap_and_constant_product_cycle() {
+    let first_leg = stable_swap(10, 1_000_000_000_000, 1_100_000_000_000);
+    let second_leg = constant_product(1_000_000_000_000, 1_000_000_000_000);
+    let trade = optimal_input(&first_leg, &second_leg, 500_000_000_000, SOLVER_MAX_ITERATIONS).unwrap();
+    let (best_profit, _) = brute_force(&first_leg, &second_leg, (1..=50_000).map(|k| k * 10_000_000));
+    assert!(trade.profit * 100 >= best_profit * 99, "{:?} vs {}", trade, best_profit);
+}
+
+#[test]
+fn cap_below_the_optimum() {
+    let first_leg = constant_product(1_000_000, 1_100_000);
+    let second_leg = constant_product(1_000_000, 1_000_000);
+    // profit still grows at 5_000, so the cap is the best input
+    let trade = optimal_input(&first_leg, &second_leg, 5_000, SOLVER_MAX_ITERATIONS).unwrap();
+    let (best_profit, _) = brute_force(&first_leg, &second_leg, 1..=5_000);
+    assert!(trade.amount_in <= 5_000);
+    assert!(trade.profit * 100 >= best_profit * 99, "{:?} vs {}", trade, best_profit);
+}
+
+#[test]
+fn no_profitable_input() {
+    let first_leg = constant_product(1_000_000, 1_000_000);
+    let second_leg = constant_product(1_000_000, 1_000_000);
+    assert_eq!(optimal_input(&first_leg, &second_leg, 200_000, SOLVER_MAX_ITERATIONS), None);
+    assert_eq!(optimal_input(&first_leg, &second_leg, 0, SOLVER_MAX_ITERATIONS), None);
+    for max_amount_in in 1..5 {
+        assert_eq!(optimal_input(&first_leg, &second_leg, max_amount_in, SOLVER_MAX_ITERATIONS), None);
+    }
+    // a leg that cannot fill anything
+    assert_eq!(optimal_input(|_| None, &second_leg, 200_000, SOLVER_MAX_ITERATIONS), None);
+}
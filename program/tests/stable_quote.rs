@@ -0,0 +1,272 @@
+#![cfg(feature = "no-entrypoint")]
+
+use solana_arbitrage::{
+    error::ArbitrageError,
+    math::U256,
+    stable_quote::{
+        compute_amp_factor, compute_d, stable_swap_output, MercurialPool, SaberPool, MERCURIAL_FEE_DENOMINATOR,
+    },
+};
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+use spl_token::state::{Account as TokenAccount, AccountState};
+use stable_swap_client::{
+    fees::Fees,
+    state::{SwapInfo, SwapTokenInfo},
+};
+
+const RESERVE: u128 = 1_000_000_000_000;
+
+#[test]
+fn amp_factor_ramps_linearly() {
+    // ramp up and down over 100 seconds
+    assert_eq!(compute_amp_factor(100, 200, 1_000, 1_100, 1_050), Some(150));
+    assert_eq!(compute_amp_factor(200, 100, 1_000, 1_100, 1_025), Some(175));
+    assert_eq!(compute_amp_factor(100, 200, 1_000, 1_100, 999), Some(100));
+    assert_eq!(compute_amp_factor(100, 200, 1_000, 1_100, 1_100), Some(200));
+    // no ramp scheduled
+    assert_eq!(compute_amp_factor(100, 100, 0, 0, 1_650_000_000), Some(100));
+}
+
+#[test]
+fn balanced_pool_invariant_is_the_sum() {
+    for amp in [1, 10, 100, 1_000] {
+        assert_eq!(compute_d(amp, &[RESERVE, RESERVE]), Some(U256::from(2 * RESERVE)));
+        assert_eq!(compute_d(amp, &[RESERVE, RESERVE, RESERVE, RESERVE]), Some(U256::from(4 * RESERVE)));
+    }
+    assert_eq!(compute_d(100, &[0, 0]), Some(U256::zero()));
+    // native u64 balances scaled by 10^12 do not overflow the intermediates
+    let scaled = u64::MAX as u128 * 1_000_000_000_000;
+    assert!(compute_d(100, &[scaled, scaled]).is_some());
+}
+
+#[test]
+fn swap_output() {
+    let imbalanced = [RESERVE, 1_100_000_000_000];
+    // reference values of curve's get_D and get_y
+    assert_eq!(stable_swap_output(1, &imbalanced, 0, 1, 1_000_000_000), Some(1_048_338_118));
+    assert_eq!(stable_swap_output(10, &imbalanced, 0, 1, 1_000_000_000), Some(1_008_640_603));
+    assert_eq!(stable_swap_output(100, &imbalanced, 0, 1, 1_000_000_000), Some(1_000_938_049));
+
+    // a higher amplification keeps the price closer to one
+    let mut previous = u128::MAX;
+    for amp in [1, 10, 100, 1_000] {
+        let amount_out = stable_swap_output(amp, &imbalanced, 0, 1, 1_000_000_000).unwrap();
+        assert!(amount_out > 1_000_000_000 && amount_out < previous);
+        previous = amount_out;
+        // the scarce side pays less than it takes
+        assert!(stable_swap_output(amp, &imbalanced, 1, 0, 1_000_000_000).unwrap() < 1_000_000_000);
+    }
+
+    // same index or an index out of range
+    assert_eq!(stable_swap_output(100, &imbalanced, 0, 0, 1_000), None);
+    assert_eq!(stable_swap_output(100, &imbalanced, 0, 2, 1_000), None);
+}
+
+struct PoolAccounts {
+    program_id: Pubkey,
+    keys: Vec<Pubkey>,
+    data: Vec<Vec<u8>>,
+    lamports: Vec<u64>,
+    owners: Vec<Pubkey>,
+}
+
+impl PoolAccounts {
+    /// the pool state followed by one vault per `(mint, amount)`
+    fn new(state: impl FnOnce(&[Pubkey]) -> Vec<u8>, program_id: Pubkey, vaults: &[(Pubkey, u64)]) -> Self {
+        let keys: Vec<Pubkey> = (0..=vaults.len()).map(|_| Pubkey::new_unique()).collect();
+        let mut data = vec![state(&keys[1..])];
+        for (mint, amount) in vaults {
+            let mut vault = vec![0u8; TokenAccount::LEN];
+            TokenAccount::pack(
+                TokenAccount {
+                    mint: *mint,
+                    owner: Pubkey::new_unique(),
+                    amount: *amount,
+                    state: AccountState::Initialized,
+                    ..TokenAccount::default()
+                },
+                &mut vault,
+            )
+            .unwrap();
+            data.push(vault);
+        }
+        let mut owners = vec![program_id];
+        owners.resize(keys.len(), spl_token::id());
+        PoolAccounts {
+            program_id: program_id,
+            lamports: vec![0; keys.len()],
+            keys: keys,
+            data: data,
+            owners: owners,
+        }
+    }
+
+    fn accounts(&mut self) -> Vec<AccountInfo> {
+        self.keys
+            .iter()
+            .zip(self.data.iter_mut())
+            .zip(self.lamports.iter_mut())
+            .zip(self.owners.iter())
+            .map(|(((key, data), lamports), owner)| AccountInfo::new(key, false, false, lamports, data, owner, false, 0))
+            .collect()
+    }
+}
+
+fn saber_accounts(mints: [Pubkey; 2], reserves: [u64; 2], is_paused: bool) -> PoolAccounts {
+    let state = |vaults: &[Pubkey]| {
+        let token = |index: usize| SwapTokenInfo {
+            reserves: vaults[index],
+            mint: mints[index],
+            admin_fees: Pubkey::new_unique(),
+            index: index as u8,
+        };
+        let swap_info = SwapInfo {
+            is_initialized: true,
+            is_paused: is_paused,
+            nonce: 255,
+            initial_amp_factor: 100,
+            target_amp_factor: 200,
+            start_ramp_ts: 1_000,
+            stop_ramp_ts: 1_100,
+            future_admin_deadline: 0,
+            future_admin_key: Pubkey::default(),
+            admin_key: Pubkey::new_unique(),
+            token_a: token(0),
+            token_b: token(1),
+            pool_mint: Pubkey::new_unique(),
+            fees: Fees {
+                admin_trade_fee_numerator: 0,
+                admin_trade_fee_denominator: 1,
+                admin_withdraw_fee_numerator: 0,
+                admin_withdraw_fee_denominator: 1,
+                trade_fee_numerator: 4,
+                trade_fee_denominator: 10_000,
+                withdraw_fee_numerator: 0,
+                withdraw_fee_denominator: 1,
+            },
+        };
+        let mut data = vec![0u8; SwapInfo::LEN];
+        swap_info.pack_into_slice(&mut data);
+        data
+    };
+    PoolAccounts::new(state, Pubkey::new_unique(), &[(mints[0], reserves[0]), (mints[1], reserves[1])])
+}
+
+#[test]
+fn saber_pool_load_and_quote() {
+    let mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let mut pool_accounts = saber_accounts(mints, [1_000_000_000_000, 1_100_000_000_000], false);
+    let program_id = pool_accounts.program_id;
+    let accounts = pool_accounts.accounts();
+
+    let pool = SaberPool::load(&program_id, &accounts[0], &accounts[1], &accounts[2], 1_050).unwrap();
+    assert_eq!(pool.amp_factor, 150);
+    assert_eq!((pool.mint_a, pool.mint_b), (mints[0], mints[1]));
+    assert_eq!((pool.reserve_a, pool.reserve_b), (1_000_000_000_000, 1_100_000_000_000));
+
+    let balances = [pool.reserve_a as u128, pool.reserve_b as u128];
+    let amount_out = stable_swap_output(150, &balances, 0, 1, 1_000_000).unwrap() as u64;
+    let quote = pool.quote(1_000_000, true).unwrap();
+    assert_eq!(quote.fee, amount_out * 4 / 10_000);
+    assert_eq!(quote.amount_out, amount_out - quote.fee);
+    assert!(pool.quote(1_000_000, false).unwrap().amount_out < quote.amount_out);
+
+    // the ramp is over
+    let pool = SaberPool::load(&program_id, &accounts[0], &accounts[1], &accounts[2], 2_000).unwrap();
+    assert_eq!(pool.amp_factor, 200);
+
+    // vaults swapped, foreign owner
+    assert_eq!(
+        SaberPool::load(&program_id, &accounts[0], &accounts[2], &accounts[1], 1_050),
+        Err(ArbitrageError::PoolAccountMismatch.into())
+    );
+    assert_eq!(
+        SaberPool::load(&Pubkey::new_unique(), &accounts[0], &accounts[1], &accounts[2], 1_050),
+        Err(ProgramError::from(ArbitrageError::InvalidOwner))
+    );
+}
+
+#[test]
+fn paused_saber_pool_quotes_nothing() {
+    let mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let mut pool_accounts = saber_accounts(mints, [1_000_000_000_000, 1_000_000_000_000], true);
+    let program_id = pool_accounts.program_id;
+    let accounts = pool_accounts.accounts();
+    let pool = SaberPool::load(&program_id, &accounts[0], &accounts[1], &accounts[2], 0).unwrap();
+    assert_eq!(pool.quote(1_000_000, true), None);
+}
+
+fn mercurial_state(amp: u64, fee_numerator: u64, multipliers: &[u64], vaults: &[Pubkey]) -> Vec<u8> {
+    let mut data = vec![0u8; 263];
+    data[1] = 1;
+    data[3..11].copy_from_slice(&amp.to_le_bytes());
+    data[11..19].copy_from_slice(&fee_numerator.to_le_bytes());
+    data[27..31].copy_from_slice(&(vaults.len() as u32).to_le_bytes());
+    data[31..39].copy_from_slice(&9u64.to_le_bytes());
+    for (k, (multiplier, vault)) in multipliers.iter().zip(vaults.iter()).enumerate() {
+        data[39 + k * 8..47 + k * 8].copy_from_slice(&multiplier.to_le_bytes());
+        data[71 + k * 32..103 + k * 32].copy_from_slice(vault.as_ref());
+    }
+    data
+}
+
+#[test]
+fn mercurial_pool_scales_by_precision() {
+    // a 6 decimal and a 9 decimal token, both worth one
+    let mints = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+    let fee_numerator = 4 * MERCURIAL_FEE_DENOMINATOR / 10_000;
+    let mut pool_accounts = PoolAccounts::new(
+        |vaults| mercurial_state(100, fee_numerator, &[1_000, 1, 1_000], vaults),
+        Pubkey::new_unique(),
+        &[(mints[0], 1_000_000_000_000), (mints[1], 1_000_000_000_000_000), (mints[2], 1_000_000_000_000)],
+    );
+    let program_id = pool_accounts.program_id;
+    let accounts = pool_accounts.accounts();
+    let swap_accs: Vec<&AccountInfo> = accounts[1..].iter().collect();
+
+    let pool = MercurialPool::load(&program_id, &accounts[0], &swap_accs).unwrap();
+    assert_eq!(pool.amp, 100);
+    assert_eq!(pool.precision_multipliers, vec![1_000, 1, 1_000]);
+    assert_eq!(pool.index_of(&mints[1]), Some(1));
+    assert_eq!(pool.index_of(&Pubkey::new_unique()), None);
+
+    // one 6 decimal token buys about 10^9 units of the 9 decimal token
+    let quote = pool.quote(1_000_000, 0, 1).unwrap();
+    assert_eq!(quote.fee + quote.amount_out, 999_999_990);
+    assert_eq!(quote.fee, 999_999_990 * 4 / 10_000);
+    // and back
+    let quote = pool.quote(1_000_000_000, 1, 2).unwrap();
+    assert!(quote.amount_out < 1_000_000 && quote.amount_out > 999_000);
+
+    // vaults out of order or missing one
+    let reordered = [&accounts[2], &accounts[1], &accounts[3]];
+    assert_eq!(
+        MercurialPool::load(&program_id, &accounts[0], &reordered),
+        Err(ArbitrageError::PoolAccountMismatch.into())
+    );
+    assert_eq!(
+        MercurialPool::load(&program_id, &accounts[0], &swap_accs[..2]),
+        Err(ArbitrageError::PoolAccountMismatch.into())
+    );
+}
+
+#[test]
+fn mercurial_pool_rejects_uninitialized_state() {
+    let mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let mut pool_accounts = PoolAccounts::new(
+        |vaults| {
+            let mut data = mercurial_state(100, 0, &[1, 1], vaults);
+            data[1] = 0;
+            data
+        },
+        Pubkey::new_unique(),
+        &[(mints[0], 1_000_000), (mints[1], 1_000_000)],
+    );
+    let program_id = pool_accounts.program_id;
+    let accounts = pool_accounts.accounts();
+    let swap_accs: Vec<&AccountInfo> = accounts[1..].iter().collect();
+    assert_eq!(
+        MercurialPool::load(&program_id, &accounts[0], &swap_accs),
+        Err(ArbitrageError::InvalidPoolState.into())
+    );
+}
@@ -0,0 +1,423 @@
+//! Two leg stable strategies against mock pools. The first venue of every
+//! pair prices ust 10% cheaper than the second, so running the pair forward
+//! earns usdc and running it reversed loses usdc.
+#![cfg(feature = "no-entrypoint")]
+
+mod common;
+
+use common::{instruction_error, Harness, MockPool, MOCK_SLIPPAGE, STRATEGY_PARAMS};
+use solana_arbitrage::{client::*, error::ArbitrageError, state::ExchangeState};
+use solana_program::{
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+};
+use solana_sdk::{signature::Signer, transaction::TransactionError};
+
+const RESERVE: u64 = 1_000_000_000_000;
+const CHEAP_UST_RESERVE: u64 = 1_100_000_000_000;
+const USER_USDC: u64 = 100_000_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Outcome {
+    Profit,
+    Loss,
+    /// the slot's threshold is above the reset state or the quote is below the
+    /// input, nothing is swapped
+    Skipped,
+}
+
+struct StrategyCase {
+    harness: Harness,
+    user: StableUserAccounts,
+    usdc: Pubkey,
+    ust: Pubkey,
+}
+
+fn strategy_case() -> StrategyCase {
+    let mut harness = Harness::new();
+    let usdc = harness.add_mint();
+    let ust = harness.add_mint();
+    let user = StableUserAccounts {
+        owner: harness.operator.pubkey(),
+        user_usdc: harness.add_user_account(&usdc, USER_USDC),
+        user_ust: harness.add_user_account(&ust, 0),
+    };
+    StrategyCase {
+        harness: harness,
+        user: user,
+        usdc: usdc,
+        ust: ust,
+    }
+}
+
+fn cheap_ust_pool(case: &mut StrategyCase, program: Pubkey) -> MockPool {
+    let (usdc, ust) = (case.usdc, case.ust);
+    case.harness.add_pool(&program, &[(usdc, RESERVE), (ust, CHEAP_UST_RESERVE)])
+}
+
+/// usdc is vault 0 except for whirlpools, where the strategies use ust as token a
+fn fair_pool(case: &mut StrategyCase, program: Pubkey, extra_tokens: usize) -> MockPool {
+    let (usdc, ust) = (case.usdc, case.ust);
+    let mut reserves = if program == case.harness.mocks.whirl {
+        vec![(ust, RESERVE), (usdc, RESERVE)]
+    } else {
+        vec![(usdc, RESERVE), (ust, RESERVE)]
+    };
+    for _ in 0..extra_tokens {
+        let mint = case.harness.add_mint();
+        reserves.push((mint, RESERVE));
+    }
+    case.harness.add_pool(&program, &reserves)
+}
+
+async fn run_strategy(case: StrategyCase, instruction: Instruction, outcome: Outcome) {
+    let user = case.user.clone();
+    let mut context = case.harness.start().await;
+    context.process(instruction).await.unwrap();
+
+    let usdc_after = context.token_balance(&user.user_usdc).await;
+    let state = context.exchange_state().await;
+    match outcome {
+        Outcome::Profit => {
+            assert!(usdc_after > USER_USDC);
+            assert_eq!(state.exchange_out, usdc_after - USER_USDC);
+            assert_eq!(state.total_profit, usdc_after - USER_USDC);
+            assert_eq!(state.total_lost, 0);
+            assert_eq!((state.successes, state.skips), (1, 0));
+        }
+        Outcome::Loss => {
+            assert!(usdc_after < USER_USDC);
+            assert_eq!(state.exchange_out, 0);
+            assert_eq!(state.total_profit, 0);
+            assert_eq!(state.total_lost, USER_USDC - usdc_after);
+            assert_eq!((state.successes, state.skips), (0, 0));
+        }
+        Outcome::Skipped => {
+            assert_eq!(usdc_after, USER_USDC);
+            assert_eq!(state.total_profit, 0);
+            assert_eq!(state.total_lost, 0);
+            assert_eq!((state.successes, state.skips), (0, 1));
+            // a skip leaves the last output, and with it the next threshold, alone
+            assert_ne!(state.exchange_out, 0);
+        }
+    }
+    // a first run, its amounts are the totals
+    assert_eq!((state.run_profit, state.run_lost), (state.total_profit, state.total_lost));
+    assert_eq!(state.attempts, 1);
+    assert!(state.last_slot > 0);
+    // the second leg sells everything the first one bought
+    assert_eq!(context.token_balance(&user.user_ust).await, 0);
+}
+
+#[tokio::test]
+async fn saber_mercurial() {
+    let slots = [
+        (3, 0, Outcome::Profit),
+        (3, 1, Outcome::Profit),
+        (2, 2, Outcome::Profit),
+        (2, 3, Outcome::Profit),
+        (4, 4, Outcome::Profit),
+        (4, 5, Outcome::Skipped),
+    ];
+    for (pool_size, slot, forward) in slots {
+        for reversed in [false, true] {
+            let mut case = strategy_case();
+            let program = case.harness.mocks.saber;
+            let saber = cheap_ust_pool(&mut case, program);
+            let program = case.harness.mocks.mercurial;
+            let mercurial = fair_pool(&mut case, program, pool_size - 2);
+            let builder = SaberMercurialExchange {
+                exchange: case.harness.exchange_for(slot),
+                saber: saber.saber(),
+                mercurial: mercurial.mercurial(),
+                user: case.user.clone(),
+                slot: slot,
+                reversed: reversed,
+                flag: 0,
+                begin_run: false,
+                max_amount_in: 0,
+                min_amounts_out: [0, 0],
+            };
+            let instruction = builder.instruction(&case.harness.program_id).unwrap();
+            // both legs are quoted first, so the losing direction never swaps
+            let outcome = match (forward, reversed) {
+                (Outcome::Profit, true) => Outcome::Skipped,
+                (outcome, _) => outcome,
+            };
+            run_strategy(case, instruction, outcome).await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn saber_mercurial_solver() {
+    // the solver ignores the slot thresholds, including slot 5's
+    for (pool_size, slot) in [(3, 0), (3, 1), (2, 2), (2, 3), (4, 4), (4, 5)] {
+        for reversed in [false, true] {
+            let mut case = strategy_case();
+            let program = case.harness.mocks.saber;
+            let saber = cheap_ust_pool(&mut case, program);
+            let program = case.harness.mocks.mercurial;
+            let mercurial = fair_pool(&mut case, program, pool_size - 2);
+            let builder = SaberMercurialExchange {
+                exchange: case.harness.exchange_for(slot),
+                saber: saber.saber(),
+                mercurial: mercurial.mercurial(),
+                user: case.user.clone(),
+                slot: slot,
+                reversed: reversed,
+                flag: 0,
+                begin_run: false,
+                max_amount_in: USER_USDC,
+                min_amounts_out: [0, 0],
+            };
+            let instruction = builder.instruction(&case.harness.program_id).unwrap();
+            // no input makes the losing direction profitable
+            let outcome = if reversed { Outcome::Skipped } else { Outcome::Profit };
+            run_strategy(case, instruction, outcome).await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn strategy_params_drive_the_handlers() {
+    // slot 5's threshold skips it with the seeded params, a lower one trades
+    let mut case = strategy_case();
+    let (input_amount, _, expected_profit) = STRATEGY_PARAMS[5];
+    case.harness.set_strategy(5, true, input_amount, 0, expected_profit);
+    let program = case.harness.mocks.saber;
+    let saber = cheap_ust_pool(&mut case, program);
+    let program = case.harness.mocks.mercurial;
+    let mercurial = fair_pool(&mut case, program, 2);
+    let builder = SaberMercurialExchange {
+        exchange: case.harness.exchange_for(5),
+        saber: saber.saber(),
+        mercurial: mercurial.mercurial(),
+        user: case.user.clone(),
+        slot: 5,
+        reversed: false,
+        flag: 0,
+        begin_run: false,
+        max_amount_in: 0,
+        min_amounts_out: [0, 0],
+    };
+    let instruction = builder.instruction(&case.harness.program_id).unwrap();
+    run_strategy(case, instruction, Outcome::Profit).await;
+
+    // a disabled strategy refuses to run
+    let mut case = strategy_case();
+    let (input_amount, threshold, expected_profit) = STRATEGY_PARAMS[0];
+    case.harness.set_strategy(0, false, input_amount, threshold, expected_profit);
+    let program = case.harness.mocks.saber;
+    let saber = cheap_ust_pool(&mut case, program);
+    let program = case.harness.mocks.mercurial;
+    let mercurial = fair_pool(&mut case, program, 1);
+    let builder = SaberMercurialExchange {
+        exchange: case.harness.exchange_for(0),
+        saber: saber.saber(),
+        mercurial: mercurial.mercurial(),
+        user: case.user.clone(),
+        slot: 0,
+        reversed: false,
+        flag: 0,
+        begin_run: false,
+        max_amount_in: 0,
+        min_amounts_out: [0, 0],
+    };
+    let instruction = builder.instruction(&case.harness.program_id).unwrap();
+    let mut context = case.harness.start().await;
+    assert_eq!(
+        context.process(instruction).await,
+        Err(instruction_error(ArbitrageError::StrategyDisabled))
+    );
+}
+
+#[tokio::test]
+async fn saber_whirl() {
+    for slot in [6, 7] {
+        for reversed in [false, true] {
+            let mut case = strategy_case();
+            let program = case.harness.mocks.saber;
+            let saber = cheap_ust_pool(&mut case, program);
+            let program = case.harness.mocks.whirl;
+            let whirl = fair_pool(&mut case, program, 0);
+            let builder = SaberWhirlExchange {
+                exchange: case.harness.exchange_for(slot),
+                saber: saber.saber(),
+                whirl: whirl.whirl(),
+                user: case.user.clone(),
+                slot: slot,
+                reversed: reversed,
+                flag: 0,
+                begin_run: false,
+                min_amounts_out: [0, 0],
+            };
+            let instruction = builder.instruction(&case.harness.program_id).unwrap();
+            let outcome = if reversed { Outcome::Loss } else { Outcome::Profit };
+            run_strategy(case, instruction, outcome).await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn saber_crema() {
+    for slot in [8, 9] {
+        for reversed in [false, true] {
+            let mut case = strategy_case();
+            let program = case.harness.mocks.saber;
+            let saber = cheap_ust_pool(&mut case, program);
+            let program = case.harness.mocks.crema;
+            let crema = fair_pool(&mut case, program, 0);
+            let builder = SaberCremaExchange {
+                exchange: case.harness.exchange_for(slot),
+                saber: saber.saber(),
+                crema: crema.crema(),
+                user: case.user.clone(),
+                slot: slot,
+                reversed: reversed,
+                flag: 0,
+                begin_run: false,
+                min_amounts_out: [0, 0],
+            };
+            let instruction = builder.instruction(&case.harness.program_id).unwrap();
+            let outcome = if reversed { Outcome::Loss } else { Outcome::Profit };
+            run_strategy(case, instruction, outcome).await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn orca_raydium() {
+    for (slot, forward) in [(12, Outcome::Profit), (13, Outcome::Skipped)] {
+        for reversed in [false, true] {
+            let mut case = strategy_case();
+            let program = case.harness.mocks.token_swap;
+            let orca = cheap_ust_pool(&mut case, program);
+            let program = case.harness.mocks.raydium;
+            let raydium = fair_pool(&mut case, program, 0);
+            let builder = OrcaRaydiumExchange {
+                exchange: case.harness.exchange_for(slot),
+                orca: orca.orca(),
+                raydium: raydium.raydium(),
+                user: case.user.clone(),
+                slot: slot,
+                reversed: reversed,
+                flag: 0,
+                begin_run: false,
+                min_amounts_out: [0, 0],
+            };
+            let instruction = builder.instruction(&case.harness.program_id).unwrap();
+            let outcome = match (forward, reversed) {
+                (Outcome::Profit, true) => Outcome::Loss,
+                (outcome, _) => outcome,
+            };
+            run_strategy(case, instruction, outcome).await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn leg_minimums_reach_the_venues() {
+    // a minimum above what the mock pays fails the leg, orca first then raydium
+    for min_amounts_out in [[u64::MAX, 0], [0, u64::MAX]] {
+        let mut case = strategy_case();
+        let program = case.harness.mocks.token_swap;
+        let orca = cheap_ust_pool(&mut case, program);
+        let program = case.harness.mocks.raydium;
+        let raydium = fair_pool(&mut case, program, 0);
+        let builder = OrcaRaydiumExchange {
+            exchange: case.harness.exchange_for(12),
+            orca: orca.orca(),
+            raydium: raydium.raydium(),
+            user: case.user.clone(),
+            slot: 12,
+            reversed: false,
+            flag: 0,
+            begin_run: false,
+            min_amounts_out: min_amounts_out,
+        };
+        let instruction = builder.instruction(&case.harness.program_id).unwrap();
+        let user = case.user.clone();
+        let mut context = case.harness.start().await;
+        assert_eq!(
+            context.process(instruction).await,
+            Err(TransactionError::InstructionError(0, InstructionError::Custom(MOCK_SLIPPAGE)))
+        );
+        assert_eq!(context.token_balance(&user.user_usdc).await, USER_USDC);
+    }
+}
+
+#[tokio::test]
+async fn orca_whirl() {
+    for slot in [14, 15] {
+        for reversed in [false, true] {
+            let mut case = strategy_case();
+            let program = case.harness.mocks.token_swap;
+            let orca = cheap_ust_pool(&mut case, program);
+            let program = case.harness.mocks.whirl;
+            let whirl = fair_pool(&mut case, program, 0);
+            let builder = OrcaWhirlExchange {
+                exchange: case.harness.exchange_for(slot),
+                orca: orca.orca(),
+                whirl: whirl.whirl(),
+                user: case.user.clone(),
+                slot: slot,
+                reversed: reversed,
+                flag: 0,
+                begin_run: false,
+                min_amounts_out: [0, 0],
+            };
+            let instruction = builder.instruction(&case.harness.program_id).unwrap();
+            let outcome = if reversed { Outcome::Loss } else { Outcome::Profit };
+            run_strategy(case, instruction, outcome).await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn begin_run_trades_after_a_losing_run() {
+    // the loss left `exchange_out` below the threshold and the lifetime totals
+    // under water; a new run reseeds the former and the flagged check only
+    // compares the run, while only `ResetExchangeState` clears the totals
+    let mut case = strategy_case();
+    let (input_amount, _, _) = STRATEGY_PARAMS[6];
+    case.harness.exchange_state = ExchangeState {
+        strategy_id: 6,
+        input_amount: input_amount,
+        exchange_out: 0,
+        total_profit: 5,
+        total_lost: USER_USDC,
+        run_profit: 5,
+        run_lost: USER_USDC,
+        attempts: 2,
+        successes: 1,
+        skips: 1,
+        ..case.harness.exchange_state
+    };
+    let program = case.harness.mocks.saber;
+    let saber = cheap_ust_pool(&mut case, program);
+    let program = case.harness.mocks.whirl;
+    let whirl = fair_pool(&mut case, program, 0);
+    let builder = SaberWhirlExchange {
+        exchange: case.harness.exchange,
+        saber: saber.saber(),
+        whirl: whirl.whirl(),
+        user: case.user.clone(),
+        slot: 6,
+        reversed: false,
+        flag: 100,
+        begin_run: true,
+        min_amounts_out: [0, 0],
+    };
+    let instruction = builder.instruction(&case.harness.program_id).unwrap();
+    let user = case.user.clone();
+    let mut context = case.harness.start().await;
+    context.process(instruction).await.unwrap();
+
+    let profit = context.token_balance(&user.user_usdc).await - USER_USDC;
+    assert!(profit > 0);
+    let state = context.exchange_state().await;
+    assert_eq!((state.run_profit, state.run_lost), (profit, 0));
+    assert_eq!((state.total_profit, state.total_lost), (5 + profit, USER_USDC));
+    assert_eq!((state.attempts, state.successes, state.skips), (3, 2, 1));
+}
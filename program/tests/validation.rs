@@ -0,0 +1,230 @@
+#![cfg(feature = "no-entrypoint")]
+
+mod common;
+
+use common::{instruction_error, Harness};
+use solana_arbitrage::{client::*, error::ArbitrageError, state::ExchangeState, validation};
+use solana_program::{
+    account_info::AccountInfo, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey, rent::Rent, sysvar,
+};
+use solana_sdk::{account::Account, signature::Signer};
+use spl_token::state::{Account as TokenAccount, AccountState};
+
+const RESERVE: u64 = 1_000_000_000_000;
+const CHEAP_UST_RESERVE: u64 = 1_100_000_000_000;
+const USER_USDC: u64 = 100_000_000_000;
+
+fn token_account_data(mint: &Pubkey, owner: &Pubkey) -> Vec<u8> {
+    let mut data = vec![0u8; TokenAccount::LEN];
+    TokenAccount::pack(
+        TokenAccount {
+            mint: *mint,
+            owner: *owner,
+            state: AccountState::Initialized,
+            ..TokenAccount::default()
+        },
+        &mut data,
+    )
+    .unwrap();
+    data
+}
+
+#[test]
+fn programs_and_sysvars() {
+    let (mut lamports, mut data) = (0, vec![]);
+    let owner = Pubkey::default();
+    let saber_id = validation::saber::id();
+    let saber_acc = AccountInfo::new(&saber_id, false, false, &mut lamports, &mut data, &owner, true, 0);
+    assert_eq!(validation::program(&saber_acc, &validation::saber::id()), Ok(()));
+    assert_eq!(
+        validation::program(&saber_acc, &validation::mercurial::id()),
+        Err(ArbitrageError::InvalidProgramId.into())
+    );
+    assert_eq!(validation::token_program(&saber_acc), Err(ArbitrageError::InvalidProgramId.into()));
+
+    let (mut lamports, mut data) = (0, vec![]);
+    let clock_id = sysvar::clock::id();
+    let clock_acc = AccountInfo::new(&clock_id, false, false, &mut lamports, &mut data, &owner, false, 0);
+    assert_eq!(validation::clock(&clock_acc), Ok(()));
+    assert_eq!(validation::rent(&clock_acc), Err(ArbitrageError::InvalidSysvar.into()));
+    assert_eq!(validation::signer(&clock_acc), Err(ProgramError::MissingRequiredSignature));
+}
+
+#[test]
+fn program_state_accounts() {
+    let program_id = Pubkey::new_unique();
+    let state_key = Pubkey::new_unique();
+    let (mut lamports, mut data) = (0, vec![]);
+    let state_acc = AccountInfo::new(&state_key, false, true, &mut lamports, &mut data, &program_id, false, 0);
+    assert_eq!(validation::program_state(&state_acc, &program_id), Ok(()));
+    assert_eq!(
+        validation::program_state(&state_acc, &Pubkey::new_unique()),
+        Err(ArbitrageError::InvalidOwner.into())
+    );
+
+    let (mut lamports, mut data) = (0, vec![]);
+    let readonly_acc = AccountInfo::new(&state_key, false, false, &mut lamports, &mut data, &program_id, false, 0);
+    assert_eq!(
+        validation::program_state(&readonly_acc, &program_id),
+        Err(ArbitrageError::AccountNotWritable.into())
+    );
+}
+
+#[test]
+fn token_accounts_and_mints() {
+    let token_program = spl_token::id();
+    let (owner_key, token_key, vault_key) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let (usdc, ust) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mut owner_lamports, mut owner_data) = (0, vec![]);
+    let owner_acc = AccountInfo::new(&owner_key, true, false, &mut owner_lamports, &mut owner_data, &owner_key, false, 0);
+    let (mut token_lamports, mut token_bytes) = (0, token_account_data(&usdc, &owner_key));
+    let token_acc = AccountInfo::new(&token_key, false, true, &mut token_lamports, &mut token_bytes, &token_program, false, 0);
+    let (mut vault_lamports, mut vault_bytes) = (0, token_account_data(&ust, &Pubkey::new_unique()));
+    let vault_acc = AccountInfo::new(&vault_key, false, true, &mut vault_lamports, &mut vault_bytes, &token_program, false, 0);
+
+    assert_eq!(validation::user_token_account(&token_acc, &owner_acc).map(|token| token.mint), Ok(usdc));
+    assert_eq!(
+        validation::user_token_account(&vault_acc, &owner_acc).err(),
+        Some(ArbitrageError::TokenOwnerMismatch.into())
+    );
+    // the signer is not a token account
+    assert_eq!(
+        validation::user_token_account(&owner_acc, &owner_acc).err(),
+        Some(ArbitrageError::InvalidTokenAccount.into())
+    );
+
+    assert_eq!(validation::vault_mints(&[usdc, ust], &[&token_acc, &vault_acc]), Ok(()));
+    assert_eq!(validation::vault_mints(&[ust], &[&vault_acc]), Ok(()));
+    assert_eq!(
+        validation::vault_mints(&[usdc, ust], &[&vault_acc]),
+        Err(ArbitrageError::MintMismatch.into())
+    );
+}
+
+/// a profitable saber/whirl cycle, `tamper` edits the accounts before it is sent
+async fn saber_whirl_with(tamper: impl FnOnce(&mut Harness, &mut SaberWhirlExchange)) -> Result<(), ArbitrageError> {
+    let mut harness = Harness::new();
+    let usdc = harness.add_mint();
+    let ust = harness.add_mint();
+    let user = StableUserAccounts {
+        owner: harness.operator.pubkey(),
+        user_usdc: harness.add_user_account(&usdc, USER_USDC),
+        user_ust: harness.add_user_account(&ust, 0),
+    };
+    let program = harness.mocks.saber;
+    let saber = harness.add_pool(&program, &[(usdc, RESERVE), (ust, CHEAP_UST_RESERVE)]);
+    let program = harness.mocks.whirl;
+    let whirl = harness.add_pool(&program, &[(ust, RESERVE), (usdc, RESERVE)]);
+    let mut builder = SaberWhirlExchange {
+        exchange: harness.exchange_for(6),
+        saber: saber.saber(),
+        whirl: whirl.whirl(),
+        user: user,
+        slot: 6,
+        reversed: false,
+        flag: 0,
+        begin_run: false,
+        min_amounts_out: [0, 0],
+    };
+    tamper(&mut harness, &mut builder);
+    let instruction = builder.instruction(&harness.program_id).unwrap();
+    let mut context = harness.start().await;
+    match context.process(instruction).await {
+        Ok(()) => Ok(()),
+        Err(err) => {
+            for expected in [
+                ArbitrageError::InvalidProgramId,
+                ArbitrageError::TokenOwnerMismatch,
+                ArbitrageError::MintMismatch,
+                ArbitrageError::InvalidOwner,
+            ] {
+                if err == instruction_error(expected.clone()) {
+                    return Err(expected);
+                }
+            }
+            panic!("unexpected error: {:?}", err);
+        }
+    }
+}
+
+#[tokio::test]
+async fn strategies_validate_their_accounts() {
+    assert!(saber_whirl_with(|_, _| {}).await.is_ok());
+
+    let result = saber_whirl_with(|_, builder| builder.saber.program = Pubkey::new_unique()).await;
+    assert!(matches!(result, Err(ArbitrageError::InvalidProgramId)));
+
+    // a usdc account someone else holds
+    let result = saber_whirl_with(|harness, builder| {
+        let usdc = harness.add_mint();
+        builder.user.user_usdc = harness.add_token_account(&usdc, &Pubkey::new_unique(), USER_USDC);
+    })
+    .await;
+    assert!(matches!(result, Err(ArbitrageError::TokenOwnerMismatch)));
+
+    // the operator's account of a mint neither pool trades
+    let result = saber_whirl_with(|harness, builder| {
+        let usdc = harness.add_mint();
+        builder.user.user_usdc = harness.add_user_account(&usdc, USER_USDC);
+    })
+    .await;
+    assert!(matches!(result, Err(ArbitrageError::MintMismatch)));
+}
+
+#[tokio::test]
+async fn strategies_check_the_exchange_account() {
+    // a copy of the state held by another program
+    let result = saber_whirl_with(|harness, builder| {
+        let mut data = vec![0; ExchangeState::LEN];
+        harness.exchange_state.pack_into_slice(&mut data);
+        builder.exchange = Pubkey::new_unique();
+        harness.program_test.add_account(
+            builder.exchange,
+            Account {
+                lamports: Rent::default().minimum_balance(ExchangeState::LEN),
+                data: data,
+                owner: Pubkey::new_unique(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    })
+    .await;
+    assert!(matches!(result, Err(ArbitrageError::InvalidOwner)));
+
+    // the state passed read-only
+    let mut harness = Harness::new();
+    let usdc = harness.add_mint();
+    let ust = harness.add_mint();
+    let program = harness.mocks.saber;
+    let saber = harness.add_pool(&program, &[(usdc, RESERVE), (ust, CHEAP_UST_RESERVE)]);
+    let program = harness.mocks.whirl;
+    let whirl = harness.add_pool(&program, &[(ust, RESERVE), (usdc, RESERVE)]);
+    let user = StableUserAccounts {
+        owner: harness.operator.pubkey(),
+        user_usdc: harness.add_user_account(&usdc, USER_USDC),
+        user_ust: harness.add_user_account(&ust, 0),
+    };
+    let exchange = harness.exchange_for(6);
+    let mut instruction = SaberWhirlExchange {
+        exchange: exchange,
+        saber: saber.saber(),
+        whirl: whirl.whirl(),
+        user: user,
+        slot: 6,
+        reversed: false,
+        flag: 0,
+        begin_run: false,
+        min_amounts_out: [0, 0],
+    }
+    .instruction(&harness.program_id)
+    .unwrap();
+    for meta in instruction.accounts.iter_mut().filter(|meta| meta.pubkey == exchange) {
+        meta.is_writable = false;
+    }
+    let mut context = harness.start().await;
+    assert_eq!(
+        context.process(instruction).await,
+        Err(instruction_error(ArbitrageError::AccountNotWritable))
+    );
+}
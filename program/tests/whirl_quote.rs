@@ -0,0 +1,174 @@
+use solana_arbitrage::{
+    error::ArbitrageError,
+    whirl_quote::{
+        compute_swap_step, sqrt_price_from_tick_index, tick_index_from_sqrt_price, TickArray, Whirlpool,
+        MAX_SQRT_PRICE, MAX_TICK_INDEX, MIN_SQRT_PRICE, MIN_TICK_INDEX, TICK_ARRAY_SIZE,
+    },
+};
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+const TICK_SPACING: u16 = 64;
+const FEE_RATE: u16 = 3000;
+const LIQUIDITY: u128 = 1_000_000_000_000;
+const TICKS_IN_ARRAY: i32 = TICK_ARRAY_SIZE * TICK_SPACING as i32;
+
+fn whirlpool_data(liquidity: u128, tick: i32) -> Vec<u8> {
+    let mut data = vec![0u8; 653];
+    data[0..8].copy_from_slice(&[63, 149, 209, 12, 225, 128, 99, 9]);
+    data[41..43].copy_from_slice(&TICK_SPACING.to_le_bytes());
+    data[45..47].copy_from_slice(&FEE_RATE.to_le_bytes());
+    data[49..65].copy_from_slice(&liquidity.to_le_bytes());
+    data[65..81].copy_from_slice(&sqrt_price_from_tick_index(tick).unwrap().to_le_bytes());
+    data[81..85].copy_from_slice(&tick.to_le_bytes());
+    data
+}
+
+/// `ticks` are `(tick_index, liquidity_net)` of the initialized ticks, those
+/// outside the array are left out
+fn tick_array_data(whirlpool: &Pubkey, start_tick_index: i32, ticks: &[(i32, i128)]) -> Vec<u8> {
+    let mut data = vec![0u8; 9988];
+    data[0..8].copy_from_slice(&[69, 97, 189, 190, 110, 7, 66, 187]);
+    data[8..12].copy_from_slice(&start_tick_index.to_le_bytes());
+    for (tick_index, liquidity_net) in ticks {
+        if *tick_index < start_tick_index || *tick_index >= start_tick_index + TICKS_IN_ARRAY {
+            continue;
+        }
+        let offset = 12 + ((tick_index - start_tick_index) / TICK_SPACING as i32) as usize * 113;
+        data[offset] = 1;
+        data[offset + 1..offset + 17].copy_from_slice(&liquidity_net.to_le_bytes());
+    }
+    data[9956..9988].copy_from_slice(whirlpool.as_ref());
+    data
+}
+
+/// a pool at tick 10 where half the liquidity ends at tick 0 and the rest at tick -640
+fn whirlpool(arrays: &[i32]) -> Whirlpool {
+    let key = Pubkey::new_unique();
+    let tick_arrays = arrays
+        .iter()
+        .map(|start_tick_index| {
+            let data = tick_array_data(&key, *start_tick_index, &[(0, LIQUIDITY as i128 / 2), (-640, LIQUIDITY as i128 / 2)]);
+            TickArray::unpack(&Pubkey::new_unique(), &data, &key).unwrap()
+        })
+        .collect();
+    Whirlpool::unpack(&whirlpool_data(LIQUIDITY, 10), tick_arrays).unwrap()
+}
+
+#[test]
+fn tick_math() {
+    assert_eq!(sqrt_price_from_tick_index(0), Some(1 << 64));
+    assert_eq!(sqrt_price_from_tick_index(MIN_TICK_INDEX), Some(MIN_SQRT_PRICE));
+    assert_eq!(sqrt_price_from_tick_index(MAX_TICK_INDEX), Some(MAX_SQRT_PRICE));
+    assert_eq!(sqrt_price_from_tick_index(MAX_TICK_INDEX + 1), None);
+    for tick in [MIN_TICK_INDEX, -100_000, -1, 0, 1, 64, 1_000, MAX_TICK_INDEX - 1] {
+        let sqrt_price = sqrt_price_from_tick_index(tick).unwrap();
+        assert!(sqrt_price < sqrt_price_from_tick_index(tick + 1).unwrap());
+        assert_eq!(tick_index_from_sqrt_price(sqrt_price), Some(tick));
+        assert_eq!(tick_index_from_sqrt_price(sqrt_price + 1), Some(tick));
+        if tick > MIN_TICK_INDEX {
+            assert_eq!(tick_index_from_sqrt_price(sqrt_price - 1), Some(tick - 1));
+        }
+    }
+}
+
+#[test]
+fn swap_within_one_range() {
+    let pool = whirlpool(&[0, -TICKS_IN_ARRAY]);
+    let sqrt_price = pool.sqrt_price;
+
+    // a to b stops short of tick 0
+    let step = compute_swap_step(1_000_000, FEE_RATE, LIQUIDITY, sqrt_price, sqrt_price_from_tick_index(0).unwrap(), true).unwrap();
+    let quote = pool.quote(1_000_000, true, MIN_SQRT_PRICE).unwrap();
+    assert_eq!(quote.amount_in, 1_000_000);
+    assert_eq!(quote.amount_out, step.amount_out);
+    assert_eq!(quote.fee, step.fee);
+    assert_eq!(quote.end_sqrt_price, step.next_sqrt_price);
+    assert_eq!(quote.end_tick_index, tick_index_from_sqrt_price(step.next_sqrt_price).unwrap());
+    assert_eq!(quote.tick_arrays, vec![pool.tick_arrays[0].key]);
+
+    // b to a searches above the current tick
+    let pool = whirlpool(&[0]);
+    let step = compute_swap_step(1_000_000, FEE_RATE, LIQUIDITY, sqrt_price, sqrt_price_from_tick_index(TICKS_IN_ARRAY - 1).unwrap(), false).unwrap();
+    let quote = pool.quote(1_000_000, false, MAX_SQRT_PRICE).unwrap();
+    assert_eq!(quote.amount_out, step.amount_out);
+    assert!(quote.end_sqrt_price > sqrt_price);
+}
+
+#[test]
+fn swap_across_ticks_and_arrays() {
+    let pool = whirlpool(&[0, -TICKS_IN_ARRAY, -2 * TICKS_IN_ARRAY]);
+    let tick_0 = sqrt_price_from_tick_index(0).unwrap();
+    let tick_640 = sqrt_price_from_tick_index(-640).unwrap();
+
+    // the whole range down to tick 0, then half the liquidity towards tick -640
+    let first = compute_swap_step(1_000_000_000, FEE_RATE, LIQUIDITY, pool.sqrt_price, tick_0, true).unwrap();
+    assert_eq!(first.next_sqrt_price, tick_0);
+    let remaining = 1_000_000_000 - first.amount_in - first.fee;
+    let second = compute_swap_step(remaining, FEE_RATE, LIQUIDITY / 2, tick_0, tick_640, true).unwrap();
+    assert!(second.next_sqrt_price > tick_640);
+
+    let quote = pool.quote(1_000_000_000, true, MIN_SQRT_PRICE).unwrap();
+    assert_eq!(quote.amount_in, 1_000_000_000);
+    assert_eq!(quote.amount_out, first.amount_out + second.amount_out);
+    assert_eq!(quote.fee, first.fee + second.fee);
+    assert_eq!(quote.end_sqrt_price, second.next_sqrt_price);
+    // tick 0 is the first tick of the first array, the swap ends in the second
+    assert_eq!(quote.tick_arrays, vec![pool.tick_arrays[0].key, pool.tick_arrays[1].key]);
+    assert!(quote.end_tick_index < 0 && quote.end_tick_index >= -640);
+}
+
+#[test]
+fn price_limit_stops_the_swap() {
+    let pool = whirlpool(&[0, -TICKS_IN_ARRAY]);
+    let limit = sqrt_price_from_tick_index(-100).unwrap();
+    let quote = pool.quote(100_000_000_000, true, limit).unwrap();
+    assert_eq!(quote.end_sqrt_price, limit);
+    assert!(quote.amount_in < 100_000_000_000);
+    // a limit on the wrong side of the price
+    assert_eq!(pool.quote(1_000, true, MAX_SQRT_PRICE), None);
+}
+
+#[test]
+fn needs_more_tick_arrays() {
+    // past tick -640 the pool is empty and the price runs to the edge of the second array
+    let pool = whirlpool(&[0, -TICKS_IN_ARRAY]);
+    assert_eq!(pool.quote(100_000_000_000, true, MIN_SQRT_PRICE), None);
+    // the first array must hold the current tick
+    let pool = whirlpool(&[-TICKS_IN_ARRAY]);
+    assert_eq!(pool.quote(1_000, true, MIN_SQRT_PRICE), None);
+}
+
+#[test]
+fn load_checks_owners_and_pool() {
+    let program_id = Pubkey::new_unique();
+    let keys = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+    let mut data = [
+        whirlpool_data(LIQUIDITY, 10),
+        tick_array_data(&keys[0], 0, &[]),
+        tick_array_data(&Pubkey::new_unique(), -TICKS_IN_ARRAY, &[]),
+    ];
+    let mut lamports = [0u64; 3];
+    let accounts: Vec<AccountInfo> = keys
+        .iter()
+        .zip(data.iter_mut())
+        .zip(lamports.iter_mut())
+        .map(|((key, data), lamports)| AccountInfo::new(key, false, false, lamports, data, &program_id, false, 0))
+        .collect();
+
+    let pool = Whirlpool::load(&program_id, &accounts[0], &[&accounts[1]]).unwrap();
+    assert_eq!(pool.tick_spacing, TICK_SPACING);
+    assert_eq!(pool.fee_rate, FEE_RATE);
+    assert_eq!(pool.liquidity, LIQUIDITY);
+    assert_eq!(pool.tick_current_index, 10);
+    assert_eq!(pool.tick_arrays[0].start_tick_index, 0);
+
+    // the second array belongs to another pool
+    assert_eq!(
+        Whirlpool::load(&program_id, &accounts[0], &[&accounts[1], &accounts[2]]),
+        Err(ArbitrageError::InvalidPoolState.into())
+    );
+    assert_eq!(
+        Whirlpool::load(&Pubkey::new_unique(), &accounts[0], &[&accounts[1]]),
+        Err(ProgramError::from(ArbitrageError::InvalidOwner))
+    );
+}